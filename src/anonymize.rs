@@ -0,0 +1,226 @@
+use crate::{diff::file_diffs, Cli};
+
+/// The prefixes of the lines of a diff whose paths start with `a/` or `b/`
+const HEADER_PREFIXES: [&str; 3] = ["diff --git ", "--- ", "+++ "];
+
+/// An original text and the placeholder which replaces it
+struct Replacement {
+    original: String,
+    placeholder: String,
+    /// Whether the original is a directory, which is only replaced as whole path components
+    directory: bool,
+}
+
+/// Replaces the directories of the changed files and internal names with placeholders like
+/// `dir-1` or `name-1` before the prompt leaves the machine, and restores them in the response.
+/// The mapping is only kept in memory.
+#[derive(Default)]
+pub(crate) struct Anonymizer {
+    /// The replacements in the order of their placeholders
+    replacements: Vec<Replacement>,
+}
+
+/// Whether the character can be part of a path component, e.g. of `my-app.v2`
+fn is_component_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '@' | '+' | '~')
+}
+
+/// Whether the text between `start` and `end` consists of whole path components, i.e. it's
+/// bounded by `/`, whitespace or the like, and isn't the `a/` or `b/` prefix of a path in the
+/// header of a diff
+fn is_whole_components(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    if before.is_some_and(is_component_char) || text[end..].starts_with(is_component_char) {
+        return false;
+    }
+    let line = &text[text[..start].rfind('\n').map_or(0, |index| index + 1)..];
+    let is_prefix = before.is_none_or(char::is_whitespace)
+        && HEADER_PREFIXES
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+        && (text[start..].starts_with("a/") || text[start..].starts_with("b/"));
+    !is_prefix
+}
+
+/// Whether the placeholder between `start` and `end` is whole, e.g. `dir-1` isn't the start of
+/// `dir-10`
+fn is_whole_placeholder(text: &str, _start: usize, end: usize) -> bool {
+    !text[end..].starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Replaces the occurrences of `from` in the text which `is_whole` accepts with `to`
+fn replace_whole(
+    text: &str,
+    from: &str,
+    to: &str,
+    is_whole: impl Fn(&str, usize, usize) -> bool,
+) -> String {
+    let mut replaced = String::new();
+    let mut copied = 0;
+    let mut searched = 0;
+    while let Some(offset) = text[searched..].find(from) {
+        let start = searched + offset;
+        let end = start + from.len();
+        if is_whole(text, start, end) {
+            replaced.push_str(&text[copied..start]);
+            replaced.push_str(to);
+            copied = end;
+            searched = end;
+        } else {
+            searched = start + text[start..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    replaced.push_str(&text[copied..]);
+    replaced
+}
+
+impl Anonymizer {
+    /// Replaces the original texts with their placeholders, the longest texts first, so a
+    /// directory is replaced before its parent directory. Directories are only replaced as
+    /// whole path components, names wherever they occur, e.g. in identifiers.
+    pub(crate) fn anonymize(&self, text: &str) -> String {
+        let mut replacements = self.replacements.iter().collect::<Vec<_>>();
+        replacements.sort_by_key(|replacement| std::cmp::Reverse(replacement.original.len()));
+        replacements
+            .into_iter()
+            .fold(text.to_string(), |text, replacement| {
+                if replacement.directory {
+                    replace_whole(
+                        &text,
+                        &replacement.original,
+                        &replacement.placeholder,
+                        is_whole_components,
+                    )
+                } else {
+                    text.replace(&replacement.original, &replacement.placeholder)
+                }
+            })
+    }
+
+    /// Replaces the whole placeholders with their original texts, the longest placeholders
+    /// first
+    pub(crate) fn restore(&self, text: &str) -> String {
+        let mut replacements = self.replacements.iter().collect::<Vec<_>>();
+        replacements.sort_by_key(|replacement| std::cmp::Reverse(replacement.placeholder.len()));
+        replacements
+            .into_iter()
+            .fold(text.to_string(), |text, replacement| {
+                replace_whole(
+                    &text,
+                    &replacement.placeholder,
+                    &replacement.original,
+                    is_whole_placeholder,
+                )
+            })
+    }
+}
+
+impl Cli {
+    /// The anonymizer for the diff, which is the same for the same diff. Without anonymized
+    /// paths or names, nothing is replaced.
+    pub(crate) fn anonymizer(&self, diff: &str) -> Anonymizer {
+        let mut anonymizer = Anonymizer::default();
+        if self.args.anonymize_paths || self.config.anonymize_paths {
+            for file in file_diffs(diff) {
+                let Some((directory, _)) = file.path.rsplit_once('/') else {
+                    continue;
+                };
+                if !anonymizer
+                    .replacements
+                    .iter()
+                    .any(|replacement| replacement.original == directory)
+                {
+                    let placeholder = format!("dir-{}", anonymizer.replacements.len() + 1);
+                    anonymizer.replacements.push(Replacement {
+                        original: directory.to_string(),
+                        placeholder,
+                        directory: true,
+                    });
+                }
+            }
+        }
+        for (index, name) in self
+            .config
+            .anonymize_names
+            .iter()
+            .filter(|name| !name.is_empty())
+            .enumerate()
+        {
+            anonymizer.replacements.push(Replacement {
+                original: name.clone(),
+                placeholder: format!("name-{}", index + 1),
+                directory: false,
+            });
+        }
+        anonymizer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anonymizer(replacements: &[(&str, &str, bool)]) -> Anonymizer {
+        Anonymizer {
+            replacements: replacements
+                .iter()
+                .map(|&(original, placeholder, directory)| Replacement {
+                    original: original.to_string(),
+                    placeholder: placeholder.to_string(),
+                    directory,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn anonymizes_whole_path_components() {
+        let anonymizer = anonymizer(&[("src/app", "dir-1", true)]);
+        assert_eq!(
+            anonymizer.anonymize("diff --git a/src/app/x.rs b/src/app/x.rs\nuse src/application;"),
+            "diff --git a/dir-1/x.rs b/dir-1/x.rs\nuse src/application;"
+        );
+        assert_eq!(anonymizer.anonymize("lib/src/app"), "lib/dir-1");
+        assert_eq!(anonymizer.anonymize("mysrc/app"), "mysrc/app");
+    }
+
+    #[test]
+    fn anonymizes_the_longest_directory_first() {
+        let anonymizer = anonymizer(&[("src", "dir-1", true), ("src/app", "dir-2", true)]);
+        assert_eq!(
+            anonymizer.anonymize("src/app/x.rs src/y.rs"),
+            "dir-2/x.rs dir-1/y.rs"
+        );
+    }
+
+    #[test]
+    fn keeps_the_prefixes_of_the_diff_header() {
+        let anonymizer = anonymizer(&[("a", "dir-1", true)]);
+        assert_eq!(
+            anonymizer.anonymize("--- a/a/x.rs\n+++ b/a/x.rs\n a/y"),
+            "--- a/dir-1/x.rs\n+++ b/dir-1/x.rs\n dir-1/y"
+        );
+    }
+
+    #[test]
+    fn anonymizes_names_within_identifiers() {
+        let anonymizer = anonymizer(&[("acme", "name-1", false)]);
+        assert_eq!(anonymizer.anonymize("acme_client"), "name-1_client");
+    }
+
+    #[test]
+    fn restores_whole_placeholders() {
+        let anonymizer = anonymizer(&[("lib", "dir-1", true), ("src", "dir-10", true)]);
+        assert_eq!(
+            anonymizer.restore("Move dir-1/x.rs to dir-10."),
+            "Move lib/x.rs to src."
+        );
+    }
+
+    #[test]
+    fn restores_what_was_anonymized() {
+        let anonymizer = anonymizer(&[("src/app", "dir-1", true), ("acme", "name-1", false)]);
+        let text = "diff --git a/src/app/acme.rs b/src/app/acme.rs\n+acme_client()";
+        assert_eq!(anonymizer.restore(&anonymizer.anonymize(text)), text);
+    }
+}
@@ -1,9 +1,29 @@
-use clap::{command, Parser};
+use std::{ops::RangeInclusive, path::PathBuf};
+
+use clap::{Parser, Subcommand};
+
+use crate::{
+    budget::LargeDiff, conventions::Convention, history::HistoryFormat, model::ReasoningEffort,
+    privacy::Privacy, provider::ProviderKind, validate::Validation,
+};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 #[command(next_line_help = true)]
 pub(crate) struct Args {
+    #[command(subcommand)]
+    pub(crate) command: Option<Commands>,
+
+    /// Run as if commitgpt was started in the given path instead of the current directory, like
+    /// `git -C`
+    #[arg(short = 'C', value_name = "PATH", global = true)]
+    pub(crate) directory: Option<PathBuf>,
+
+    /// The profile of the configuration file which should be used, i.e. its `[profile.<name>]`
+    /// section
+    #[arg(long, global = true)]
+    pub(crate) profile: Option<String>,
+
     /// The amount of suggestions ChatGPT should generate
     #[arg(short, long, value_parser = 1..=10)]
     pub(crate) suggestions: Option<i64>,
@@ -16,13 +36,318 @@ pub(crate) struct Args {
     #[arg(short = 't', long, value_parser = 1..=128000)]
     pub(crate) max_tokens: Option<i64>,
 
-    /// The model which should be used for ChatGPT
+    /// The sampling temperature between 0 and 2, lower values give more deterministic messages
+    #[arg(long, value_parser = temperature)]
+    pub(crate) temperature: Option<f32>,
+
+    /// The nucleus sampling probability between 0 and 1
+    #[arg(long, value_parser = top_p)]
+    pub(crate) top_p: Option<f32>,
+
+    /// The penalty between -2 and 2 of tokens which already occur in the message
+    #[arg(long, value_parser = penalty, allow_negative_numbers = true)]
+    pub(crate) presence_penalty: Option<f32>,
+
+    /// The penalty between -2 and 2 of tokens by how often they already occur in the message
+    #[arg(long, value_parser = penalty, allow_negative_numbers = true)]
+    pub(crate) frequency_penalty: Option<f32>,
+
+    /// The seed of the sampling, so rerunning on the same diff reproduces the same suggestions
+    #[arg(long)]
+    pub(crate) seed: Option<u32>,
+
+    /// The backend which should be used to generate the commit messages
+    #[arg(long, value_enum)]
+    pub(crate) provider: Option<ProviderKind>,
+
+    /// The URL of the provider's API, e.g. the resource URL for Azure
+    #[arg(long)]
+    pub(crate) base_url: Option<String>,
+
+    /// The API version which should be used for Azure
+    #[arg(long)]
+    pub(crate) api_version: Option<String>,
+
+    /// The deployment which should be used for Azure, regardless of the model
+    #[arg(long)]
+    pub(crate) deployment: Option<String>,
+
+    /// The model which should be used, either a known alias (e.g. `4o`) or any model name
     #[arg(short, long)]
     pub(crate) model: Option<String>,
 
+    /// How much effort reasoning models like o1 or o3 should spend on reasoning
+    #[arg(long, value_enum)]
+    pub(crate) reasoning_effort: Option<ReasoningEffort>,
+
+    /// Stream the suggestions and render them while they arrive
+    #[arg(long)]
+    pub(crate) stream: bool,
+
+    /// Request the commit message as structured output and render it locally
+    #[arg(long)]
+    pub(crate) structured: bool,
+
+    /// Send the drafts back to the model to critique and improve them before they're shown
+    #[arg(long)]
+    pub(crate) refine: bool,
+
+    /// Order the suggestions best-first by their score, which is shown in the selection
+    #[arg(long)]
+    pub(crate) rank: bool,
+
+    /// Generate only a subject line and commit it without the selection and editor
+    #[arg(short = '1', long)]
+    pub(crate) oneline: bool,
+
+    /// Amend the last commit, improving its message with the staged changes
+    #[arg(long)]
+    pub(crate) amend: bool,
+
+    /// Stage and commit the changes of all tracked files, like `git commit -a`
+    #[arg(short, long)]
+    pub(crate) all: bool,
+
+    /// Add a `Co-authored-by` trailer, either as `Name <email>` or a part of the name or email
+    /// of a previous author or co-author
+    #[arg(long, value_name = "NAME")]
+    pub(crate) co_author: Vec<String>,
+
+    /// Add a `Signed-off-by` trailer with `user.name` and `user.email`, as required by the DCO
+    #[arg(long)]
+    pub(crate) signoff: bool,
+
+    /// Record the provider, model and picked suggestion in a git note of the commit
+    #[arg(long)]
+    pub(crate) notes: bool,
+
+    /// Send the changes of lockfiles and generated files, instead of a note that they're updated
+    #[arg(long)]
+    pub(crate) include_lockfiles: bool,
+
+    /// List the untracked files with a short preview in the prompt, as context about new files
+    #[arg(long)]
+    pub(crate) include_untracked: bool,
+
+    /// Describe the changes of a revision range like `main..feature` instead of the staged
+    /// changes and print the picked message, a single revision is compared to `HEAD`
+    #[arg(long, value_name = "REV..REV", conflicts_with_all = ["amend", "all"])]
+    pub(crate) range: Option<String>,
+
+    /// Describe the changes of a patch file, e.g. received by mail, instead of the staged changes
+    /// and print the picked message. Use `-` to read the patch from stdin.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["amend", "all", "range"])]
+    pub(crate) patch_file: Option<PathBuf>,
+
+    /// Apply the patch file and commit it with the picked message
+    #[arg(long, requires = "patch_file")]
+    pub(crate) apply: bool,
+
+    /// Read a unified diff from stdin and print the first suggestion to stdout, without any
+    /// interaction, e.g. `git diff | commitgpt --stdin`
+    #[arg(long, conflicts_with_all = ["amend", "all", "range", "patch_file"])]
+    pub(crate) stdin: bool,
+
+    /// Commit every staged file on its own, each with its own generated message
+    #[arg(
+        long,
+        conflicts_with_all = ["amend", "all", "range", "patch_file", "stdin", "choose_files", "choose_hunks"]
+    )]
+    pub(crate) per_file: bool,
+
+    /// Pick the files which should be described from the staged files, the others are still
+    /// committed
+    #[arg(long, conflicts_with = "stdin")]
+    pub(crate) choose_files: bool,
+
+    /// Pick the hunks which should be described one by one, like `git add -p`, the others are
+    /// still committed
+    #[arg(long, conflicts_with = "stdin")]
+    pub(crate) choose_hunks: bool,
+
+    /// The subject line of the commit, e.g. `feat: add retry logic`, so only the body is
+    /// generated
+    #[arg(long, value_name = "SUBJECT")]
+    pub(crate) subject: Option<String>,
+
     /// An optional hint or context to guide commit message generation
     pub(crate) hint: Option<String>,
 
     /// The files which should be transmitted as diff, otherwise all files till be transmited
     pub(crate) path: Vec<String>,
+
+    /// Send only the changed files with the amount of changed lines and the hunk headers instead
+    /// of the code
+    #[arg(
+        long,
+        value_enum,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "stats"
+    )]
+    pub(crate) privacy: Option<Privacy>,
+
+    /// What happens with diffs larger than `max_diff_tokens`
+    #[arg(long, value_enum)]
+    pub(crate) large_diff: Option<LargeDiff>,
+
+    /// The conventional commit type of the message, e.g. `fix`, which the model can't change.
+    /// Without a value, the type is picked from a list.
+    #[arg(
+        long = "type",
+        value_name = "TYPE",
+        num_args = 0..=1,
+        default_missing_value = ""
+    )]
+    pub(crate) kind: Option<String>,
+
+    /// What happens with suggestions which don't follow conventional commits
+    #[arg(long, value_enum)]
+    pub(crate) validation: Option<Validation>,
+
+    /// The issue of the change, e.g. `123` or `PROJ-123`, whose title and description are
+    /// fetched from the issue tracker, defaults to the ticket of the branch
+    #[arg(long, value_name = "ID")]
+    pub(crate) issue: Option<String>,
+
+    /// The convention of the commit messages
+    #[arg(long, value_enum)]
+    pub(crate) convention: Option<Convention>,
+
+    /// The language of the commit messages, e.g. `de` or `German`
+    #[arg(long, value_name = "LANGUAGE")]
+    pub(crate) lang: Option<String>,
+
+    /// Send the latest commit messages as examples of the style of the repository
+    #[arg(long, value_name = "COUNT")]
+    pub(crate) history_examples: Option<usize>,
+
+    /// Whether the examples are only the subject lines or the full messages
+    #[arg(long, value_enum)]
+    pub(crate) history_format: Option<HistoryFormat>,
+
+    /// Print the system and user message before they're sent, including the reduced and
+    /// anonymized diff, and ask to confirm
+    #[arg(long)]
+    pub(crate) show_prompt: bool,
+
+    /// Replace the directories of the changed files with placeholders before the prompt is sent
+    #[arg(long)]
+    pub(crate) anonymize_paths: bool,
+
+    /// The amount of context lines around every change in the diff
+    #[arg(short = 'U', long = "unified", value_name = "LINES")]
+    pub(crate) diff_context: Option<u32>,
+
+    /// Describe the changes of modified source files by their added, removed or modified
+    /// definitions instead of their hunks
+    #[cfg(feature = "tree-sitter")]
+    #[arg(long)]
+    pub(crate) semantic: bool,
+
+    /// Leave the files matching the glob out of the diff, e.g. `--exclude '*.snap'`. Can be
+    /// repeated.
+    #[arg(long, value_name = "GLOB")]
+    pub(crate) exclude: Vec<String>,
+
+    /// An argument which is passed as it is to `git commit`, e.g. `--git-arg=--no-verify`
+    #[arg(long = "git-arg", value_name = "ARG", allow_hyphen_values = true)]
+    pub(crate) git_args: Vec<String>,
+
+    /// Arguments after `--` are passed as they are to `git commit`, e.g. `-- --signoff -S`
+    #[arg(last = true, value_name = "GIT COMMIT ARGS")]
+    pub(crate) git_commit_args: Vec<String>,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum Commands {
+    /// Write a generated commit message into the message file, to be called from git's
+    /// `prepare-commit-msg` hook
+    Hook {
+        /// The file which contains the commit message
+        file: PathBuf,
+
+        /// The source of the commit message, e.g. `message` if it was given with `-m`
+        source: Option<String>,
+
+        /// The commit which is amended, if any
+        sha: Option<String>,
+    },
+
+    /// Record the note of a commit whose message was written by the hook, to be called from
+    /// git's `post-commit` hook
+    PostCommit,
+
+    /// Install the `prepare-commit-msg` and `post-commit` hooks into the current repository,
+    /// existing hooks are kept and called first
+    InstallHook,
+
+    /// Uninstall the hooks and restore previously existing hooks
+    UninstallHook,
+
+    /// Consolidate the commits since the base into a single message and print it, e.g. for
+    /// `git merge --squash` or a squash during an interactive rebase
+    Squash {
+        /// The commit the squashed commits are based on, e.g. `main`
+        base: String,
+    },
+
+    /// Suggest branch names for the staged changes, then create and switch to the picked one
+    Branch {
+        /// Only print the picked name instead of creating the branch
+        #[arg(long)]
+        print: bool,
+    },
+
+    /// Let the model group the staged hunks into logically related commits with a message each,
+    /// then commit the groups one after another after a confirmation
+    Split,
+
+    /// Walk the commits since the base and reword them with regenerated messages, each one can
+    /// be accepted, edited or skipped
+    RewordRange {
+        /// The commit the reworded commits are based on, e.g. `main`
+        base: String,
+    },
+
+    /// Regenerate the messages of all commits in the range at once, preview the old and new
+    /// messages and rewrite the commits after a confirmation
+    Rewrite {
+        /// The commits which are rewritten, e.g. `main..HEAD` or `main`. The range has to end at
+        /// `HEAD`.
+        range: String,
+
+        /// Only preview the old and new messages, without rewriting the commits
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Parses a number within the range of a sampling parameter
+fn sampling_parameter(value: &str, range: RangeInclusive<f32>) -> Result<f32, String> {
+    let parameter = value.parse::<f32>().map_err(|err| err.to_string())?;
+    if range.contains(&parameter) {
+        Ok(parameter)
+    } else {
+        Err(format!(
+            "the value is between {} and {}",
+            range.start(),
+            range.end()
+        ))
+    }
+}
+
+/// Parses a sampling temperature between 0 and 2
+fn temperature(value: &str) -> Result<f32, String> {
+    sampling_parameter(value, 0.0..=2.0)
+}
+
+/// Parses a nucleus sampling probability between 0 and 1
+fn top_p(value: &str) -> Result<f32, String> {
+    sampling_parameter(value, 0.0..=1.0)
+}
+
+/// Parses a presence or frequency penalty between -2 and 2
+fn penalty(value: &str) -> Result<f32, String> {
+    sampling_parameter(value, -2.0..=2.0)
 }
@@ -0,0 +1,83 @@
+use crate::{render_response, Cli, CompletionRequest, Error};
+
+/// Whether the text contains the phrase as whole words, ignoring the case
+fn contains_phrase(text: &str, phrase: &str) -> bool {
+    let text = text.to_lowercase();
+    let phrase = phrase.trim().to_lowercase();
+    if phrase.is_empty() {
+        return false;
+    }
+    text.match_indices(&phrase).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + phrase.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// The message without a period at the end of its subject, e.g. `feat: add x.`
+pub(crate) fn strip_subject_period(message: &str) -> String {
+    match message.split_once('\n') {
+        Some((subject, body)) => format!("{}\n{body}", subject.trim_end().trim_end_matches('.')),
+        None => message.trim_end().trim_end_matches('.').to_string(),
+    }
+}
+
+impl Cli {
+    /// The banned phrases which the message contains
+    pub(crate) fn banned_phrases_in(&self, message: &str) -> Vec<&str> {
+        self.config
+            .banned_phrases
+            .iter()
+            .map(String::as_str)
+            .filter(|phrase| contains_phrase(message, phrase))
+            .collect()
+    }
+
+    /// The responses without the ones which contain banned phrases. If all of them contain
+    /// some, the model is asked once more for suggestions without them, and it's an error if the
+    /// new ones contain banned phrases as well.
+    pub(crate) async fn without_banned_phrases(
+        &self,
+        request: &CompletionRequest,
+        responses: Vec<String>,
+    ) -> Result<Vec<String>, Error> {
+        if self.config.banned_phrases.is_empty() {
+            return Ok(responses);
+        }
+        let allowed = |responses: &[String]| {
+            responses
+                .iter()
+                .filter(|response| {
+                    self.banned_phrases_in(&render_response(response, request.structured))
+                        .is_empty()
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        let allowed_responses = allowed(&responses);
+        if !allowed_responses.is_empty() {
+            return Ok(allowed_responses);
+        }
+        let mut used = Vec::new();
+        for response in &responses {
+            for phrase in self.banned_phrases_in(&render_response(response, request.structured)) {
+                if !used.contains(&phrase) {
+                    used.push(phrase);
+                }
+            }
+        }
+        let mut request = request.clone();
+        request.user.push_str(&format!(
+            r#"
+
+The previous suggestions used phrases which aren't allowed ("{}"), write them again without these phrases.
+"#,
+            used.join("\", \"")
+        ));
+        let regenerated = allowed(&self.complete(&request).await?);
+        if regenerated.is_empty() {
+            return Err(Error::BannedPhrases(used.join("\", \"")));
+        }
+        Ok(regenerated)
+    }
+}
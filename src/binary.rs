@@ -0,0 +1,108 @@
+use crate::{diff::FileDiff, Cli};
+
+/// The kinds of binary files by their extensions
+const KINDS: &[(&str, &[&str])] = &[
+    (
+        "image",
+        &[
+            "png", "jpg", "jpeg", "gif", "webp", "avif", "bmp", "ico", "tif", "tiff", "psd",
+        ],
+    ),
+    ("font", &["ttf", "otf", "woff", "woff2", "eot"]),
+    ("audio", &["mp3", "wav", "ogg", "flac", "m4a", "aac"]),
+    ("video", &["mp4", "mov", "webm", "avi", "mkv"]),
+    (
+        "document",
+        &["pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx"],
+    ),
+    (
+        "archive",
+        &["zip", "gz", "tgz", "tar", "bz2", "xz", "7z", "rar", "jar"],
+    ),
+    (
+        "executable",
+        &["exe", "dll", "so", "dylib", "a", "lib", "o", "wasm"],
+    ),
+    ("database", &["db", "sqlite", "sqlite3"]),
+];
+
+/// The kind of the binary file by its extension, e.g. `image`
+fn kind(path: &str) -> &'static str {
+    let extension = path
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .unwrap_or_default();
+    KINDS
+        .iter()
+        .find(|(_, extensions)| extensions.contains(&extension.as_str()))
+        .map_or("file", |(kind, _)| kind)
+}
+
+/// The size in a human readable unit, e.g. `12.3 KiB`
+fn human_size(bytes: u64) -> String {
+    let mut size = bytes as f64;
+    for unit in ["B", "KiB", "MiB"] {
+        if size < 1024.0 {
+            return if unit == "B" {
+                format!("{bytes} B")
+            } else {
+                format!("{size:.1} {unit}")
+            };
+        }
+        size /= 1024.0;
+    }
+    format!("{size:.1} GiB")
+}
+
+impl Cli {
+    /// Describes a changed binary file by its kind and size, as the diff only states that it
+    /// differs
+    pub(crate) fn describe_binary_change(&self, file: &FileDiff) -> Option<String> {
+        if !file.is_binary() {
+            return None;
+        }
+
+        let (old_id, new_id) = file.blob_ids();
+        let size = |id: &str| {
+            if id.is_empty() {
+                return None;
+            }
+            self.vcs.blob_size(id).ok().flatten()
+        };
+        let kind = kind(&file.path);
+        let path = &file.path;
+        Some(match (file.is_new(), file.is_deleted()) {
+            (true, _) => match size(new_id) {
+                Some(new_size) => format!(
+                    "(added the binary {kind} {path} of {})",
+                    human_size(new_size)
+                ),
+                None => format!("(added the binary {kind} {path})"),
+            },
+            (_, true) => match size(old_id) {
+                Some(old_size) => {
+                    format!(
+                        "(removed the binary {kind} {path} of {})",
+                        human_size(old_size)
+                    )
+                }
+                None => format!("(removed the binary {kind} {path})"),
+            },
+            _ => match (size(old_id), size(new_id)) {
+                (Some(old_size), Some(new_size)) => {
+                    let delta = if new_size >= old_size {
+                        format!("+{}", human_size(new_size - old_size))
+                    } else {
+                        format!("-{}", human_size(old_size - new_size))
+                    };
+                    format!(
+                        "(changed the binary {kind} {path} from {} to {}, {delta})",
+                        human_size(old_size),
+                        human_size(new_size)
+                    )
+                }
+                _ => format!("(changed the binary {kind} {path})"),
+            },
+        })
+    }
+}
@@ -0,0 +1,83 @@
+use dialoguer::{theme::ColorfulTheme, Select};
+
+use crate::{Cli, Error};
+
+impl Cli {
+    /// Suggests branch names for the staged changes and creates the picked branch
+    pub(crate) async fn suggest_branch(&self, print: bool) -> Result<(), Error> {
+        let diff = self.get_git_diff()?;
+        if diff.is_empty() {
+            return Err(Error::EmptyDiff);
+        }
+
+        let system = format!(
+            "You are a helpful assistant which names git branches based on the given diff. \
+Respond with a single branch name in kebab-case and nothing else. It starts with one of these \
+prefixes, matching the kind of change: {}",
+            self.config.branch_prefixes.join(", ")
+        );
+        let anonymizer = self.anonymizer(&diff);
+        let user = self.get_user_message(diff, None).await?;
+        let request = self.completion_request(system, user, false);
+        let mut names = Vec::new();
+        for name in self
+            .complete(&request)
+            .await?
+            .iter()
+            .filter_map(|choice| branch_name(&anonymizer.restore(choice)))
+        {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        if names.is_empty() {
+            return Err(Error::EmptySelection);
+        }
+
+        let Ok(index) = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Pick branch name")
+            .default(0)
+            .items(&names)
+            .interact()
+        else {
+            return Ok(());
+        };
+        let name = names.get(index).ok_or(Error::EmptySelection)?;
+        if print {
+            println!("{name}");
+            return Ok(());
+        }
+        self.vcs.create_branch(name)?;
+        println!("Switched to the new branch `{name}`.");
+        Ok(())
+    }
+}
+
+/// Normalizes the response to a valid branch name, e.g. `` `Feat/Add Login` `` to
+/// `feat/add-login`
+fn branch_name(response: &str) -> Option<String> {
+    let line = response.lines().find(|line| !line.trim().is_empty())?;
+    let name = line
+        .trim()
+        .trim_matches(|char: char| matches!(char, '`' | '"' | '\'' | '.'))
+        .to_lowercase()
+        .chars()
+        .map(|char| match char {
+            'a'..='z' | '0'..='9' | '/' | '-' | '.' => char,
+            _ => '-',
+        })
+        .collect::<String>();
+    let name = name
+        .split('/')
+        .map(|part| {
+            part.split('-')
+                .filter(|word| !word.is_empty())
+                .collect::<Vec<_>>()
+                .join("-")
+        })
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+        .replace("..", ".");
+    (!name.is_empty()).then_some(name)
+}
@@ -0,0 +1,158 @@
+use crate::{conventions::Convention, diff::file_diffs, Cli, Header};
+
+/// The most breaking changes which are listed in the prompt and the footer
+const MAX_BREAKING_CHANGES: usize = 10;
+
+/// The keywords of the declarations which belong to the API, without `impl` blocks
+const API_KEYWORDS: &[&str] = &[
+    "fn ",
+    "struct ",
+    "enum ",
+    "trait ",
+    "type ",
+    "class ",
+    "interface ",
+    "def ",
+    "function ",
+    "func ",
+];
+
+/// The modifiers which may follow the one which makes the declaration public, e.g. `async`
+const MODIFIERS: &[&str] = &[
+    "async ",
+    "unsafe ",
+    "const ",
+    "default ",
+    "static ",
+    "abstract ",
+    "final ",
+    "sealed ",
+    "open ",
+    "data ",
+];
+
+/// The name and the normalized signature of a public declaration of the file, `None` if the line
+/// doesn't declare a public function or type. Public are the declarations with `pub`, `export`
+/// or `public`, capitalized Go functions and Python definitions without a leading underscore.
+fn public_declaration(path: &str, line: &str) -> Option<(String, String)> {
+    let indentation = line.len() - line.trim_start().len();
+    if indentation > 4 {
+        return None;
+    }
+    let line = line.trim();
+    let (explicit, mut declaration) = ["pub ", "export ", "public "]
+        .iter()
+        .find_map(|modifier| line.strip_prefix(modifier))
+        .map_or((false, line), |rest| (true, rest));
+    while let Some(rest) = MODIFIERS
+        .iter()
+        .find_map(|modifier| declaration.strip_prefix(modifier))
+    {
+        declaration = rest;
+    }
+    let (keyword, rest) = API_KEYWORDS
+        .iter()
+        .find_map(|keyword| Some((*keyword, declaration.strip_prefix(keyword)?)))?;
+    // The receiver of a Go method precedes its name.
+    let rest = match rest.strip_prefix('(') {
+        Some(receiver) if keyword == "func " => receiver.split_once(')')?.1.trim_start(),
+        _ => rest,
+    };
+    let name = rest
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()
+        .filter(|name| !name.is_empty())?;
+    let public = match keyword {
+        _ if explicit => true,
+        "func " if path.ends_with(".go") => name.starts_with(char::is_uppercase),
+        "def " | "class " if path.ends_with(".py") => !name.starts_with('_'),
+        _ => false,
+    };
+    let signature = line
+        .trim_end_matches(['{', ':', ' '])
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    public.then(|| (name.to_string(), signature))
+}
+
+/// The public functions and types which the diff removes or whose signature it changes, e.g.
+/// ``removed or renamed `pub fn parse(text: &str)` ``. A declaration which is only moved to
+/// another file isn't a breaking change.
+pub(crate) fn breaking_changes(diff: &str) -> Vec<String> {
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    for file in file_diffs(diff) {
+        removed.extend(
+            file.changed_lines('-')
+                .into_iter()
+                .filter_map(|line| public_declaration(&file.path, line)),
+        );
+        added.extend(
+            file.changed_lines('+')
+                .into_iter()
+                .filter_map(|line| public_declaration(&file.path, line)),
+        );
+    }
+    let mut changes = Vec::new();
+    for (name, signature) in &removed {
+        if added.iter().any(|(_, added)| added == signature) {
+            continue;
+        }
+        let change = match added.iter().find(|(added, _)| added == name) {
+            Some((_, new_signature)) => {
+                format!("changed the signature `{signature}` to `{new_signature}`")
+            }
+            None => format!("removed or renamed `{signature}`"),
+        };
+        if !changes.contains(&change) {
+            changes.push(change);
+        }
+    }
+    changes
+}
+
+/// The message with the `!` marker of a breaking change in its header and a `BREAKING CHANGE:`
+/// footer, which lists the changes if the message has none
+pub(crate) fn mark_breaking(message: &str, changes: &[String]) -> String {
+    let (header, body) = match message.split_once('\n') {
+        Some((header, body)) => (header, Some(body)),
+        None => (message, None),
+    };
+    let mut marked = match Header::parse(header) {
+        Some(parsed) => Header {
+            breaking: true,
+            ..parsed
+        }
+        .to_string(),
+        None => header.to_string(),
+    };
+    if let Some(body) = body {
+        marked.push('\n');
+        marked.push_str(body);
+    }
+    let has_footer = marked
+        .lines()
+        .any(|line| line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:"));
+    if !has_footer {
+        marked = format!(
+            "{}\n\nBREAKING CHANGE: {}",
+            marked.trim_end(),
+            changes.join("; ")
+        );
+    }
+    marked
+}
+
+impl Cli {
+    /// The breaking changes of the public API in the diff, at most `MAX_BREAKING_CHANGES`.
+    /// Empty if the detection is disabled or the messages don't follow conventional commits.
+    pub(crate) fn breaking_changes(&self, diff: &str) -> Vec<String> {
+        if !self.config.detect_breaking_changes || self.convention() != Convention::Conventional {
+            return Vec::new();
+        }
+        let mut changes = breaking_changes(diff);
+        changes.truncate(MAX_BREAKING_CHANGES);
+        changes
+    }
+}
@@ -0,0 +1,257 @@
+use std::io::{self, IsTerminal};
+
+use dialoguer::{theme::ColorfulTheme, Select};
+use serde::Deserialize;
+
+use crate::{
+    diff::{file_diffs, split_before},
+    summary::CHARS_PER_TOKEN,
+    Cli, Error, Model,
+};
+
+/// What happens with a diff which exceeds `max_diff_tokens`
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum LargeDiff {
+    /// The parts of the diff are summarized on their own first
+    #[default]
+    Summarize,
+    /// The most important files and hunks are sent as far as they fit, the rest is left out
+    Truncate,
+}
+
+/// How important the changes of a file are for the message, the most important first
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Priority {
+    Source,
+    Test,
+    DocumentationOrConfig,
+}
+
+/// The extensions of documentation and configuration files
+const DOCUMENTATION_OR_CONFIG_EXTENSIONS: &[&str] = &[
+    "md", "rst", "txt", "adoc", "toml", "yaml", "yml", "json", "ini", "cfg", "conf", "xml", "lock",
+];
+
+/// The priority of the file by its path
+fn priority(path: &str) -> Priority {
+    let lowercase = path.to_ascii_lowercase();
+    let mut components = lowercase.split('/').collect::<Vec<_>>();
+    let name = components.pop().unwrap_or_default();
+    let (stem, extension) = name.rsplit_once('.').unwrap_or((name, ""));
+    if components
+        .iter()
+        .any(|component| matches!(*component, "test" | "tests" | "spec" | "__tests__"))
+        || stem.starts_with("test_")
+        || stem.ends_with("_test")
+        || stem.ends_with(".test")
+        || stem.ends_with(".spec")
+    {
+        Priority::Test
+    } else if components
+        .iter()
+        .any(|component| matches!(*component, "doc" | "docs"))
+        || DOCUMENTATION_OR_CONFIG_EXTENSIONS.contains(&extension)
+        || stem.starts_with("license")
+        || stem.starts_with("changelog")
+        || name.starts_with('.')
+    {
+        Priority::DocumentationOrConfig
+    } else {
+        Priority::Source
+    }
+}
+
+/// Whether the hunk changes logic, i.e. any changed line is neither blank, a comment nor an
+/// import
+fn changes_logic(hunk: &str) -> bool {
+    hunk.lines()
+        .skip(1)
+        .filter_map(|line| line.strip_prefix(['+', '-']))
+        .map(str::trim)
+        .any(|line| {
+            !line.is_empty()
+                && ![
+                    "//", "#", "/*", "*", "--", "use ", "import ", "from ", "require(",
+                ]
+                .iter()
+                .any(|prefix| line.starts_with(prefix))
+        })
+}
+
+impl Cli {
+    /// The configured handling of large diffs
+    pub(crate) fn large_diff(&self) -> LargeDiff {
+        self.args.large_diff.unwrap_or(self.config.large_diff)
+    }
+
+    /// How the diff should be reduced before it's sent, `None` if it should be sent as a whole.
+    /// Diffs larger than `confirm_diff_tokens` aren't sent without asking, with the estimated
+    /// tokens and price, unless there is no terminal to ask.
+    pub(crate) fn large_diff_handling(&self, diff: &str) -> Result<Option<LargeDiff>, Error> {
+        let handling = (diff.len() > self.max_diff_chars()).then(|| self.large_diff());
+        let tokens = diff.len() / CHARS_PER_TOKEN;
+        let confirm_diff_tokens =
+            usize::try_from(self.config.confirm_diff_tokens).unwrap_or(usize::MAX);
+        if confirm_diff_tokens == 0
+            || tokens <= confirm_diff_tokens
+            || self.args.stdin
+            || !io::stdin().is_terminal()
+        {
+            return Ok(handling);
+        }
+
+        let price = Model::from_alias(&self.model())
+            .map(|model| {
+                format!(
+                    " (~${:.2})",
+                    tokens as f64 * model.input_price() / 1_000_000.0
+                )
+            })
+            .unwrap_or_default();
+        let prompt = format!(
+            "About to send ~{tokens} tokens{price} covering {} files",
+            file_diffs(diff).len()
+        );
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .default(0)
+            .items(&["Continue", "Summarize", "Abort"])
+            .interact();
+        match selection {
+            Ok(0) => Ok(None),
+            Ok(1) => Ok(Some(LargeDiff::Summarize)),
+            _ => Err(Error::Aborted),
+        }
+    }
+}
+
+/// Reduces the diff to at most `max_chars` by leaving out the least important changes. Source
+/// files come first, tests second and documentation and configuration last. A file which
+/// doesn't fit as a whole keeps its hunks which change logic first. The files keep their order
+/// and a note tells how much was left out.
+pub(crate) fn truncate_diff(diff: &str, max_chars: usize) -> String {
+    if diff.len() <= max_chars {
+        return diff.to_string();
+    }
+
+    // The header of every file followed by its hunks.
+    let files = file_diffs(diff)
+        .iter()
+        .map(|file| {
+            let mut parts = split_before(file.text, "@@ ").into_iter();
+            let header = parts.next().unwrap_or_default();
+            (priority(&file.path), header, parts.collect::<Vec<_>>())
+        })
+        .collect::<Vec<_>>();
+    let mut order = (0..files.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&index| files[index].0);
+
+    let mut selected = files
+        .iter()
+        .map(|(_, _, hunks)| vec![false; hunks.len()])
+        .collect::<Vec<_>>();
+    let mut included = vec![false; files.len()];
+    let mut used = 0;
+    for index in order {
+        let (_, header, hunks) = &files[index];
+        let size = header.len() + hunks.iter().map(String::len).sum::<usize>();
+        if used + size <= max_chars {
+            used += size;
+            included[index] = true;
+            selected[index].fill(true);
+            continue;
+        }
+        let mut hunk_order = (0..hunks.len()).collect::<Vec<_>>();
+        hunk_order.sort_by_key(|&hunk| !changes_logic(&hunks[hunk]));
+        for hunk in hunk_order {
+            let size = hunks[hunk].len() + if included[index] { 0 } else { header.len() };
+            if used + size <= max_chars {
+                used += size;
+                included[index] = true;
+                selected[index][hunk] = true;
+            }
+        }
+    }
+
+    let mut truncated = String::with_capacity(used);
+    let mut omitted_files = 0;
+    let mut omitted_hunks = 0;
+    for (index, (_, header, hunks)) in files.iter().enumerate() {
+        if !included[index] {
+            omitted_files += 1;
+            continue;
+        }
+        truncated.push_str(header);
+        for (hunk, selected) in hunks.iter().zip(&selected[index]) {
+            if *selected {
+                truncated.push_str(hunk);
+            } else {
+                omitted_hunks += 1;
+            }
+        }
+    }
+    let omitted = match (omitted_files, omitted_hunks) {
+        (0, hunks) => format!("{hunks} hunks"),
+        (files, 0) => format!("{files} files"),
+        (files, hunks) => format!("{files} files and {hunks} further hunks"),
+    };
+    truncated.push_str(&format!(
+        "({omitted} are omitted to fit the token budget, the changed files are listed above)\n"
+    ));
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The diff of a file which adds the lines
+    fn file_diff(path: &str, lines: &[&str]) -> String {
+        let added = lines
+            .iter()
+            .map(|line| format!("+{line}\n"))
+            .collect::<String>();
+        format!(
+            "diff --git a/{path} b/{path}\n--- a/{path}\n+++ b/{path}\n@@ -0,0 +1,{} @@\n{added}",
+            lines.len()
+        )
+    }
+
+    #[test]
+    fn prioritizes_source_over_tests_over_docs() {
+        for path in ["src/main.rs", "lib/parser.py"] {
+            assert!(priority(path) == Priority::Source, "{path}");
+        }
+        for path in ["tests/cli.rs", "src/parser_test.go", "web/app.spec.ts"] {
+            assert!(priority(path) == Priority::Test, "{path}");
+        }
+        for path in ["README.md", "docs/guide.html", "Cargo.toml", ".gitignore"] {
+            assert!(priority(path) == Priority::DocumentationOrConfig, "{path}");
+        }
+        assert!(Priority::Source < Priority::Test);
+        assert!(Priority::Test < Priority::DocumentationOrConfig);
+    }
+
+    #[test]
+    fn omits_the_least_important_files() {
+        let docs = file_diff("README.md", &["# Parser", "Parses the input."]);
+        let test = file_diff("tests/parser.rs", &["#[test]", "fn parses() {}"]);
+        let source = file_diff("src/parser.rs", &["fn parse() {", "    todo!()", "}"]);
+        let diff = format!("{docs}{test}{source}");
+        assert_eq!(truncate_diff(&diff, diff.len()), diff);
+
+        let truncated = truncate_diff(&diff, test.len() + source.len());
+        assert!(
+            truncated.starts_with(&format!("{test}{source}")),
+            "{truncated}"
+        );
+        assert!(truncated.ends_with(
+            "(1 files are omitted to fit the token budget, the changed files are listed above)\n"
+        ));
+
+        let truncated = truncate_diff(&diff, source.len());
+        assert!(truncated.starts_with(&source), "{truncated}");
+        assert!(truncated.contains("(2 files are omitted"), "{truncated}");
+    }
+}
@@ -0,0 +1,58 @@
+use dialoguer::{theme::ColorfulTheme, Confirm, MultiSelect};
+
+use crate::{
+    diff::{file_diffs, split_before},
+    Cli, Error,
+};
+
+impl Cli {
+    /// Lets the user untick the files which shouldn't be described, e.g. noisy ones, without
+    /// unstaging them. Only the diff of the ticked files is returned.
+    pub(crate) fn choose_files(&self, diff: &str) -> Result<String, Error> {
+        let files = file_diffs(diff);
+        let items = files.iter().map(|file| file.stat()).collect::<Vec<_>>();
+        let chosen = MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Pick the files which should be described")
+            .items(&items)
+            .defaults(&vec![true; items.len()])
+            .interact()?;
+        if chosen.is_empty() {
+            return Err(Error::EmptySelection);
+        }
+        Ok(chosen.into_iter().map(|index| files[index].text).collect())
+    }
+
+    /// Asks for every hunk whether it should be described, like `git add -p`, e.g. to leave out
+    /// debugging leftovers. Files without hunks, e.g. binary files, are kept as they are.
+    pub(crate) fn choose_hunks(&self, diff: &str) -> Result<String, Error> {
+        let mut chosen = String::with_capacity(diff.len());
+        for file in file_diffs(diff) {
+            let mut parts = split_before(file.text, "@@ ").into_iter();
+            let header = parts.next().unwrap_or_default();
+            let hunks = parts.collect::<Vec<_>>();
+            if hunks.is_empty() {
+                chosen.push_str(&header);
+                continue;
+            }
+            let mut chosen_hunks = String::new();
+            for hunk in hunks {
+                eprintln!("{}\n{}", file.path, hunk.trim_end());
+                let describe = Confirm::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Describe this hunk?")
+                    .default(true)
+                    .interact()?;
+                if describe {
+                    chosen_hunks.push_str(&hunk);
+                }
+            }
+            if !chosen_hunks.is_empty() {
+                chosen.push_str(&header);
+                chosen.push_str(&chosen_hunks);
+            }
+        }
+        if chosen.is_empty() {
+            return Err(Error::EmptySelection);
+        }
+        Ok(chosen)
+    }
+}
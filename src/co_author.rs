@@ -0,0 +1,80 @@
+use std::io::{self, IsTerminal};
+
+use dialoguer::{theme::ColorfulTheme, Select};
+
+use crate::{Cli, Error};
+
+impl Cli {
+    /// The co-authors of the command line and the config as `Name <email>`. Names or emails
+    /// without both are completed from the authors and co-authors of the recent commits.
+    pub(crate) fn co_authors(&self) -> Result<Vec<String>, Error> {
+        let queries = self
+            .args
+            .co_author
+            .iter()
+            .chain(&self.config.co_authors)
+            .collect::<Vec<_>>();
+        if queries.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut contributors = None;
+        let mut co_authors = Vec::<String>::new();
+        for query in queries {
+            let co_author = if is_complete(query) {
+                query.clone()
+            } else {
+                let contributors = match &contributors {
+                    Some(contributors) => contributors,
+                    None => contributors.insert(self.vcs.contributors()?),
+                };
+                complete(query, contributors)?
+            };
+            if !co_authors.contains(&co_author) {
+                co_authors.push(co_author);
+            }
+        }
+        Ok(co_authors)
+    }
+}
+
+/// Whether the co-author is given as `Name <email>`
+fn is_complete(co_author: &str) -> bool {
+    co_author
+        .split_once(" <")
+        .is_some_and(|(name, email)| !name.trim().is_empty() && email.ends_with('>'))
+}
+
+/// Completes the name or email (or a part of it) from the contributors, asking the user if
+/// it's ambiguous
+fn complete(query: &str, contributors: &[String]) -> Result<String, Error> {
+    let lowercase_query = query.to_lowercase();
+    let matches = contributors
+        .iter()
+        .filter(|contributor| contributor.to_lowercase().contains(&lowercase_query))
+        .collect::<Vec<_>>();
+    match matches.as_slice() {
+        [] => Err(Error::UnknownCoAuthor(query.to_string())),
+        [co_author] => Ok(co_author.to_string()),
+        // Without a terminal, or if the user doesn't pick one, the candidates are listed.
+        _ => {
+            let ambiguous = || {
+                let candidates = matches
+                    .iter()
+                    .map(|candidate| format!("`{candidate}`"))
+                    .collect::<Vec<_>>();
+                Error::AmbiguousCoAuthor(query.to_string(), candidates.join(", "))
+            };
+            if !io::stdin().is_terminal() {
+                return Err(ambiguous());
+            }
+            let index = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!("Pick co-author for `{query}`"))
+                .default(0)
+                .items(&matches)
+                .interact()
+                .map_err(|_| ambiguous())?;
+            Ok(matches[index].to_string())
+        }
+    }
+}
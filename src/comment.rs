@@ -0,0 +1,83 @@
+use crate::{Cli, Commands, Error};
+
+impl Cli {
+    /// The string comment lines of commit messages start with, configured with
+    /// `core.commentString` or `core.commentChar`. `None` if git picks an unused character.
+    pub(crate) fn comment_string(&self) -> Result<Option<String>, Error> {
+        let comment_string = match self.vcs.config("core.commentString")? {
+            Some(comment_string) => Some(comment_string),
+            None => self.vcs.config("core.commentChar")?,
+        };
+        match comment_string.as_deref() {
+            Some("auto") => Ok(None),
+            Some(comment_string) if !comment_string.is_empty() => {
+                Ok(Some(comment_string.to_string()))
+            }
+            _ => Ok(Some("#".to_string())),
+        }
+    }
+
+    /// Whether git strips the comments of the message, as it goes through the editor or the
+    /// `prepare-commit-msg` hook. Other messages are printed or committed verbatim.
+    pub(crate) fn strips_comments(&self) -> bool {
+        match self.args.command {
+            Some(Commands::Hook { .. }) => true,
+            Some(Commands::Split) => !self.oneline(),
+            Some(_) => false,
+            None => {
+                !(self.args.stdin
+                    || self.oneline()
+                    || self.range().is_some()
+                    || (self.args.patch_file.is_some() && !self.args.apply))
+            }
+        }
+    }
+}
+
+/// Indents the lines of the message which start with the comment string, e.g. a Markdown
+/// heading or `#123`, as git would drop them when cleaning up the message. The lines of `keep`,
+/// e.g. the comments of the commit template, are kept as comments.
+pub(crate) fn escape_comments(message: &str, comment_string: &str, keep: &str) -> String {
+    message
+        .lines()
+        .map(|line| {
+            if line.starts_with(comment_string)
+                && !keep.lines().any(|kept| kept.trim_end() == line.trim_end())
+            {
+                format!(" {line}")
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_lines_which_start_with_the_comment_string() {
+        assert_eq!(
+            escape_comments("fix: x\n\n#123 was broken\n## Notes", "#", ""),
+            "fix: x\n\n #123 was broken\n ## Notes"
+        );
+    }
+
+    #[test]
+    fn keeps_comments_of_the_template() {
+        assert_eq!(
+            escape_comments("fix: x\n# Why:\n#1", "#", "# Why:  \n"),
+            "fix: x\n# Why:\n #1"
+        );
+    }
+
+    #[test]
+    fn escapes_longer_comment_strings() {
+        assert_eq!(
+            escape_comments("fix: x\n\n// a\n/ b", "//", ""),
+            "fix: x\n\n // a\n/ b"
+        );
+    }
+}
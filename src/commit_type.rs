@@ -0,0 +1,95 @@
+use dialoguer::{theme::ColorfulTheme, Select};
+
+use crate::{conventions::Convention, Cli, Error, Header};
+
+/// The common conventional commit types with their meaning, offered when the type is picked
+pub(crate) const COMMIT_TYPES: &[(&str, &str)] = &[
+    ("feat", "a new feature"),
+    ("fix", "a bug fix"),
+    ("docs", "documentation only changes"),
+    (
+        "style",
+        "formatting which doesn't change the meaning of the code",
+    ),
+    (
+        "refactor",
+        "a change which neither fixes a bug nor adds a feature",
+    ),
+    ("perf", "a change which improves performance"),
+    ("test", "adding missing or correcting existing tests"),
+    (
+        "build",
+        "changes of the build system or external dependencies",
+    ),
+    ("ci", "changes of the CI configuration and scripts"),
+    (
+        "chore",
+        "other changes which don't modify source or test files",
+    ),
+    ("revert", "reverts a previous commit"),
+];
+
+/// The message with the type as type of its header, keeping the scope and the breaking marker.
+/// The type is prepended if the header isn't a conventional commit header.
+pub(crate) fn force_type(message: &str, kind: &str) -> String {
+    let (header, body) = match message.split_once('\n') {
+        Some((header, body)) => (header, Some(body)),
+        None => (message, None),
+    };
+    let mut forced = match Header::parse(header) {
+        Some(parsed) => Header { kind, ..parsed }.to_string(),
+        None => format!("{kind}: {}", header.trim_start()),
+    };
+    if let Some(body) = body {
+        forced.push('\n');
+        forced.push_str(body);
+    }
+    forced
+}
+
+impl Cli {
+    /// The type every message gets from `--type`, picked interactively if no type is given.
+    /// `None` if the type is up to the model or the messages don't follow conventional commits.
+    pub(crate) fn commit_type(&self) -> Result<Option<&str>, Error> {
+        let Some(kind) = self.args.kind.as_deref() else {
+            return Ok(None);
+        };
+        if self.convention() != Convention::Conventional {
+            return Ok(None);
+        }
+        if !kind.is_empty() {
+            return Ok(Some(kind));
+        }
+        if let Some(picked) = self.picked_type.get() {
+            return Ok(Some(picked));
+        }
+        // The types of commitlint win over the common ones.
+        let types = match self
+            .commitlint()
+            .map(|commitlint| commitlint.types().to_vec())
+        {
+            Some(types) if !types.is_empty() => types,
+            _ => COMMIT_TYPES
+                .iter()
+                .map(|(kind, _)| kind.to_string())
+                .collect(),
+        };
+        let items = types
+            .iter()
+            .map(
+                |kind| match COMMIT_TYPES.iter().find(|(known, _)| known == kind) {
+                    Some((_, description)) => format!("{kind}: {description}"),
+                    None => kind.clone(),
+                },
+            )
+            .collect::<Vec<_>>();
+        let index = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Pick commit type")
+            .default(0)
+            .items(&items)
+            .interact()
+            .map_err(|_| Error::Aborted)?;
+        let picked = types.get(index).ok_or(Error::EmptySelection)?;
+        Ok(Some(self.picked_type.get_or_init(|| picked.clone())))
+    }
+}
@@ -0,0 +1,536 @@
+use std::{fmt, fs};
+
+use config_reader::{Config, File, FileFormat};
+use serde_json::{json, Map, Value};
+
+use crate::Cli;
+
+/// The configuration files of commitlint in the order commitlint looks them up
+const CONFIG_FILES: &[&str] = &[
+    ".commitlintrc",
+    ".commitlintrc.json",
+    ".commitlintrc.yaml",
+    ".commitlintrc.yml",
+    ".commitlintrc.js",
+    ".commitlintrc.cjs",
+    ".commitlintrc.mjs",
+    ".commitlintrc.ts",
+    "commitlint.config.js",
+    "commitlint.config.cjs",
+    "commitlint.config.mjs",
+    "commitlint.config.ts",
+];
+
+/// The rules which are understood, the others are ignored
+const RULES: &[&str] = &[
+    "type-enum",
+    "type-case",
+    "scope-enum",
+    "scope-case",
+    "scope-empty",
+    "subject-case",
+    "subject-full-stop",
+    "header-max-length",
+    "body-max-line-length",
+];
+
+/// The rules of the common presets which are relevant for the message
+fn preset_rules(preset: &str) -> Option<Value> {
+    let conventional_types = json!([
+        "build", "chore", "ci", "docs", "feat", "fix", "perf", "refactor", "revert", "style",
+        "test"
+    ]);
+    let subject_case = json!([
+        2,
+        "never",
+        ["sentence-case", "start-case", "pascal-case", "upper-case"]
+    ]);
+    if preset.contains("config-conventional") {
+        Some(json!({
+            "type-enum": [2, "always", conventional_types],
+            "type-case": [2, "always", "lower-case"],
+            "scope-case": [2, "always", "lower-case"],
+            "subject-case": subject_case,
+            "subject-full-stop": [2, "never", "."],
+            "header-max-length": [2, "always", 100],
+            "body-max-line-length": [2, "always", 100],
+        }))
+    } else if preset.contains("config-angular") {
+        Some(json!({
+            "type-enum": [2, "always", [
+                "build", "ci", "docs", "feat", "fix", "perf", "refactor", "revert", "style", "test"
+            ]],
+            "type-case": [2, "always", "lower-case"],
+            "scope-case": [2, "always", "lower-case"],
+            "subject-case": subject_case,
+            "subject-full-stop": [2, "never", "."],
+            "header-max-length": [2, "always", 72],
+        }))
+    } else {
+        None
+    }
+}
+
+/// The value of the JavaScript literal, e.g. an array, which starts the source, converted to
+/// JSON. `None` if it isn't a simple literal.
+fn js_literal(source: &str) -> Option<Value> {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut end = None;
+    for (index, c) in source.char_indices() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => {
+                depth -= 1;
+                if depth == 0 {
+                    end = Some(index + 1);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let literal = source[..end?].replace(['\'', '`'], "\"");
+    // Trailing commas aren't valid JSON.
+    let json = literal
+        .char_indices()
+        .filter(|&(index, c)| {
+            c != ',' || !literal[index + 1..].trim_start().starts_with([']', '}'])
+        })
+        .map(|(_, c)| c)
+        .collect::<String>();
+    serde_json::from_str(&json).ok()
+}
+
+/// The presets and rules of a JavaScript configuration, as far as they're simple literals
+fn js_config(source: &str) -> Value {
+    let extends = ["config-conventional", "config-angular"]
+        .iter()
+        .filter(|preset| source.contains(*preset))
+        .map(|preset| Value::from(format!("@commitlint/{preset}")))
+        .collect::<Vec<_>>();
+    let mut rules = Map::new();
+    for rule in RULES {
+        for quote in ['\'', '"'] {
+            let key = format!("{quote}{rule}{quote}");
+            let Some(position) = source.find(&key) else {
+                continue;
+            };
+            let value = source[position + key.len()..]
+                .trim_start()
+                .strip_prefix(':')
+                .and_then(|value| js_literal(value.trim_start()));
+            if let Some(value) = value {
+                rules.insert(rule.to_string(), value);
+            }
+        }
+    }
+    json!({ "extends": extends, "rules": rules })
+}
+
+/// The configuration in the format of the file, `None` if it's invalid
+fn read_config(path: &std::path::Path, name: &str) -> Option<Value> {
+    let source = fs::read_to_string(path).ok()?;
+    if [".js", ".cjs", ".mjs", ".ts"]
+        .iter()
+        .any(|extension| name.ends_with(extension))
+    {
+        return Some(js_config(&source));
+    }
+    if let Ok(value) = serde_json::from_str(&source) {
+        return Some(value);
+    }
+    Config::builder()
+        .add_source(File::from_str(&source, FileFormat::Yaml))
+        .build()
+        .ok()?
+        .try_deserialize()
+        .ok()
+}
+
+/// The part of a conventional commit header, e.g. `feat(cli)!: add x`
+pub(crate) struct Header<'a> {
+    pub(crate) kind: &'a str,
+    pub(crate) scope: Option<&'a str>,
+    /// Whether the type is followed by the `!` of a breaking change
+    pub(crate) breaking: bool,
+    pub(crate) subject: &'a str,
+}
+
+impl<'a> Header<'a> {
+    /// Parses the header of a conventional commit, `None` if it doesn't follow the format
+    pub(crate) fn parse(header: &'a str) -> Option<Self> {
+        let (prefix, subject) = header.split_once(": ")?;
+        let breaking = prefix.ends_with('!');
+        let prefix = prefix.strip_suffix('!').unwrap_or(prefix);
+        let (kind, scope) = match prefix.split_once('(') {
+            Some((kind, scope)) => (kind, Some(scope.strip_suffix(')')?)),
+            None => (prefix, None),
+        };
+        if kind.is_empty() || !kind.chars().all(char::is_alphanumeric) {
+            return None;
+        }
+        Some(Self {
+            kind,
+            scope,
+            breaking,
+            subject,
+        })
+    }
+}
+
+impl fmt::Display for Header<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(scope) = self.scope {
+            write!(f, "({scope})")?;
+        }
+        if self.breaking {
+            write!(f, "!")?;
+        }
+        write!(f, ": {}", self.subject)
+    }
+}
+
+/// Whether the text is written in the case of commitlint, e.g. `lower-case`
+pub(crate) fn is_case(text: &str, case: &str) -> bool {
+    let mut chars = text.chars();
+    let first = chars.next();
+    let rest = chars.as_str();
+    match case {
+        "lower-case" | "lowercase" => text == text.to_lowercase(),
+        "upper-case" | "uppercase" => text == text.to_uppercase(),
+        "sentence-case" | "sentencecase" => {
+            first.is_some_and(char::is_uppercase) && rest == rest.to_lowercase()
+        }
+        "start-case" | "startcase" => text
+            .split_whitespace()
+            .all(|word| word.chars().next().is_some_and(char::is_uppercase)),
+        "pascal-case" | "pascalcase" => {
+            first.is_some_and(char::is_uppercase) && text.chars().all(char::is_alphanumeric)
+        }
+        "camel-case" | "camelcase" => {
+            first.is_some_and(char::is_lowercase) && text.chars().all(char::is_alphanumeric)
+        }
+        "kebab-case" | "kebabcase" => text
+            .chars()
+            .all(|c| c.is_lowercase() || c.is_numeric() || c == '-'),
+        "snake-case" | "snakecase" => text
+            .chars()
+            .all(|c| c.is_lowercase() || c.is_numeric() || c == '_'),
+        _ => true,
+    }
+}
+
+/// The enabled rules of a commitlint configuration which concern the message
+#[derive(Default)]
+pub(crate) struct Commitlint {
+    types: Vec<String>,
+    type_case: Option<String>,
+    scopes: Vec<String>,
+    scope_cases: Vec<String>,
+    /// `Some(true)` if a scope is required, `Some(false)` if it's forbidden
+    scope_required: Option<bool>,
+    /// The cases the subject may not be written in
+    forbidden_subject_cases: Vec<String>,
+    subject_full_stop: Option<String>,
+    header_max_length: Option<usize>,
+    body_max_line_length: Option<usize>,
+}
+
+/// The applicability and value of an enabled rule like `[2, "always", 72]`
+fn rule(rules: &Map<String, Value>, name: &str) -> Option<(bool, Value)> {
+    let rule = rules.get(name)?.as_array()?;
+    let level = rule.first()?.as_u64()?;
+    if level == 0 {
+        return None;
+    }
+    let always = rule.get(1).and_then(Value::as_str).unwrap_or("always") == "always";
+    Some((always, rule.get(2).cloned().unwrap_or(Value::Null)))
+}
+
+/// The strings of the value, which is either a string or a list of strings
+fn strings(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(string) => vec![string.clone()],
+        Value::Array(values) => values
+            .iter()
+            .filter_map(|value| value.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+impl Commitlint {
+    /// The allowed types, empty if every type is allowed
+    pub(crate) fn types(&self) -> &[String] {
+        &self.types
+    }
+
+    /// The cases the scope may be written in, empty if every case is allowed
+    pub(crate) fn scope_cases(&self) -> &[String] {
+        &self.scope_cases
+    }
+
+    /// The most characters of the header, `None` if the length isn't limited
+    pub(crate) fn header_max_length(&self) -> Option<usize> {
+        self.header_max_length
+    }
+
+    /// The rules of the configuration, with the rules of its presets overridden by its own
+    fn from_config(config: &Value) -> Self {
+        let mut rules = Map::new();
+        for preset in strings(&config["extends"]) {
+            if let Some(Value::Object(preset_rules)) = preset_rules(&preset) {
+                rules.extend(preset_rules);
+            }
+        }
+        if let Value::Object(own_rules) = &config["rules"] {
+            rules.extend(own_rules.clone());
+        }
+
+        let mut commitlint = Self::default();
+        if let Some((true, value)) = rule(&rules, "type-enum") {
+            commitlint.types = strings(&value);
+        }
+        if let Some((true, value)) = rule(&rules, "type-case") {
+            commitlint.type_case = strings(&value).into_iter().next();
+        }
+        if let Some((true, value)) = rule(&rules, "scope-enum") {
+            commitlint.scopes = strings(&value);
+        }
+        if let Some((true, value)) = rule(&rules, "scope-case") {
+            commitlint.scope_cases = strings(&value);
+        }
+        if let Some((always, _)) = rule(&rules, "scope-empty") {
+            commitlint.scope_required = Some(!always);
+        }
+        if let Some((false, value)) = rule(&rules, "subject-case") {
+            commitlint.forbidden_subject_cases = strings(&value);
+        }
+        if let Some((false, value)) = rule(&rules, "subject-full-stop") {
+            commitlint.subject_full_stop = Some(value.as_str().unwrap_or(".").to_string());
+        }
+        if let Some((true, value)) = rule(&rules, "header-max-length") {
+            commitlint.header_max_length = value.as_u64().map(|length| length as usize);
+        }
+        if let Some((true, value)) = rule(&rules, "body-max-line-length") {
+            commitlint.body_max_line_length = value.as_u64().map(|length| length as usize);
+        }
+        commitlint
+    }
+
+    /// The rules as instructions for the model, one per line
+    pub(crate) fn constraints(&self) -> String {
+        let mut constraints = Vec::new();
+        if !self.types.is_empty() {
+            constraints.push(format!("The type is one of: {}", self.types.join(", ")));
+        }
+        if let Some(case) = &self.type_case {
+            constraints.push(format!("The type is in {case}"));
+        }
+        match self.scope_required {
+            Some(true) => constraints.push("The scope is required".to_string()),
+            Some(false) => constraints.push("There is no scope".to_string()),
+            None => {}
+        }
+        if !self.scopes.is_empty() {
+            constraints.push(format!("The scope is one of: {}", self.scopes.join(", ")));
+        }
+        if !self.scope_cases.is_empty() {
+            constraints.push(format!("The scope is in {}", self.scope_cases.join(" or ")));
+        }
+        if !self.forbidden_subject_cases.is_empty() {
+            constraints.push(format!(
+                "The subject is not in {}",
+                self.forbidden_subject_cases.join(", ")
+            ));
+        }
+        if let Some(full_stop) = &self.subject_full_stop {
+            constraints.push(format!("The subject doesn't end with \"{full_stop}\""));
+        }
+        if let Some(length) = self.header_max_length {
+            constraints.push(format!("The header has at most {length} characters"));
+        }
+        if let Some(length) = self.body_max_line_length {
+            constraints.push(format!(
+                "The lines of the body have at most {length} characters"
+            ));
+        }
+        constraints
+            .iter()
+            .map(|constraint| format!("- {constraint}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The rules the message breaks, empty if it follows all of them
+    pub(crate) fn violations(&self, message: &str) -> Vec<String> {
+        let mut lines = message.lines();
+        let header_line = lines.next().unwrap_or_default();
+        let mut violations = Vec::new();
+        if let Some(length) = self.header_max_length {
+            if header_line.chars().count() > length {
+                violations.push(format!("the header is longer than {length} characters"));
+            }
+        }
+        if let Some(length) = self.body_max_line_length {
+            if lines.any(|line| line.chars().count() > length) {
+                violations.push(format!(
+                    "a line of the body is longer than {length} characters"
+                ));
+            }
+        }
+        let Some(header) = Header::parse(header_line) else {
+            if !self.types.is_empty() {
+                violations.push("the header isn't like `type(scope): subject`".to_string());
+            }
+            return violations;
+        };
+        if !self.types.is_empty() && !self.types.iter().any(|kind| kind == header.kind) {
+            violations.push(format!("the type `{}` isn't allowed", header.kind));
+        }
+        if let Some(case) = &self.type_case {
+            if !is_case(header.kind, case) {
+                violations.push(format!("the type isn't in {case}"));
+            }
+        }
+        match (self.scope_required, header.scope) {
+            (Some(true), None) => violations.push("the scope is missing".to_string()),
+            (Some(false), Some(_)) => violations.push("the scope isn't allowed".to_string()),
+            _ => {}
+        }
+        if let Some(scope) = header.scope {
+            // Multiple scopes are separated by commas, like in commitlint.
+            let scopes = scope.split([',', '/']).map(str::trim).collect::<Vec<_>>();
+            if !self.scopes.is_empty()
+                && !scopes
+                    .iter()
+                    .all(|scope| self.scopes.iter().any(|allowed| allowed == scope))
+            {
+                violations.push(format!("the scope `{scope}` isn't allowed"));
+            }
+            if !self.scope_cases.is_empty()
+                && !scopes
+                    .iter()
+                    .all(|scope| self.scope_cases.iter().any(|case| is_case(scope, case)))
+            {
+                violations.push(format!(
+                    "the scope isn't in {}",
+                    self.scope_cases.join(" or ")
+                ));
+            }
+        }
+        if let Some(case) = self
+            .forbidden_subject_cases
+            .iter()
+            .find(|case| is_case(header.subject, case))
+        {
+            violations.push(format!("the subject is in {case}"));
+        }
+        if let Some(full_stop) = &self.subject_full_stop {
+            if header.subject.ends_with(full_stop.as_str()) {
+                violations.push(format!("the subject ends with \"{full_stop}\""));
+            }
+        }
+        violations
+    }
+}
+
+/// Orders the suggestions which follow the rules first and warns about the rules the others
+/// break
+pub(crate) fn order_by_commitlint(commitlint: &Commitlint, choices: Vec<String>) -> Vec<String> {
+    let (mut valid, invalid) = choices
+        .into_iter()
+        .map(|choice| {
+            let violations = commitlint.violations(&choice);
+            (choice, violations)
+        })
+        .partition::<Vec<_>, _>(|(_, violations)| violations.is_empty());
+    for (choice, violations) in &invalid {
+        eprintln!(
+            "The suggestion \"{}\" breaks the commitlint rules: {}",
+            choice.lines().next().unwrap_or_default(),
+            violations.join(", ")
+        );
+    }
+    valid.extend(invalid);
+    valid.into_iter().map(|(choice, _)| choice).collect()
+}
+
+impl Cli {
+    /// The commitlint configuration of the repository from `.commitlintrc`, `commitlint.config.js`
+    /// or the `commitlint` field of `package.json`. JavaScript configurations are only understood
+    /// as far as their presets and rules are plain literals. `None` if there is none.
+    pub(crate) fn commitlint(&self) -> Option<Commitlint> {
+        let workdir = self.vcs.workdir().ok()??;
+        let config = CONFIG_FILES
+            .iter()
+            .map(|name| (name, workdir.join(name)))
+            .filter(|(_, path)| path.is_file())
+            .find_map(|(name, path)| read_config(&path, name))
+            .or_else(|| {
+                let package = fs::read_to_string(workdir.join("package.json")).ok()?;
+                let package = serde_json::from_str::<Value>(&package).ok()?;
+                package.get("commitlint").cloned()
+            })?;
+        Some(Commitlint::from_config(&config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_js_literals() {
+        assert_eq!(
+            js_literal("[2, 'always', ['feat', \"fix\", `docs`,],\n],\n'type-case': [2]"),
+            Some(json!([2, "always", ["feat", "fix", "docs"]]))
+        );
+        assert_eq!(js_literal("['a]b', 'c,'] rest"), Some(json!(["a]b", "c,"])));
+        assert_eq!(js_literal("{ level: 2 }"), None);
+        assert_eq!(js_literal("rules"), None);
+    }
+
+    #[test]
+    fn overrides_the_preset_with_its_own_rules() {
+        let commitlint = Commitlint::from_config(&json!({
+            "extends": ["@commitlint/config-conventional"],
+            "rules": {
+                "type-enum": [2, "always", ["feat", "fix"]],
+                "header-max-length": [2, "always", 72],
+                "subject-full-stop": [0],
+            },
+        }));
+        assert_eq!(commitlint.types(), ["feat", "fix"]);
+        assert_eq!(commitlint.header_max_length(), Some(72));
+        assert_eq!(commitlint.subject_full_stop, None);
+        assert_eq!(commitlint.scope_cases(), ["lower-case"]);
+        assert_eq!(commitlint.body_max_line_length, Some(100));
+    }
+
+    #[test]
+    fn reports_violations() {
+        let commitlint = Commitlint::from_config(&json!({
+            "extends": "@commitlint/config-conventional",
+            "rules": { "scope-enum": [2, "always", ["api", "cli"]] },
+        }));
+        assert!(commitlint.violations("feat(api,cli): add x").is_empty());
+        assert!(commitlint.violations("feat(api/cli): add x").is_empty());
+        assert_eq!(
+            commitlint.violations("feat(api,web): add x"),
+            ["the scope `api,web` isn't allowed"]
+        );
+        assert_eq!(
+            commitlint.violations("fix: Handle x."),
+            [
+                "the subject is in sentence-case",
+                "the subject ends with \".\""
+            ]
+        );
+    }
+}
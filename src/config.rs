@@ -1,51 +1,516 @@
-use std::path::PathBuf;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use serde::Deserialize;
-use serde_valid::Validate;
+use serde_valid::{validation::Errors, Validate};
+
+use crate::{
+    budget::LargeDiff,
+    conventions::Convention,
+    gitmoji::GitmojiFormat,
+    history::HistoryFormat,
+    issue::IssueTracker,
+    model::ReasoningEffort,
+    per_file::Grouping,
+    privacy::Privacy,
+    provider::{OpenAiApi, ProviderKind},
+    ticket::TicketPlacement,
+    validate::Validation,
+};
 
 #[derive(Deserialize, Validate)]
 pub(crate) struct Config {
-    /// Your API key from https://platform.openai.com/account/api-keys
-    pub(crate) api_key: String,
+    /// The backend which should be used to generate the commit messages
+    #[serde(default)]
+    pub(crate) provider: ProviderKind,
+
+    /// The API key of the provider, e.g. from https://platform.openai.com/account/api-keys. Not
+    /// needed for Ollama.
+    pub(crate) api_key: Option<String>,
+
+    /// The API which should be used for OpenAI, either `chat_completions` or `responses`
+    #[serde(default)]
+    pub(crate) api: OpenAiApi,
+
+    /// The OpenAI organization the usage should be attributed to
+    pub(crate) organization: Option<String>,
+
+    /// The OpenAI project the usage should be attributed to
+    pub(crate) project: Option<String>,
+
+    /// The URL of the provider's API, e.g. `http://localhost:1234/v1` for an OpenAI-compatible
+    /// server, `http://localhost:11434` for Ollama or `https://<resource>.openai.azure.com` for
+    /// Azure
+    pub(crate) base_url: Option<String>,
+
+    /// The API version which should be used for Azure
+    #[serde(default = "default_api_version")]
+    pub(crate) api_version: String,
+
+    /// The deployment which should be used for Azure if the model has no entry in `deployments`,
+    /// defaults to the name of the model
+    pub(crate) deployment: Option<String>,
+
+    /// The deployments of the Azure resource by model, e.g. `gpt-4 = "my-gpt-4-deployment"`
+    #[serde(default)]
+    pub(crate) deployments: HashMap<String, String>,
+
+    /// The AWS region which should be used for Bedrock, defaults to the region of the AWS config
+    #[cfg(feature = "bedrock")]
+    pub(crate) region: Option<String>,
+
+    /// The AWS profile which should be used for Bedrock, defaults to the default credential chain
+    #[cfg(feature = "bedrock")]
+    pub(crate) aws_profile: Option<String>,
+
+    /// The providers and models which should be tried in order if the provider is unavailable,
+    /// e.g. because of rate limits, server errors or timeouts
+    #[serde(default)]
+    pub(crate) fallback: Vec<FallbackProvider>,
+
+    /// The timeout of a single request in seconds
+    #[validate(minimum = 1)]
+    // serde_valid takes the default for the key in its errors, unless the key is renamed first.
+    #[serde(rename = "timeout", default = "default_timeout")]
+    pub(crate) timeout: u64,
+
+    /// Stream the suggestions and render them while they arrive
+    #[serde(default)]
+    pub(crate) stream: bool,
+
+    /// Request the commit message as structured output (type, scope, subject, body and breaking)
+    /// and render it locally
+    #[serde(default)]
+    pub(crate) structured: bool,
 
     /// The given context to let ChatGPT know what he should do with the git diff
     #[serde(default = "default_context_prefix")]
     pub(crate) context_prefix: String,
 
+    /// What the project is about, e.g. "This repo is a CAN-bus firmware for coffee machines",
+    /// which is prepended to the system prompt, so the changes are described in the terms of
+    /// the domain
+    pub(crate) project_description: Option<String>,
+
+    /// The most characters of the subject line, e.g. 50 for the strict git convention. If every
+    /// suggestion is longer, the model is asked once more for shorter ones. 0 disables the limit.
+    #[serde(default = "default_max_subject_length")]
+    pub(crate) max_subject_length: usize,
+
+    /// What happens with suggestions which don't follow conventional commits, i.e. the allowed
+    /// types, the case of the scope, the length of the header and the format of the footers:
+    /// `off`, `fix` what can be fixed and drop the others, or `drop` them
+    #[serde(default)]
+    pub(crate) validation: Validation,
+
+    /// The allowed types of the validation, defaults to the types of commitlint or the common
+    /// conventional commit types
+    #[serde(default)]
+    pub(crate) types: Vec<String>,
+
+    /// The case of the scope for the validation, e.g. `lower-case` or `kebab-case`, defaults to
+    /// the case of commitlint
+    pub(crate) scope_case: Option<String>,
+
+    /// Phrases which the messages mustn't contain, e.g. `fix stuff` or `misc changes`, compared
+    /// as whole words and ignoring the case. Suggestions with them are dropped, and if all
+    /// contain some, new ones are requested.
+    #[serde(default)]
+    pub(crate) banned_phrases: Vec<String>,
+
+    /// Remove the period at the end of the subject line
+    #[serde(default)]
+    pub(crate) strip_subject_period: bool,
+
+    /// The column at which the paragraphs and list items of the body are wrapped, 0 keeps the
+    /// lines of the model as they are
+    #[serde(default = "default_body_width")]
+    pub(crate) body_width: usize,
+
     /// The amount of suggestions ChatGPT should generate
     #[validate(minimum = 1)]
     #[validate(maximum = 10)]
-    #[serde(default = "default_suggestions")]
+    #[serde(rename = "suggestions", default = "default_suggestions")]
     pub(crate) suggestions: u8,
 
+    /// Request more suggestions if some of them are the same or nearly the same, until the
+    /// configured amount of distinct suggestions is reached
+    #[serde(default)]
+    pub(crate) top_up_suggestions: bool,
+
+    /// Send the drafts back to the model to critique and improve them before they're shown,
+    /// which takes a second request but helps with complex diffs
+    #[serde(default)]
+    pub(crate) refine: bool,
+
+    /// Order the suggestions best-first by a score from local heuristics, i.e. the compliance
+    /// with the convention, the length and mood of the subject, its specificity and the body
+    #[serde(default)]
+    pub(crate) rank_suggestions: bool,
+
     /// Ignore space change and blank lines in the git diff
     #[serde(default = "default_ignore_space")]
     pub(crate) ignore_space: bool,
 
+    /// Detect removed or renamed public functions and types and changed signatures, which require
+    /// the `!` marker and a `BREAKING CHANGE:` footer
+    #[serde(default = "default_detect_breaking_changes")]
+    pub(crate) detect_breaking_changes: bool,
+
+    /// Leave out hunks which only change formatting, e.g. re-indentation or reordered imports,
+    /// and mention the files with such changes instead
+    #[serde(default = "default_strip_formatting")]
+    pub(crate) strip_formatting: bool,
+
+    /// Describe blocks of code which were only moved, e.g. a function to another file, instead
+    /// of sending them as removed and added lines
+    #[serde(default = "default_detect_moves")]
+    pub(crate) detect_moves: bool,
+
+    /// The amount of context lines around every change in the diff, fewer save tokens for huge
+    /// changes and more help with small tricky patches
+    #[serde(default = "default_diff_context")]
+    pub(crate) diff_context: u32,
+
+    /// The pattern of the ticket in the name of the branch, e.g. `PROJ-123` in
+    /// `feature/PROJ-123-add-cache`. The first capture group is the ticket if there is one.
+    #[serde(default = "default_ticket_pattern")]
+    pub(crate) ticket_pattern: String,
+
+    /// Where the ticket of the branch is added to every message, either as `footer`, at the end
+    /// of the `subject` or `off`
+    #[serde(default)]
+    pub(crate) ticket_placement: TicketPlacement,
+
+    /// The issue tracker which the title and description of the issue of `--issue` or the
+    /// ticket of the branch are fetched from, as context for the reasons of the change
+    pub(crate) issue_tracker: Option<IssueTracker>,
+
+    /// The co-authors of every commit, e.g. during a pairing session, either as `Name <email>` or
+    /// a part of the name or email of a previous author or co-author
+    #[serde(default)]
+    pub(crate) co_authors: Vec<String>,
+
+    /// Generate only a subject line and commit it without the selection and editor, like
+    /// `--oneline`
+    #[serde(default)]
+    pub(crate) oneline: bool,
+
+    /// Add a `Signed-off-by` trailer with `user.name` and `user.email` to every message, as
+    /// required by the DCO of many projects
+    #[serde(default)]
+    pub(crate) signoff: bool,
+
+    /// The trailers which are appended to every message as `Key: value`, e.g.
+    /// `Ticket: {{ticket}}`, with the variables `ticket`, `branch`, `author`, `git.<key>` for the
+    /// git configuration and `env.<NAME>` for the environment. A trailer is left out if one of
+    /// its variables has no value.
+    #[serde(default)]
+    pub(crate) trailers: Vec<String>,
+
+    /// Use the touched packages of a monorepo, e.g. the members of a Cargo or pnpm workspace, as
+    /// scope of the commit message
+    #[serde(default = "default_package_scopes")]
+    pub(crate) package_scopes: bool,
+
+    /// The convention of the commit messages, either `conventional` commits or `gitmoji`
+    #[serde(default)]
+    pub(crate) convention: Convention,
+
+    /// Whether gitmojis are written as `emoji` or as text `code` like `:sparkles:`, e.g. for
+    /// terminals without emoji support
+    #[serde(default)]
+    pub(crate) gitmoji_format: GitmojiFormat,
+
+    /// Derive the scope of the commit message from the changed files and enforce it, if they
+    /// share one. The scope of a file is its entry in `scopes`, its package in a monorepo or its
+    /// top-level directory.
+    #[serde(default)]
+    pub(crate) infer_scope: bool,
+
+    /// The scopes of the files by the prefix of their path, e.g. `"src/provider" = "provider"`,
+    /// the longest prefix wins
+    #[serde(default)]
+    pub(crate) scopes: HashMap<String, String>,
+
+    /// Follow the commit conventions which the repository documents, e.g. in `CONTRIBUTING.md`
+    #[serde(default = "default_repo_conventions")]
+    pub(crate) repo_conventions: bool,
+
+    /// The language of the commit messages, e.g. `de` or `German`, defaults to the language the
+    /// model picks, which is mostly English
+    pub(crate) language: Option<String>,
+
+    /// The template of the user message instead of the built-in one, with the variables
+    /// `{{diff}}`, `{{diffstat}}`, `{{files}}`, `{{branch}}`, `{{reason}}` and `{{author}}`
+    pub(crate) prompt_template: Option<String>,
+
+    /// The amount of the latest commit messages which are sent as examples of the style of the
+    /// repository, none by default
+    #[serde(default)]
+    pub(crate) history_examples: usize,
+
+    /// Whether the examples are only the `subject` lines or the `full` messages
+    #[serde(default)]
+    pub(crate) history_format: HistoryFormat,
+
+    /// Remember the picked suggestions and how they were edited, and send the latest of this
+    /// repository as examples of the personal style
+    #[serde(default)]
+    pub(crate) learn: bool,
+
+    /// The amount of remembered messages which are sent as examples when learning
+    #[serde(default = "default_learned_examples")]
+    pub(crate) learned_examples: usize,
+
+    /// How `--per-file` groups the staged files into commits, either by `file`, `directory` or
+    /// the `package` of a monorepo
+    #[serde(default)]
+    pub(crate) group_by: Grouping,
+
+    /// Record the provider, model and picked suggestion of every commit in a git note in
+    /// `refs/notes/commitgpt`
+    #[serde(default)]
+    pub(crate) notes: bool,
+
+    /// How much of the code may leave the machine, either `full` or `stats`, which sends only
+    /// the changed files with the amount of changed lines and the hunk headers
+    #[serde(default)]
+    pub(crate) privacy: Privacy,
+
+    /// Replace the directories of the changed files with placeholders like `dir-1` before the
+    /// prompt is sent, the suggestions contain the original directories again
+    #[serde(default)]
+    pub(crate) anonymize_paths: bool,
+
+    /// Internal names, e.g. of projects or customers, which are replaced with placeholders like
+    /// `name-1` before the prompt is sent
+    #[serde(default)]
+    pub(crate) anonymize_names: Vec<String>,
+
+    /// Send the changes of lockfiles like `Cargo.lock` and generated files, instead of a note
+    /// that they're updated
+    #[serde(default)]
+    pub(crate) include_lockfiles: bool,
+
+    /// List the untracked files with a short preview in the prompt, as context about new files
+    #[serde(default)]
+    pub(crate) include_untracked: bool,
+
+    /// The similarity in percent from which a deleted and an added file are shown as rename, 0
+    /// disables the detection
+    #[validate(maximum = 100)]
+    #[serde(rename = "rename_threshold", default = "default_similarity_threshold")]
+    pub(crate) rename_threshold: u8,
+
+    /// The similarity in percent from which an added file is shown as copy of a modified file,
+    /// 0 disables the detection
+    #[validate(maximum = 100)]
+    #[serde(rename = "copy_threshold", default = "default_similarity_threshold")]
+    pub(crate) copy_threshold: u8,
+
     /// The maximum amount of token which should be used for ChatGPT
     #[validate(minimum = 1)]
     #[validate(maximum = 128000)]
-    #[serde(default = "default_tokens")]
+    #[serde(rename = "max_tokens", default = "default_tokens")]
     pub(crate) max_tokens: u64,
 
-    /// The model which should be used for ChatGPT
-    /// The model which should be used for ChatGPT
-    #[serde(default = "default_model")]
-    pub(crate) model: String,
+    /// The sampling temperature between 0 and 2, lower values give more focused and
+    /// deterministic messages, defaults to the one of the provider
+    #[validate(minimum = 0.0)]
+    #[validate(maximum = 2.0)]
+    pub(crate) temperature: Option<f32>,
+
+    /// The lowest and highest temperature of the suggestions, e.g. `[0.2, 1.0]`. Every suggestion
+    /// is requested on its own with a temperature evenly spread across the range, so the
+    /// suggestions frame the change differently.
+    pub(crate) temperature_spread: Option<[f32; 2]>,
+
+    /// The nucleus sampling probability between 0 and 1, defaults to the one of the provider
+    #[validate(minimum = 0.0)]
+    #[validate(maximum = 1.0)]
+    pub(crate) top_p: Option<f32>,
+
+    /// The penalty between -2 and 2 of tokens which already occur in the message, higher
+    /// values make repetitive bodies less likely
+    #[validate(minimum = -2.0)]
+    #[validate(maximum = 2.0)]
+    pub(crate) presence_penalty: Option<f32>,
+
+    /// The penalty between -2 and 2 of tokens by how often they already occur in the message,
+    /// higher values make rambling bodies less likely
+    #[validate(minimum = -2.0)]
+    #[validate(maximum = 2.0)]
+    pub(crate) frequency_penalty: Option<f32>,
+
+    /// The seed of the sampling, so rerunning on the same diff reproduces the same suggestions
+    /// with providers which support it
+    pub(crate) seed: Option<u32>,
+
+    /// The estimated amount of tokens from which the diff is split into parts, which are
+    /// summarized on their own before the commit message is generated from the summaries
+    #[validate(minimum = 100)]
+    #[serde(rename = "max_diff_tokens", default = "default_max_diff_tokens")]
+    pub(crate) max_diff_tokens: u64,
+
+    /// The estimated amount of tokens from which the user is asked whether the diff should be
+    /// sent as a whole, summarized or not at all, 0 never asks
+    #[serde(default = "default_confirm_diff_tokens")]
+    pub(crate) confirm_diff_tokens: u64,
+
+    /// What happens with diffs larger than `max_diff_tokens`, either `summarize` or `truncate`,
+    /// which sends the source files first, then the tests and the documentation and
+    /// configuration last, as far as they fit
+    #[serde(default)]
+    pub(crate) large_diff: LargeDiff,
+
+    /// The amount of lines from which a new file is summarized by its first lines, imports and
+    /// declarations instead of being sent as a whole
+    #[validate(minimum = 1)]
+    #[serde(rename = "max_new_file_lines", default = "default_max_new_file_lines")]
+    pub(crate) max_new_file_lines: usize,
+
+    /// Describe the changes of modified source files by the functions, types and methods which
+    /// were added, removed or modified instead of their hunks
+    #[cfg(feature = "tree-sitter")]
+    #[serde(default)]
+    pub(crate) semantic_diff: bool,
+
+    /// The model which summarizes the parts of a large diff, e.g. a cheaper one. Defaults to the
+    /// model which generates the commit messages.
+    pub(crate) summary_model: Option<String>,
+
+    /// The model which should be used, either a known alias or any model name. Defaults to the
+    /// default model of the provider.
+    pub(crate) model: Option<String>,
+
+    /// Custom aliases for models, e.g. `fast = "gpt-4o-mini"`, usable as `--model fast`
+    #[serde(default)]
+    pub(crate) aliases: HashMap<String, String>,
+
+    /// How much effort reasoning models like o1 or o3 should spend on reasoning
+    pub(crate) reasoning_effort: Option<ReasoningEffort>,
+
+    /// The prefixes suggested branch names start with, e.g. `feat/` or `fix/`
+    #[serde(default = "default_branch_prefixes")]
+    pub(crate) branch_prefixes: Vec<String>,
+}
+
+/// A provider and model which is tried if the previous one is unavailable
+#[derive(Deserialize)]
+pub(crate) struct FallbackProvider {
+    /// The backend which should be used, defaults to the configured provider
+    pub(crate) provider: Option<ProviderKind>,
+
+    /// The model which should be used, defaults to the default model of the provider
+    pub(crate) model: Option<String>,
+
+    /// The API key of the fallback, which is never taken from the configured provider
+    pub(crate) api_key: Option<String>,
+
+    /// The environment variable the API key is read from if there's no `api_key`, e.g.
+    /// `ANTHROPIC_API_KEY`
+    pub(crate) api_key_env: Option<String>,
+
+    /// The URL of the API of the fallback, defaults to the one of the provider
+    pub(crate) base_url: Option<String>,
+}
+
+impl FallbackProvider {
+    /// The API key of the fallback, either configured or read from `api_key_env`
+    pub(crate) fn api_key(&self) -> Option<String> {
+        self.api_key.clone().or_else(|| {
+            self.api_key_env
+                .as_deref()
+                .and_then(|name| std::env::var(name).ok())
+        })
+    }
 }
 
 pub(crate) fn default_suggestions() -> u8 {
     5
 }
 
+pub(crate) fn default_max_subject_length() -> usize {
+    72
+}
+
+pub(crate) fn default_body_width() -> usize {
+    72
+}
+
 pub(crate) fn default_ignore_space() -> bool {
     true
 }
 
+pub(crate) fn default_detect_breaking_changes() -> bool {
+    true
+}
+
+pub(crate) fn default_strip_formatting() -> bool {
+    true
+}
+
+pub(crate) fn default_detect_moves() -> bool {
+    true
+}
+
+pub(crate) fn default_repo_conventions() -> bool {
+    true
+}
+
+pub(crate) fn default_learned_examples() -> usize {
+    5
+}
+
+pub(crate) fn default_package_scopes() -> bool {
+    true
+}
+
+pub(crate) fn default_ticket_pattern() -> String {
+    r"[A-Z][A-Z0-9]+-\d+".to_string()
+}
+
+pub(crate) fn default_diff_context() -> u32 {
+    3
+}
+
+pub(crate) fn default_similarity_threshold() -> u8 {
+    50
+}
+
 pub(crate) fn default_tokens() -> u64 {
     400
 }
 
+pub(crate) fn default_max_diff_tokens() -> u64 {
+    16000
+}
+
+pub(crate) fn default_confirm_diff_tokens() -> u64 {
+    50000
+}
+
+pub(crate) fn default_max_new_file_lines() -> usize {
+    100
+}
+
+pub(crate) fn default_timeout() -> u64 {
+    60
+}
+
+pub(crate) fn default_api_version() -> String {
+    "2024-06-01".to_string()
+}
+
+pub(crate) fn default_branch_prefixes() -> Vec<String> {
+    ["feat/", "fix/", "refactor/", "docs/", "chore/"]
+        .map(str::to_string)
+        .to_vec()
+}
+
 pub(crate) fn default_context_prefix() -> String {
     r#"You are a helpful assistant which helps to write commit messages based on the given diff and reason.
 The first line is explaining why there are specific changes and the other lines describes what have been changed.
@@ -59,11 +524,120 @@ Changes:
         .to_string()
 }
 
-pub(crate) fn default_model() -> String {
-    "gpt-4o-mini".to_string()
+/// The configuration file at the root of the working tree, which is shared with the team
+pub(crate) const REPOSITORY_CONFIG: &str = ".commitgpt.toml";
+
+/// The settings which a repository can't override: the provider and the issue tracker, so a
+/// shared configuration can't redirect the diff or the tokens elsewhere, the profile which might
+/// pick another provider, the trailers which can expand environment variables into the message
+/// and the lockfiles which would be sent
+const GLOBAL_SETTINGS: &[&str] = &[
+    "provider",
+    "api_key",
+    "api",
+    "organization",
+    "project",
+    "base_url",
+    "api_version",
+    "deployment",
+    "deployments",
+    "region",
+    "aws_profile",
+    "fallback",
+    "issue_tracker",
+    "profile",
+    "trailers",
+    "include_lockfiles",
+];
+
+/// The settings of `.commitgpt.toml` in the working tree without the global settings, empty
+/// if there is no such file
+fn repository_settings(
+    workdir: &Path,
+) -> Result<config_reader::Config, config_reader::ConfigError> {
+    let path = workdir.join(REPOSITORY_CONFIG);
+    if !path.is_file() {
+        return Ok(config_reader::Config::default());
+    }
+    let mut settings = config_reader::Config::builder()
+        .add_source(config_reader::File::from(path).format(config_reader::FileFormat::Toml))
+        .build()?
+        .try_deserialize::<HashMap<String, config_reader::Value>>()?;
+    settings.retain(|key, _| {
+        let global_setting = GLOBAL_SETTINGS.contains(&key.as_str());
+        if global_setting {
+            eprintln!(
+                "Ignoring `{key}` of {REPOSITORY_CONFIG}, it can only be configured globally"
+            );
+        }
+        !global_setting
+    });
+    let mut builder = config_reader::Config::builder();
+    for (key, value) in settings {
+        builder = builder.set_override(key, value)?;
+    }
+    builder.build()
 }
 
-pub(crate) async fn read_config() -> Result<Config, crate::Error> {
+/// The settings of the `[profile.<name>]` section of the global configuration
+fn profile_settings(
+    global_settings: &config_reader::Config,
+    profile: &str,
+) -> Result<config_reader::Config, crate::Error> {
+    let settings = global_settings
+        .get_table(&format!("profile.{profile}"))
+        .map_err(|_| crate::Error::UnknownProfile(profile.to_string()))?;
+    let mut builder = config_reader::Config::builder();
+    for (key, value) in settings {
+        builder = builder.set_override(key, value)?;
+    }
+    Ok(builder.build()?)
+}
+
+/// The configuration of the settings, with the ranges of its values checked
+fn validated_config(settings: config_reader::Config) -> Result<Config, crate::Error> {
+    let config = settings.try_deserialize::<Config>()?;
+    config.validate().map_err(|errors| {
+        // The errors of the settings are listed by their keys, e.g. `temperature`.
+        let message = match errors {
+            Errors::Object(errors) => errors
+                .properties
+                .iter()
+                .map(|(key, errors)| match errors {
+                    Errors::NewType(errors) => format!(
+                        "`{key}`: {}",
+                        errors
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    ),
+                    errors => format!("`{key}`: {errors}"),
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            errors => errors.to_string(),
+        };
+        crate::Error::InvalidConfig(message)
+    })?;
+    // The order of the temperatures can't be checked by the attributes.
+    if let Some([lowest, highest]) = config.temperature_spread {
+        if !(0.0 <= lowest && lowest <= highest && highest <= 2.0) {
+            return Err(crate::Error::InvalidConfig(format!(
+                "`temperature_spread`: the lowest and highest temperature must be between 0 and 2, \
+                the lowest first, not [{lowest}, {highest}]"
+            )));
+        }
+    }
+    Ok(config)
+}
+
+/// Reads the global configuration, overridden by the profile and the `.commitgpt.toml` of the
+/// working tree. The profile of the command line wins over the one which is configured.
+pub(crate) async fn read_config(
+    workdir: Option<&Path>,
+    profile: Option<&str>,
+) -> Result<Config, crate::Error> {
     let mut settings_path = if let Ok(xdg_env) = std::env::var("XDG_CONFIG_HOME") {
         PathBuf::from(xdg_env)
     } else {
@@ -73,13 +647,115 @@ pub(crate) async fn read_config() -> Result<Config, crate::Error> {
     };
     settings_path.push("commitgpt/config");
 
-    let settings = config_reader::Config::builder()
+    let repository_settings = match workdir.map(repository_settings).transpose() {
+        Ok(settings) => settings.unwrap_or_default(),
+        Err(err) => {
+            eprintln!("Ignoring the invalid {REPOSITORY_CONFIG}: {err}");
+            config_reader::Config::default()
+        }
+    };
+
+    let global_settings = config_reader::Config::builder()
         .add_source(
             config_reader::File::with_name(settings_path.to_string_lossy().as_ref()).required(true),
         )
+        .build()?;
+    let profile = profile
+        .map(str::to_string)
+        .or_else(|| global_settings.get_string("profile").ok());
+    let profile_settings = match profile {
+        Some(profile) => profile_settings(&global_settings, &profile)?,
+        None => config_reader::Config::default(),
+    };
+
+    let user_settings = config_reader::Config::builder()
+        .add_source(global_settings.clone())
+        .add_source(profile_settings.clone())
+        .add_source(config_reader::Environment::with_prefix("OPENAI"))
+        .build()?;
+    let settings = config_reader::Config::builder()
+        .add_source(global_settings)
+        .add_source(profile_settings)
+        .add_source(repository_settings)
         .add_source(config_reader::Environment::with_prefix("OPENAI"))
         .build()?;
 
-    let config = settings.try_deserialize::<Config>()?;
+    let user_config = validated_config(user_settings)?;
+    let mut config = validated_config(settings)?;
+    // A repository can only make the privacy settings of the user stricter.
+    if user_config.privacy == Privacy::Stats {
+        config.privacy = Privacy::Stats;
+    }
+    config.anonymize_paths |= user_config.anonymize_paths;
+    for name in user_config.anonymize_names {
+        if !config.anonymize_names.contains(&name) {
+            config.anonymize_names.push(name);
+        }
+    }
     Ok(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The validated configuration of the TOML
+    fn config(toml: &str) -> Result<Config, crate::Error> {
+        let settings = config_reader::Config::builder()
+            .add_source(config_reader::File::from_str(
+                toml,
+                config_reader::FileFormat::Toml,
+            ))
+            .build()?;
+        validated_config(settings)
+    }
+
+    #[test]
+    fn checks_the_temperature() {
+        assert!(config("temperature = 0.7").is_ok());
+        assert!(matches!(
+            config("temperature = 2.5"),
+            Err(crate::Error::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn checks_top_p_and_the_penalties() {
+        assert!(config("top_p = 0.9\npresence_penalty = -1.5\nfrequency_penalty = 2.0").is_ok());
+        for toml in [
+            "top_p = 1.5",
+            "presence_penalty = -2.5",
+            "frequency_penalty = 3.0",
+        ] {
+            assert!(
+                matches!(config(toml), Err(crate::Error::InvalidConfig(_))),
+                "{toml}"
+            );
+        }
+    }
+
+    #[test]
+    fn checks_the_temperature_spread() {
+        assert!(config("temperature_spread = [0.2, 1.0]").is_ok());
+        for toml in [
+            "temperature_spread = [1.0, 0.2]",
+            "temperature_spread = [-0.5, 1.0]",
+            "temperature_spread = [0.5, 2.5]",
+        ] {
+            assert!(
+                matches!(config(toml), Err(crate::Error::InvalidConfig(_))),
+                "{toml}"
+            );
+        }
+    }
+
+    #[test]
+    fn names_the_invalid_keys() {
+        match config("max_diff_tokens = 10") {
+            Err(crate::Error::InvalidConfig(message)) => {
+                assert!(message.starts_with("`max_diff_tokens`: "), "{message}")
+            }
+            _ => panic!("expect an invalid config"),
+        }
+    }
+}
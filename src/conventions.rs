@@ -0,0 +1,118 @@
+use std::fs;
+
+use serde::Deserialize;
+
+use crate::{Cli, Error};
+
+/// The convention the commit messages follow
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Convention {
+    /// Conventional commits with a type like `feat` or `fix`, e.g. `feat: add x`
+    #[default]
+    Conventional,
+    /// A gitmoji of https://gitmoji.dev at the start of the subject, e.g. `✨ Add x`
+    Gitmoji,
+}
+
+/// The most characters of the conventions of a single file which are sent
+const MAX_CONVENTION_CHARS: usize = 4000;
+
+/// The documents whose section about commit messages describes the conventions
+const CONTRIBUTING_FILES: &[&str] = &[
+    "CONTRIBUTING.md",
+    ".github/CONTRIBUTING.md",
+    "docs/CONTRIBUTING.md",
+];
+
+/// The files which describe the conventions as a whole
+const CONVENTION_FILES: &[&str] = &[
+    ".gitmessage",
+    "COMMIT_CONVENTION.md",
+    "docs/COMMIT_CONVENTION.md",
+];
+
+/// The level of the Markdown heading, `None` if the line isn't a heading
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    (level > 0 && line[level..].starts_with(' ')).then_some(level)
+}
+
+/// The section of the Markdown document whose heading mentions commits, including its
+/// subsections, `None` if there is none
+fn commit_section(markdown: &str) -> Option<String> {
+    let mut section = Vec::new();
+    let mut level = None;
+    let mut in_code_block = false;
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+        }
+        let heading = heading_level(line).filter(|_| !in_code_block);
+        match (level, heading) {
+            (Some(section_level), Some(heading)) if heading <= section_level => break,
+            (None, Some(heading)) if line.to_lowercase().contains("commit") => {
+                level = Some(heading)
+            }
+            (None, _) => continue,
+            _ => {}
+        }
+        section.push(line);
+    }
+    let section = section.join("\n");
+    (!section.trim().is_empty()).then_some(section)
+}
+
+/// The text shortened to at most `MAX_CONVENTION_CHARS` at the boundary of a line
+fn shorten(text: &str) -> &str {
+    if text.len() <= MAX_CONVENTION_CHARS {
+        return text;
+    }
+    let mut end = MAX_CONVENTION_CHARS;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let end = text[..end].rfind('\n').unwrap_or(end);
+    &text[..end]
+}
+
+impl Cli {
+    /// The configured convention of the commit messages
+    pub(crate) fn convention(&self) -> Convention {
+        self.args.convention.unwrap_or(self.config.convention)
+    }
+
+    /// The commit conventions which the repository documents, e.g. the section about commit
+    /// messages of `CONTRIBUTING.md`, `.gitmessage` or `docs/COMMIT_CONVENTION.md`. `None` if it
+    /// documents none or repository conventions are disabled.
+    pub(crate) fn repo_conventions(&self) -> Result<Option<String>, Error> {
+        if !self.config.repo_conventions {
+            return Ok(None);
+        }
+        let Some(workdir) = self.vcs.workdir()? else {
+            return Ok(None);
+        };
+        // The commit template is already part of the prompt.
+        let template = self.commit_template()?;
+        let mut conventions = Vec::new();
+        for (files, whole) in [(CONVENTION_FILES, true), (CONTRIBUTING_FILES, false)] {
+            for file in files {
+                let Ok(content) = fs::read_to_string(workdir.join(file)) else {
+                    continue;
+                };
+                if template.as_deref() == Some(content.as_str()) {
+                    continue;
+                }
+                let convention = if whole {
+                    Some(content)
+                } else {
+                    commit_section(&content)
+                };
+                if let Some(convention) = convention.filter(|text| !text.trim().is_empty()) {
+                    conventions.push(format!("From {file}:\n{}", shorten(convention.trim())));
+                }
+            }
+        }
+        Ok((!conventions.is_empty()).then(|| conventions.join("\n\n")))
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+
+use crate::{render_response, Cli, CompletionRequest, Error};
+
+/// The share of common words from which two suggestions are considered the same
+const SIMILARITY_THRESHOLD: f64 = 0.8;
+
+/// The most additional requests for suggestions to reach the configured amount
+const MAX_TOP_UP_ROUNDS: usize = 2;
+
+/// The words of the message in lowercase, without punctuation
+fn words(message: &str) -> HashSet<String> {
+    message
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Whether the messages are the same apart from case, punctuation, whitespace and a few words,
+/// i.e. they share most of their words
+fn is_near_duplicate(message: &str, other: &str) -> bool {
+    let (words, other_words) = (words(message), words(other));
+    let union = words.union(&other_words).count();
+    if union == 0 {
+        return true;
+    }
+    let common = words.intersection(&other_words).count();
+    common as f64 / union as f64 >= SIMILARITY_THRESHOLD
+}
+
+/// The suggestions without the ones which are nearly the same as an earlier one, compared by
+/// the key of the suggestion
+fn distinct_by(choices: Vec<String>, key: impl Fn(&str) -> String) -> Vec<String> {
+    let mut distinct = Vec::<(String, String)>::new();
+    for choice in choices {
+        let choice_key = key(&choice);
+        if !distinct
+            .iter()
+            .any(|(distinct_key, _)| is_near_duplicate(distinct_key, &choice_key))
+        {
+            distinct.push((choice_key, choice));
+        }
+    }
+    distinct.into_iter().map(|(_, choice)| choice).collect()
+}
+
+/// The suggestions without the ones which are identical or nearly the same as an earlier one
+pub(crate) fn deduplicate(choices: Vec<String>) -> Vec<String> {
+    distinct_by(choices, str::to_string)
+}
+
+impl Cli {
+    /// The responses without duplicates. With `top_up_suggestions`, more responses are requested
+    /// until the requested amount of distinct ones is reached, for at most `MAX_TOP_UP_ROUNDS`.
+    pub(crate) async fn distinct_responses(
+        &self,
+        request: &CompletionRequest,
+        responses: Vec<String>,
+    ) -> Result<Vec<String>, Error> {
+        let key = |response: &str| render_response(response, request.structured);
+        let mut responses = distinct_by(responses, key);
+        if !self.config.top_up_suggestions {
+            return Ok(responses);
+        }
+        for _ in 0..MAX_TOP_UP_ROUNDS {
+            let missing = usize::from(request.suggestions).saturating_sub(responses.len());
+            if missing == 0 {
+                break;
+            }
+            let mut request = request.clone();
+            request.suggestions = missing as u8;
+            responses.extend(self.complete(&request).await?);
+            responses = distinct_by(responses, key);
+        }
+        responses.truncate(request.suggestions.into());
+        Ok(responses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_near_duplicates() {
+        let choices = vec![
+            "fix: handle the empty diff".to_string(),
+            "Fix: handle the empty diff.".to_string(),
+            "feat: add the export of reports".to_string(),
+        ];
+        assert_eq!(
+            deduplicate(choices),
+            [
+                "fix: handle the empty diff",
+                "feat: add the export of reports"
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_different_suggestions() {
+        let choices = vec![
+            "fix: handle the empty diff".to_string(),
+            "fix: reject the empty diff early".to_string(),
+        ];
+        assert_eq!(deduplicate(choices.clone()), choices);
+    }
+
+    #[test]
+    fn treats_messages_without_words_as_the_same() {
+        assert!(is_near_duplicate("", "..."));
+    }
+}
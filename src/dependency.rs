@@ -0,0 +1,302 @@
+use crate::{diff::file_diffs, is_lockfile};
+
+/// A dependency whose version requirement or locked version changed
+struct Bump {
+    name: String,
+    from: String,
+    to: String,
+}
+
+/// Whether the file is a manifest which declares the dependencies of a project
+fn is_manifest(name: &str) -> bool {
+    matches!(
+        name,
+        "Cargo.toml" | "pyproject.toml" | "package.json" | "go.mod" | "Gemfile"
+    ) || (name.starts_with("requirements") && name.ends_with(".txt"))
+}
+
+/// Whether the text looks like a version or version requirement, e.g. `1.2`, `^18.2.0` or
+/// `v1.4.0`
+fn is_version(text: &str) -> bool {
+    text.trim_start_matches(['^', '~', '=', '<', '>', '!', ' ', 'v'])
+        .starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// The version without the operators of its requirement, e.g. `18.2.0` for `^18.2.0`
+fn plain_version(version: &str) -> String {
+    version
+        .trim_start_matches(['^', '~', '=', '<', '>', '!', ' '])
+        .to_string()
+}
+
+/// The text between the first pair of double quotes
+fn quoted(text: &str) -> Option<&str> {
+    let (_, rest) = text.split_once('"')?;
+    rest.split_once('"').map(|(quoted, _)| quoted)
+}
+
+/// Parses the declaration of a dependency in a manifest into its name and version, e.g.
+/// `serde = { version = "1.0" }`, `"react": "^18.2.0"` or `github.com/pkg/errors v0.9.1`
+fn parse_dependency(manifest: &str, line: &str) -> Option<(String, String)> {
+    let line = line.trim().trim_end_matches(',');
+    let (name, version) = match manifest {
+        "Cargo.toml" | "pyproject.toml" => {
+            let (name, value) = line.split_once('=')?;
+            let name = name.trim().trim_matches('"');
+            let value = value.trim();
+            let version = if value.starts_with('{') {
+                quoted(value.split_once("version")?.1)?
+            } else {
+                quoted(value)?
+            };
+            (name, version)
+        }
+        "package.json" => {
+            let (name, version) = line.split_once(':')?;
+            (quoted(name)?, quoted(version)?)
+        }
+        "go.mod" => {
+            let line = line.strip_prefix("require ").unwrap_or(line);
+            let mut parts = line.split_whitespace();
+            (parts.next()?, parts.next()?)
+        }
+        "Gemfile" => {
+            let line = line.strip_prefix("gem ")?;
+            let mut parts = line.split(',');
+            let name = parts.next()?.trim().trim_matches(['"', '\'']);
+            let version = parts.next()?.trim().trim_matches(['"', '\'']);
+            (name, version)
+        }
+        _ => {
+            let position = line.find(['=', '<', '>', '~', '!'])?;
+            (line[..position].trim(), line[position..].trim())
+        }
+    };
+    // The version of the project itself isn't a dependency.
+    if name.is_empty() || name == "version" || !is_version(version) {
+        return None;
+    }
+    Some((name.to_string(), version.to_string()))
+}
+
+/// The changed lines of the diff of a file as removed and added lines, without the file header
+fn changed_lines(file_diff: &str) -> (Vec<&str>, Vec<&str>) {
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    let mut in_hunk = false;
+    for line in file_diff.lines() {
+        if line.starts_with("@@ ") {
+            in_hunk = true;
+        } else if !in_hunk {
+            continue;
+        } else if let Some(line) = line.strip_prefix('-') {
+            removed.push(line);
+        } else if let Some(line) = line.strip_prefix('+') {
+            added.push(line);
+        }
+    }
+    (removed, added)
+}
+
+/// The dependencies whose requirements changed in the manifest. `None` if anything else
+/// changed, e.g. a dependency was added or removed.
+fn manifest_bumps(manifest: &str, file_diff: &str) -> Option<Vec<Bump>> {
+    let (removed, added) = changed_lines(file_diff);
+    let parse = |lines: Vec<&str>| {
+        lines
+            .into_iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| parse_dependency(manifest, line))
+            .collect::<Option<Vec<_>>>()
+    };
+    let removed = parse(removed)?;
+    let added = parse(added)?;
+    if removed.len() != added.len() {
+        return None;
+    }
+    added
+        .into_iter()
+        .map(|(name, to)| {
+            let (_, from) = removed.iter().find(|(removed, _)| *removed == name)?;
+            (*from != to).then(|| Bump {
+                name,
+                from: from.clone(),
+                to,
+            })
+        })
+        .collect()
+}
+
+/// The packages whose locked versions changed in a lockfile with `name = "…"` and
+/// `version = "…"` entries, like `Cargo.lock`, `poetry.lock` or `uv.lock`
+fn lockfile_bumps(file_diff: &str) -> Vec<Bump> {
+    let mut bumps = Vec::new();
+    let mut name = None;
+    let mut from = None;
+    for line in file_diff.lines() {
+        let (marker, content) = line.split_at(line.len().min(1));
+        let content = content.trim();
+        if let Some(value) = content.strip_prefix("name = ") {
+            name = quoted(value).map(str::to_string);
+            from = None;
+        } else if let Some(value) = content.strip_prefix("version = ") {
+            match (marker, &name) {
+                ("-", Some(_)) => from = quoted(value).map(str::to_string),
+                ("+", Some(name)) => {
+                    if let (Some(from), Some(to)) = (from.take(), quoted(value)) {
+                        bumps.push(Bump {
+                            name: name.clone(),
+                            from,
+                            to: to.to_string(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    bumps
+}
+
+/// A conventional message for a diff which only bumps dependencies, i.e. it only touches
+/// manifests and lockfiles, so the model doesn't need to be asked. The bumps of the manifests
+/// are described, or the bumps of the lockfiles if no manifest changed, e.g. after
+/// `cargo update`.
+pub(crate) fn dependency_bump_message(diff: &str) -> Option<String> {
+    let files = file_diffs(diff);
+    if files.is_empty() {
+        return None;
+    }
+    let mut manifest_changed = false;
+    let mut bumps = Vec::new();
+    let mut locked_bumps = Vec::new();
+    for file in files {
+        let name = file.path.rsplit('/').next().unwrap_or(&file.path);
+        if is_manifest(name) {
+            manifest_changed = true;
+            bumps.extend(manifest_bumps(name, file.text)?);
+        } else if is_lockfile(&file.path) {
+            locked_bumps.extend(lockfile_bumps(file.text));
+        } else {
+            return None;
+        }
+    }
+    if !manifest_changed {
+        bumps = locked_bumps;
+    }
+    let mut unique = Vec::<Bump>::new();
+    for bump in bumps {
+        if !unique.iter().any(|known| known.name == bump.name) {
+            unique.push(bump);
+        }
+    }
+
+    match unique.as_slice() {
+        [] => None,
+        [bump] => Some(format!(
+            "chore(deps): bump {} from {} to {}",
+            bump.name,
+            plain_version(&bump.from),
+            plain_version(&bump.to)
+        )),
+        bumps => Some(format!(
+            "chore(deps): bump {} dependencies\n\n{}",
+            bumps.len(),
+            bumps
+                .iter()
+                .map(|bump| format!(
+                    "- Bump {} from {} to {}",
+                    bump.name,
+                    plain_version(&bump.from),
+                    plain_version(&bump.to)
+                ))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The diff of a file with a single hunk of the changed lines
+    fn file_diff(path: &str, lines: &str) -> String {
+        format!(
+            "diff --git a/{path} b/{path}\n--- a/{path}\n+++ b/{path}\n@@ -1,3 +1,3 @@\n{lines}"
+        )
+    }
+
+    #[test]
+    fn describes_a_single_bump() {
+        let diff = file_diff(
+            "Cargo.toml",
+            " [dependencies]\n-serde = { version = \"1.0.197\", features = [\"derive\"] }\n+serde = { version = \"1.0.203\", features = [\"derive\"] }\n",
+        );
+        assert_eq!(
+            dependency_bump_message(&diff).as_deref(),
+            Some("chore(deps): bump serde from 1.0.197 to 1.0.203")
+        );
+    }
+
+    #[test]
+    fn lists_several_bumps_without_the_requirement_operators() {
+        let diff = file_diff(
+            "web/package.json",
+            "-    \"react\": \"^18.2.0\",\n-    \"vite\": \"~5.0.0\"\n+    \"react\": \"^18.3.1\",\n+    \"vite\": \"~5.2.0\"\n",
+        );
+        assert_eq!(
+            dependency_bump_message(&diff).as_deref(),
+            Some("chore(deps): bump 2 dependencies\n\n- Bump react from 18.2.0 to 18.3.1\n- Bump vite from 5.0.0 to 5.2.0")
+        );
+    }
+
+    #[test]
+    fn describes_the_lockfile_without_a_manifest() {
+        let diff = file_diff(
+            "Cargo.lock",
+            " name = \"serde\"\n-version = \"1.0.197\"\n+version = \"1.0.203\"\n",
+        );
+        assert_eq!(
+            dependency_bump_message(&diff).as_deref(),
+            Some("chore(deps): bump serde from 1.0.197 to 1.0.203")
+        );
+    }
+
+    #[test]
+    fn prefers_the_bumps_of_the_manifest() {
+        let diff = file_diff(
+            "go.mod",
+            "-require github.com/pkg/errors v0.9.0\n+require github.com/pkg/errors v0.9.1\n",
+        ) + &file_diff("go.sum", "-name = \"x\"\n");
+        assert_eq!(
+            dependency_bump_message(&diff).as_deref(),
+            Some("chore(deps): bump github.com/pkg/errors from v0.9.0 to v0.9.1")
+        );
+    }
+
+    #[test]
+    fn ignores_other_changes() {
+        let added = file_diff("Cargo.toml", "+serde = \"1.0\"\n");
+        assert_eq!(dependency_bump_message(&added), None);
+        let version = file_diff("Cargo.toml", "-version = \"0.1.0\"\n+version = \"0.2.0\"\n");
+        assert_eq!(dependency_bump_message(&version), None);
+        let source = file_diff("Cargo.toml", "-serde = \"1.0\"\n+serde = \"1.1\"\n")
+            + &file_diff("src/main.rs", "-a\n+b\n");
+        assert_eq!(dependency_bump_message(&source), None);
+        assert_eq!(dependency_bump_message(""), None);
+    }
+
+    #[test]
+    fn parses_the_declarations_of_the_manifests() {
+        assert_eq!(
+            parse_dependency("requirements.txt", "requests>=2.31"),
+            Some(("requests".to_string(), ">=2.31".to_string()))
+        );
+        assert_eq!(
+            parse_dependency("Gemfile", "gem 'rails', '~> 7.1'"),
+            Some(("rails".to_string(), "~> 7.1".to_string()))
+        );
+        assert_eq!(parse_dependency("package.json", "\"name\": \"app\""), None);
+    }
+}
@@ -0,0 +1,305 @@
+use ignore::gitignore::Gitignore;
+
+use crate::{
+    describe_mode_change, formatting::without_formatting, is_generated, is_ignored, is_lockfile,
+    is_minified, is_vendored, moved::strip_moved_code, outline::Outline, Cli, Error,
+};
+
+/// The first lines of a summarized file which are sent as they are
+const PREVIEW_LINES: usize = 20;
+
+/// The most declarations which are listed for a deleted file
+const MAX_DELETED_DECLARATIONS: usize = 10;
+
+/// The diff of a single file
+pub(crate) struct FileDiff<'a> {
+    /// The path of the file after the change, which is the same as before unless it was renamed
+    pub(crate) path: String,
+    /// The complete diff of the file, starting with its `diff --git` line
+    pub(crate) text: &'a str,
+}
+
+impl FileDiff<'_> {
+    /// The `diff --git` line of the file
+    pub(crate) fn header(&self) -> &str {
+        self.text.lines().next().unwrap_or_default()
+    }
+
+    /// The lines of the extended header before the first hunk, e.g. `new file mode 100644`
+    fn extended_header(&self) -> impl Iterator<Item = &str> {
+        self.text
+            .lines()
+            .skip(1)
+            .take_while(|line| !line.starts_with("@@ "))
+    }
+
+    /// Whether the file was added
+    pub(crate) fn is_new(&self) -> bool {
+        self.extended_header()
+            .any(|line| line.starts_with("new file mode "))
+    }
+
+    /// Whether the file was deleted
+    pub(crate) fn is_deleted(&self) -> bool {
+        self.extended_header()
+            .any(|line| line.starts_with("deleted file mode "))
+    }
+
+    /// The path of the file before it was renamed, `None` if it wasn't renamed
+    pub(crate) fn renamed_from(&self) -> Option<&str> {
+        self.extended_header()
+            .find_map(|line| line.strip_prefix("rename from "))
+    }
+
+    /// Whether the file is binary, i.e. the diff only states that it differs
+    pub(crate) fn is_binary(&self) -> bool {
+        self.text
+            .lines()
+            .any(|line| line.starts_with("Binary files ") || line.starts_with("GIT binary patch"))
+    }
+
+    /// The path with the amount of added and removed lines like `src/main.rs | +3 -1`, as in
+    /// `git diff --stat`
+    pub(crate) fn stat(&self) -> String {
+        if self.is_binary() {
+            return format!("{} | binary", self.path);
+        }
+        format!(
+            "{} | +{} -{}",
+            self.path,
+            self.changed_lines('+').len(),
+            self.changed_lines('-').len()
+        )
+    }
+
+    /// The IDs of the blobs before and after the change from the `index` line, empty if the
+    /// blob is missing, i.e. the file was added or deleted
+    pub(crate) fn blob_ids(&self) -> (&str, &str) {
+        let (old_id, new_id) = self
+            .extended_header()
+            .find_map(|line| line.strip_prefix("index "))
+            .and_then(|ids| ids.split_whitespace().next())
+            .and_then(|ids| ids.split_once(".."))
+            .unwrap_or_default();
+        // The ID of a missing blob consists of zeros only.
+        let present = |id: &'_ str| !id.bytes().all(|byte| byte == b'0');
+        (
+            if present(old_id) { old_id } else { "" },
+            if present(new_id) { new_id } else { "" },
+        )
+    }
+
+    /// The lines of the hunks which start with the marker, i.e. `+` or `-`, without it
+    pub(crate) fn changed_lines(&self, marker: char) -> Vec<&str> {
+        self.text
+            .lines()
+            .skip_while(|line| !line.starts_with("@@ "))
+            .filter_map(|line| line.strip_prefix(marker))
+            .collect()
+    }
+}
+
+/// Splits the diff into the diffs of its files
+pub(crate) fn file_diffs(diff: &str) -> Vec<FileDiff<'_>> {
+    let mut starts = diff
+        .match_indices("diff --git ")
+        .map(|(start, _)| start)
+        .filter(|&start| start == 0 || diff[..start].ends_with('\n'))
+        .collect::<Vec<_>>();
+    starts.push(diff.len());
+    starts
+        .windows(2)
+        .map(|window| {
+            let text = &diff[window[0]..window[1]];
+            let path = text
+                .lines()
+                .next()
+                .and_then(|line| line.split_once(" b/"))
+                .map(|(_, path)| path.to_string())
+                .unwrap_or_default();
+            FileDiff { path, text }
+        })
+        .collect()
+}
+
+/// The changed files with the amount of added and removed lines, like `git diff --stat`,
+/// followed by the totals
+pub(crate) fn diffstat(diff: &str) -> String {
+    let files = file_diffs(diff);
+    let mut stat = String::new();
+    let mut insertions = 0;
+    let mut deletions = 0;
+    for file in &files {
+        if !file.is_binary() {
+            insertions += file.changed_lines('+').len();
+            deletions += file.changed_lines('-').len();
+        }
+        stat.push_str(&format!("{}\n", file.stat()));
+    }
+    let plural = |count: usize| if count == 1 { "" } else { "s" };
+    stat.push_str(&format!(
+        "{} file{} changed, {insertions} insertion{}(+), {deletions} deletion{}(-)\n",
+        files.len(),
+        plural(files.len()),
+        plural(insertions),
+        plural(deletions)
+    ));
+    stat
+}
+
+/// Splits the text before every line which starts with the prefix, keeping the line endings
+pub(crate) fn split_before(text: &str, prefix: &str) -> Vec<String> {
+    let mut parts = Vec::<String>::new();
+    for line in text.split_inclusive('\n') {
+        match parts.last_mut() {
+            Some(part) if !line.starts_with(prefix) => part.push_str(line),
+            _ => parts.push(line.to_string()),
+        }
+    }
+    parts
+}
+
+impl Cli {
+    /// The note which replaces the changes of the file in the prompt, for files in
+    /// `.commitgptignore`, minified and vendored files, as well as lockfiles and generated files
+    /// unless they're included. `None` if the changes are sent.
+    pub(crate) fn omission_note(
+        &self,
+        file: &FileDiff,
+        ignore_file: Option<&Gitignore>,
+    ) -> Option<&'static str> {
+        let include_lockfiles = self.args.include_lockfiles || self.config.include_lockfiles;
+        if ignore_file.is_some_and(|ignore_file| is_ignored(ignore_file, &file.path)) {
+            Some("changes are omitted by .commitgptignore")
+        } else if is_vendored(&file.path)
+            || self
+                .vcs
+                .attribute(&file.path, "linguist-vendored")
+                .unwrap_or(false)
+        {
+            Some("vendored file updated, its changes are omitted")
+        } else if is_minified(&file.path, file.text) {
+            Some("minified file updated, its changes are omitted")
+        } else if include_lockfiles {
+            None
+        } else if is_lockfile(&file.path) {
+            Some("lockfile updated, its changes are omitted")
+        } else if is_generated(file.text) {
+            Some("generated file updated, its changes are omitted")
+        } else {
+            None
+        }
+    }
+
+    /// Reduces the diff to the changes which are worth sending to the model. The changes of
+    /// files in `.commitgptignore`, of minified and vendored files are replaced with a note, as
+    /// well as the changes of lockfiles and generated files, unless they're included. Large new
+    /// files and deleted files are summarized, binary files, mode changes and symlinks are
+    /// described as facts. Modified source files are described by their changed definitions
+    /// with semantic diffs. Hunks which only change formatting are collapsed into a note, as well
+    /// as blocks of code which were only moved.
+    pub(crate) fn prompt_diff(&self, diff: &str) -> Result<String, Error> {
+        let ignore_file = self.ignore_file()?;
+        let (diff, moves) = if self.config.detect_moves {
+            strip_moved_code(diff)
+        } else {
+            (diff.to_string(), Vec::new())
+        };
+        let mut prompt_diff = String::with_capacity(diff.len());
+        // The files whose formatting-only hunks are left out
+        let mut reformatted = Vec::new();
+        for file in file_diffs(&diff) {
+            let replacement = match self.omission_note(&file, ignore_file.as_ref()) {
+                Some(note) => Some(format!("({note})")),
+                None => self
+                    .describe_binary_change(&file)
+                    .or_else(|| describe_mode_change(&file))
+                    .or_else(|| new_file_summary(&file, self.config.max_new_file_lines))
+                    .or_else(|| deleted_file_summary(&file)),
+            };
+            #[cfg(feature = "tree-sitter")]
+            let replacement = replacement.or_else(|| self.semantic_summary(&file));
+            match replacement {
+                Some(replacement) => {
+                    prompt_diff.push_str(&format!("{}\n{replacement}\n", file.header()))
+                }
+                None => match without_formatting(&file).filter(|_| self.config.strip_formatting) {
+                    Some(text) => {
+                        reformatted.push(file.path.clone());
+                        prompt_diff.push_str(&text);
+                    }
+                    None => prompt_diff.push_str(file.text),
+                },
+            }
+        }
+        if !reformatted.is_empty() {
+            prompt_diff.push_str(&format!(
+                "(formatting changes like re-indentation or reordered imports are omitted in {} file{}: {})\n",
+                reformatted.len(),
+                if reformatted.len() == 1 { "" } else { "s" },
+                reformatted.join(", ")
+            ));
+        }
+        if !moves.is_empty() {
+            prompt_diff.push_str(&format!(
+                "(code which was only moved is omitted from the hunks)\n{}\n",
+                moves.join("\n")
+            ));
+        }
+        Ok(prompt_diff)
+    }
+}
+
+/// Summarizes a new file with more than `max_lines` lines by its first lines, imports and
+/// declarations, so it doesn't crowd out the rest of the changes
+fn new_file_summary(file: &FileDiff, max_lines: usize) -> Option<String> {
+    if !file.is_new() {
+        return None;
+    }
+    let lines = file.changed_lines('+');
+    if lines.len() <= max_lines {
+        return None;
+    }
+    let outline = Outline::of(lines.iter().copied());
+    let mut summary = format!(
+        "(new file with {} lines, summarized by its first lines, imports and declarations)\nFirst lines:\n{}\n",
+        lines.len(),
+        lines[..PREVIEW_LINES.min(lines.len())].join("\n")
+    );
+    if !outline.imports.is_empty() {
+        summary.push_str(&format!("Imports:\n{}\n", outline.imports.join("\n")));
+    }
+    if !outline.declarations.is_empty() {
+        summary.push_str(&format!(
+            "Declarations:\n{}\n",
+            outline.declarations.join("\n")
+        ));
+    }
+    Some(summary.trim_end().to_string())
+}
+
+/// Summarizes a deleted file by its amount of lines and declarations, so the model focuses on
+/// what remains instead of the removed content
+fn deleted_file_summary(file: &FileDiff) -> Option<String> {
+    if !file.is_deleted() {
+        return None;
+    }
+    let lines = file.changed_lines('-');
+    let outline = Outline::of(lines.iter().copied());
+    let mut summary = format!("(deleted {} with {} lines", file.path, lines.len());
+    if !outline.declarations.is_empty() {
+        let declarations = &outline.declarations;
+        summary.push_str(&format!(
+            ", which previously contained {}",
+            declarations[..declarations.len().min(MAX_DELETED_DECLARATIONS)].join("; ")
+        ));
+        if declarations.len() > MAX_DELETED_DECLARATIONS {
+            summary.push_str(&format!(
+                " and {} more",
+                declarations.len() - MAX_DELETED_DECLARATIONS
+            ));
+        }
+    }
+    summary.push(')');
+    Some(summary)
+}
@@ -1,10 +1,19 @@
 #[derive(thiserror::Error, Debug)]
 pub(crate) enum Error {
-    #[error("unexpected chat completion error: `{0}`")]
-    ChatCompletionBuilder(#[from] openai::chat::ChatCompletionBuilderError),
+    #[error("aborted, the diff wasn't sent")]
+    Aborted,
+
+    #[error("ambiguous co-author `{0}`, pick one of: {1}")]
+    AmbiguousCoAuthor(String, String),
+
+    #[error("the repository has no working tree, only the changes of existing commits can be described, e.g. with `--range`")]
+    BareRepository,
+
+    #[error("every suggestion contains a banned phrase (\"{0}\"), try again or change the `banned_phrases`")]
+    BannedPhrases(String),
 
     #[error("unable to run command: `{0}`")]
-    CommandError(#[from] std::io::Error),
+    Command(#[from] std::io::Error),
 
     #[error("unable to load config: `{0}`")]
     Config(#[from] config_reader::ConfigError),
@@ -15,15 +24,136 @@ pub(crate) enum Error {
     #[error("couldn't find a suitable selection")]
     EmptySelection,
 
-    #[error("couldn't fetch data, response from openai is not okay: {0}")]
+    #[error("couldn't fetch data, response from the provider is not okay: {0}")]
     FetchData(String),
 
     #[error("unable to parse to utf8: `{0}`")]
     FromUtf8(#[from] std::string::FromUtf8Error),
 
+    #[error("unable to apply the patch")]
+    GitApply,
+
+    #[cfg(feature = "libgit2")]
+    #[error("the arguments for `git commit` require the git command, which isn't installed")]
+    GitArguments,
+
+    #[error("unable to create the branch")]
+    GitBranch,
+
+    #[error("unable to run command 'git check-attr'")]
+    GitCheckAttr,
+
+    #[error("unable to run command 'git config'")]
+    GitConfig,
+
     #[error("unable to run command 'git commit'")]
     GitCommit,
 
     #[error("unable to run command 'git diff'")]
     GitDiff,
+
+    #[cfg(feature = "libgit2")]
+    #[error("unable to access the repository: `{0}`")]
+    Git2(#[from] git2::Error),
+
+    #[error("unable to update the index")]
+    GitIndex,
+
+    #[error("unable to run command 'git log'")]
+    GitLog,
+
+    #[error("unable to run command 'git notes'")]
+    GitNotes,
+
+    #[error("unable to run command 'git rev-parse'")]
+    GitRevParse,
+
+    #[error("unable to reword the commits")]
+    GitReword,
+
+    #[error("the range `{0}` doesn't end at `HEAD`, only the commits of the current branch can be rewritten")]
+    RangeNotAtHead(String),
+
+    #[error("unable to run command 'git status'")]
+    GitStatus,
+
+    #[error("there is already a hook at `{}`, which prevents chaining the existing hook", .0.display())]
+    HookExists(std::path::PathBuf),
+
+    #[error("the hooks aren't installed by commitgpt")]
+    HookNotInstalled,
+
+    #[error("unable to send request: `{0}`")]
+    Http(#[from] reqwest::Error),
+
+    #[error("unable to read `.commitgptignore`: `{0}`")]
+    Ignore(#[from] ignore::Error),
+
+    #[error("invalid config: {0}")]
+    InvalidConfig(String),
+
+    #[error(
+        "invalid trailer `{0}` in the config, use `Key: value` like `Reviewed-by: {{{{author}}}}`"
+    )]
+    InvalidTrailer(String),
+
+    #[error("unable to run command 'jj {0}'")]
+    Jujutsu(&'static str),
+
+    #[error("the arguments for `git commit` aren't supported with Jujutsu, run `jj describe` with them instead")]
+    JujutsuArguments,
+
+    #[error("unable to parse the response: `{0}`")]
+    Json(#[from] serde_json::Error),
+
+    #[error("missing `api_key` in the config, it's required for the selected provider")]
+    MissingApiKey,
+
+    #[error("missing `api_key`, `api_key_env` or `base_url` of the fallback {0} in the config, fallbacks don't use the credentials of the provider")]
+    MissingFallbackCredentials(usize),
+
+    #[error("missing `base_url` in the config, it's required for the selected provider")]
+    MissingBaseUrl,
+
+    #[error("missing `user.name` or `user.email` in the git config, they're required to sign off")]
+    MissingIdentity,
+
+    #[error("Jujutsu has no staging area, split the changes with `jj split` instead")]
+    NoStagingArea,
+
+    #[error("none of the suggestions follows the convention, try again or lower the `validation`")]
+    NoValidSuggestion,
+
+    #[error("not a git repository")]
+    NotARepository,
+
+    #[error("invalid `ticket_pattern` in the config: {0}")]
+    TicketPattern(#[from] regex::Error),
+
+    #[error("the provider is currently unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("unknown co-author `{0}`, neither an author nor a co-author of the recent commits, use `Name <email>` instead")]
+    UnknownCoAuthor(String),
+
+    #[error("unknown profile `{0}`, add it as `[profile.{0}]` to the configuration file")]
+    UnknownProfile(String),
+
+    #[error("unknown variable `{0}` in the prompt template, the available variables are {}", crate::prompt_template::TEMPLATE_VARIABLES.join(", "))]
+    UnknownTemplateVariable(String),
+
+    #[error("unknown variable `{0}` in the trailers, the available variables are {}, `git.<key>` and `env.<NAME>`", crate::trailer::TRAILER_VARIABLES.join(", "))]
+    UnknownTrailerVariable(String),
+}
+
+impl Error {
+    /// Whether the request might succeed with another provider, e.g. on rate limits, server
+    /// errors or timeouts
+    pub(crate) fn is_retryable(&self) -> bool {
+        match self {
+            Error::Unavailable(_) => true,
+            Error::Http(error) => error.is_timeout() || error.is_connect(),
+            _ => false,
+        }
+    }
 }
@@ -0,0 +1,61 @@
+use crate::{
+    diff::{split_before, FileDiff},
+    outline::is_import,
+};
+
+/// The trimmed non-blank lines in sorted order, `None` if any of them isn't an import
+fn sorted_imports<'a>(lines: &[&'a str]) -> Option<Vec<&'a str>> {
+    let mut imports = lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    imports.sort_unstable();
+    imports
+        .iter()
+        .all(|line| is_import(line))
+        .then_some(imports)
+}
+
+/// Whether the hunk only changes formatting, i.e. its lines are the same without whitespace,
+/// e.g. re-indented or rewrapped code and blank lines, or it only reorders imports
+fn is_formatting_only(hunk: &str) -> bool {
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    for line in hunk.lines().skip(1) {
+        if let Some(line) = line.strip_prefix('-') {
+            removed.push(line);
+        } else if let Some(line) = line.strip_prefix('+') {
+            added.push(line);
+        }
+    }
+    if removed.is_empty() && added.is_empty() {
+        return false;
+    }
+    let without_whitespace = |lines: &[&str]| {
+        lines
+            .iter()
+            .flat_map(|line| line.chars())
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+    };
+    if without_whitespace(&removed) == without_whitespace(&added) {
+        return true;
+    }
+    sorted_imports(&removed).is_some_and(|imports| Some(imports) == sorted_imports(&added))
+}
+
+/// The diff of the file without its hunks which only change formatting, `None` if it has no
+/// such hunks. Empty if nothing but formatting changed and the file wasn't renamed.
+pub(crate) fn without_formatting(file: &FileDiff) -> Option<String> {
+    let mut parts = split_before(file.text, "@@ ").into_iter();
+    let header = parts.next().unwrap_or_default();
+    let (formatting, hunks) = parts.partition::<Vec<_>, _>(|hunk| is_formatting_only(hunk));
+    if formatting.is_empty() {
+        return None;
+    }
+    if hunks.is_empty() && file.renamed_from().is_none() {
+        return Some(String::new());
+    }
+    Some(header + &hunks.concat())
+}
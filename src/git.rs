@@ -0,0 +1,15 @@
+use std::{path::PathBuf, process::Command};
+
+use crate::Error;
+
+/// Resolves a path inside the git directory, e.g. `hooks` or `MERGE_MSG`, honoring linked
+/// worktrees and `core.hooksPath`
+pub(crate) fn git_path(path: &str) -> Result<PathBuf, Error> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-path", path])
+        .output()?;
+    if !output.status.success() {
+        return Err(Error::GitRevParse);
+    }
+    Ok(PathBuf::from(String::from_utf8(output.stdout)?.trim_end()))
+}
@@ -0,0 +1,243 @@
+use serde::Deserialize;
+
+use crate::{conventions::Convention, Cli};
+
+/// The gitmojis of https://gitmoji.dev with their code and meaning
+const GITMOJIS: &[(&str, &str, &str)] = &[
+    ("🎨", ":art:", "Improve structure / format of the code"),
+    ("⚡️", ":zap:", "Improve performance"),
+    ("🔥", ":fire:", "Remove code or files"),
+    ("🐛", ":bug:", "Fix a bug"),
+    ("🚑️", ":ambulance:", "Critical hotfix"),
+    ("✨", ":sparkles:", "Introduce new features"),
+    ("📝", ":memo:", "Add or update documentation"),
+    ("🚀", ":rocket:", "Deploy stuff"),
+    ("💄", ":lipstick:", "Add or update the UI and style files"),
+    ("🎉", ":tada:", "Begin a project"),
+    ("✅", ":white_check_mark:", "Add, update, or pass tests"),
+    ("🔒️", ":lock:", "Fix security or privacy issues"),
+    ("🔐", ":closed_lock_with_key:", "Add or update secrets"),
+    ("🔖", ":bookmark:", "Release / Version tags"),
+    ("🚨", ":rotating_light:", "Fix compiler / linter warnings"),
+    ("🚧", ":construction:", "Work in progress"),
+    ("💚", ":green_heart:", "Fix CI Build"),
+    ("⬇️", ":arrow_down:", "Downgrade dependencies"),
+    ("⬆️", ":arrow_up:", "Upgrade dependencies"),
+    ("📌", ":pushpin:", "Pin dependencies to specific versions"),
+    (
+        "👷",
+        ":construction_worker:",
+        "Add or update CI build system",
+    ),
+    (
+        "📈",
+        ":chart_with_upwards_trend:",
+        "Add or update analytics or track code",
+    ),
+    ("♻️", ":recycle:", "Refactor code"),
+    ("➕", ":heavy_plus_sign:", "Add a dependency"),
+    ("➖", ":heavy_minus_sign:", "Remove a dependency"),
+    ("🔧", ":wrench:", "Add or update configuration files"),
+    ("🔨", ":hammer:", "Add or update development scripts"),
+    (
+        "🌐",
+        ":globe_with_meridians:",
+        "Internationalization and localization",
+    ),
+    ("✏️", ":pencil2:", "Fix typos"),
+    ("💩", ":poop:", "Write bad code that needs to be improved"),
+    ("⏪️", ":rewind:", "Revert changes"),
+    ("🔀", ":twisted_rightwards_arrows:", "Merge branches"),
+    (
+        "📦️",
+        ":package:",
+        "Add or update compiled files or packages",
+    ),
+    ("👽️", ":alien:", "Update code due to external API changes"),
+    (
+        "🚚",
+        ":truck:",
+        "Move or rename resources (e.g.: files, paths, routes)",
+    ),
+    ("📄", ":page_facing_up:", "Add or update license"),
+    ("💥", ":boom:", "Introduce breaking changes"),
+    ("🍱", ":bento:", "Add or update assets"),
+    ("♿️", ":wheelchair:", "Improve accessibility"),
+    ("💡", ":bulb:", "Add or update comments in source code"),
+    ("🍻", ":beers:", "Write code drunkenly"),
+    ("💬", ":speech_balloon:", "Add or update text and literals"),
+    ("🗃️", ":card_file_box:", "Perform database related changes"),
+    ("🔊", ":loud_sound:", "Add or update logs"),
+    ("🔇", ":mute:", "Remove logs"),
+    (
+        "👥",
+        ":busts_in_silhouette:",
+        "Add or update contributor(s)",
+    ),
+    (
+        "🚸",
+        ":children_crossing:",
+        "Improve user experience / usability",
+    ),
+    (
+        "🏗️",
+        ":building_construction:",
+        "Make architectural changes",
+    ),
+    ("📱", ":iphone:", "Work on responsive design"),
+    ("🤡", ":clown_face:", "Mock things"),
+    ("🥚", ":egg:", "Add or update an easter egg"),
+    ("🙈", ":see_no_evil:", "Add or update a .gitignore file"),
+    ("📸", ":camera_flash:", "Add or update snapshots"),
+    ("⚗️", ":alembic:", "Perform experiments"),
+    ("🔍️", ":mag:", "Improve SEO"),
+    ("🏷️", ":label:", "Add or update types"),
+    ("🌱", ":seedling:", "Add or update seed files"),
+    (
+        "🚩",
+        ":triangular_flag_on_post:",
+        "Add, update, or remove feature flags",
+    ),
+    ("🥅", ":goal_net:", "Catch errors"),
+    ("💫", ":dizzy:", "Add or update animations and transitions"),
+    (
+        "🗑️",
+        ":wastebasket:",
+        "Deprecate code that needs to be cleaned up",
+    ),
+    (
+        "🛂",
+        ":passport_control:",
+        "Work on code related to authorization, roles and permissions",
+    ),
+    (
+        "🩹",
+        ":adhesive_bandage:",
+        "Simple fix for a non-critical issue",
+    ),
+    ("🧐", ":monocle_face:", "Data exploration/inspection"),
+    ("⚰️", ":coffin:", "Remove dead code"),
+    ("🧪", ":test_tube:", "Add a failing test"),
+    ("👔", ":necktie:", "Add or update business logic"),
+    ("🩺", ":stethoscope:", "Add or update healthcheck"),
+    ("🧱", ":bricks:", "Infrastructure related changes"),
+    ("🧑‍💻", ":technologist:", "Improve developer experience"),
+    (
+        "💸",
+        ":money_with_wings:",
+        "Add sponsorships or money related infrastructure",
+    ),
+    (
+        "🧵",
+        ":thread:",
+        "Add or update code related to multithreading or concurrency",
+    ),
+    (
+        "🦺",
+        ":safety_vest:",
+        "Add or update code related to validation",
+    ),
+    ("✈️", ":airplane:", "Improve offline support"),
+];
+
+/// The variation selector which requests the emoji presentation of a character, which models
+/// and terminals often leave out
+const VARIATION_SELECTOR: char = '\u{fe0f}';
+
+/// How the gitmojis are written into the message
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum GitmojiFormat {
+    /// The emoji itself, e.g. `✨`
+    #[default]
+    Emoji,
+    /// The text code, e.g. `:sparkles:`, for terminals and tools without emoji support
+    Code,
+}
+
+/// The emoji and code of the gitmoji the header starts with, either as emoji or as code, and
+/// the rest of the header. `None` if the header doesn't start with a known gitmoji.
+fn leading_gitmoji(header: &str) -> Option<(&'static str, &'static str, &str)> {
+    let header = header.trim_start();
+    GITMOJIS.iter().find_map(|&(emoji, code, _)| {
+        if let Some(rest) = header.strip_prefix(code) {
+            return Some((emoji, code, rest));
+        }
+        let rest = header.strip_prefix(emoji.trim_end_matches(VARIATION_SELECTOR))?;
+        let rest = rest.strip_prefix(VARIATION_SELECTOR).unwrap_or(rest);
+        // A longer emoji which only starts with the same character is another gitmoji.
+        (rest.is_empty() || rest.starts_with(' ')).then_some((emoji, code, rest))
+    })
+}
+
+/// The message with its gitmoji written in the format, `None` if its header doesn't start with
+/// a gitmoji of the official list
+pub(crate) fn normalize_gitmoji(message: &str, format: GitmojiFormat) -> Option<String> {
+    let (header, body) = match message.split_once('\n') {
+        Some((header, body)) => (header, Some(body)),
+        None => (message, None),
+    };
+    let (emoji, code, rest) = leading_gitmoji(header)?;
+    let gitmoji = match format {
+        GitmojiFormat::Emoji => emoji,
+        GitmojiFormat::Code => code,
+    };
+    let mut normalized = format!("{gitmoji} {}", rest.trim_start());
+    if let Some(body) = body {
+        normalized.push('\n');
+        normalized.push_str(body);
+    }
+    Some(normalized)
+}
+
+impl Cli {
+    /// The instruction to start the subject with a gitmoji of the official list, `None` unless
+    /// the gitmoji convention is configured
+    pub(crate) fn gitmoji_instruction(&self) -> Option<String> {
+        if self.convention() != Convention::Gitmoji {
+            return None;
+        }
+        let gitmojis = GITMOJIS
+            .iter()
+            .map(|(emoji, code, description)| format!("{emoji} {code} {description}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Some(format!(
+            "Follow the gitmoji convention instead of conventional commit types: start the \
+            subject with the single gitmoji which fits the change best, followed by a space and \
+            the description, e.g. `✨ Add the export of reports`. Only use a gitmoji of this \
+            list:\n{gitmojis}"
+        ))
+    }
+
+    /// Writes the gitmojis of the suggestions in the configured format and puts the suggestions
+    /// without a valid gitmoji last, with a warning. The suggestions are kept as they are unless
+    /// the gitmoji convention is configured.
+    pub(crate) fn apply_gitmoji(&self, choices: Vec<String>) -> Vec<String> {
+        if self.convention() != Convention::Gitmoji {
+            return choices;
+        }
+        let (mut valid, invalid) = choices
+            .into_iter()
+            .map(
+                |choice| match normalize_gitmoji(&choice, self.config.gitmoji_format) {
+                    Some(normalized) => Ok(normalized),
+                    None => Err(choice),
+                },
+            )
+            .partition::<Vec<_>, _>(Result::is_ok);
+        valid.extend(invalid);
+        valid
+            .into_iter()
+            .map(|choice| {
+                choice.unwrap_or_else(|choice| {
+                    eprintln!(
+                        "The suggestion \"{}\" doesn't start with a gitmoji of https://gitmoji.dev",
+                        choice.lines().next().unwrap_or_default()
+                    );
+                    choice
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,44 @@
+use serde::Deserialize;
+
+use crate::{Cli, Error};
+
+/// How much of the recent commit messages is shown as examples
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum HistoryFormat {
+    /// Only the subject lines
+    #[default]
+    Subject,
+    /// The whole messages with their bodies
+    Full,
+}
+
+impl Cli {
+    /// The last `history_examples` commit messages of the repository as examples of its style,
+    /// `None` if examples are disabled or there are no commits yet
+    pub(crate) fn history_examples(&self) -> Result<Option<String>, Error> {
+        let count = self
+            .args
+            .history_examples
+            .unwrap_or(self.config.history_examples);
+        let commits = self.vcs.recent_commits(count)?;
+        if commits.is_empty() {
+            return Ok(None);
+        }
+        let format = self
+            .args
+            .history_format
+            .unwrap_or(self.config.history_format);
+        let examples = commits
+            .iter()
+            .map(|commit| match format {
+                HistoryFormat::Subject => commit.message.lines().next().unwrap_or_default(),
+                HistoryFormat::Full => commit.message.as_str(),
+            })
+            .collect::<Vec<_>>();
+        Ok(Some(match format {
+            HistoryFormat::Subject => examples.join("\n"),
+            HistoryFormat::Full => examples.join("\n---\n"),
+        }))
+    }
+}
@@ -0,0 +1,172 @@
+use std::{fs, path::Path};
+
+use crate::{
+    augment_prepared_message, git::git_path, note::discard_stashed_note, vcs::Operation, Cli, Error,
+};
+
+/// The hooks installed by commitgpt with the command they call
+const HOOKS: [(&str, &str); 2] = [
+    ("prepare-commit-msg", "hook"),
+    // Records the note of the message written by `prepare-commit-msg`, once the commit exists.
+    ("post-commit", "post-commit"),
+];
+
+/// An existing hook is renamed to its name with this suffix and called before commitgpt
+const CHAINED_SUFFIX: &str = ".local";
+
+/// Identifies the hook installed by commitgpt
+const MARKER: &str = "# Installed by commitgpt";
+
+fn hook_script(hook: &str, command: &str) -> String {
+    format!(
+        r#"#!/bin/sh
+{MARKER}, remove it with `commitgpt uninstall-hook`
+HOOK_DIR=$(dirname "$0")
+if [ -x "$HOOK_DIR/{hook}{CHAINED_SUFFIX}" ]; then
+    "$HOOK_DIR/{hook}{CHAINED_SUFFIX}" "$@" || exit $?
+fi
+commitgpt {command} "$@"
+"#
+    )
+}
+
+/// Installs the `prepare-commit-msg` and `post-commit` hooks into the hooks directory of the
+/// repository, which honors `core.hooksPath`. Existing hooks are kept and called before
+/// commitgpt.
+pub(crate) fn install_hook() -> Result<(), Error> {
+    let hooks = git_path("hooks")?;
+    for (name, command) in HOOKS {
+        let hook = hooks.join(name);
+        let chained_hook = hooks.join(format!("{name}{CHAINED_SUFFIX}"));
+
+        if let Ok(existing) = fs::read_to_string(&hook) {
+            if existing.contains(MARKER) {
+                println!("The hook is already installed at `{}`.", hook.display());
+                continue;
+            }
+            if chained_hook.exists() {
+                return Err(Error::HookExists(chained_hook));
+            }
+            fs::rename(&hook, &chained_hook)?;
+            println!(
+                "Moved the existing hook to `{}`, it's called before commitgpt.",
+                chained_hook.display()
+            );
+        }
+
+        fs::create_dir_all(&hooks)?;
+        fs::write(&hook, hook_script(name, command))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&hook, fs::Permissions::from_mode(0o755))?;
+        }
+        println!("Installed the hook at `{}`.", hook.display());
+    }
+    Ok(())
+}
+
+/// Removes the hooks installed by commitgpt and restores previously existing hooks. Fails if
+/// none of them is installed.
+pub(crate) fn uninstall_hook() -> Result<(), Error> {
+    let hooks = git_path("hooks")?;
+    let mut uninstalled = false;
+    for (name, _) in HOOKS {
+        let hook = hooks.join(name);
+        let chained_hook = hooks.join(format!("{name}{CHAINED_SUFFIX}"));
+
+        match fs::read_to_string(&hook) {
+            Ok(existing) if existing.contains(MARKER) => fs::remove_file(&hook)?,
+            _ => continue,
+        }
+        uninstalled = true;
+        if chained_hook.exists() {
+            fs::rename(&chained_hook, &hook)?;
+            println!("Restored the previous hook at `{}`.", hook.display());
+        }
+    }
+    if !uninstalled {
+        return Err(Error::HookNotInstalled);
+    }
+    println!("Uninstalled the hooks.");
+    Ok(())
+}
+
+impl Cli {
+    /// Prepends a generated commit message to the message file, as git's `prepare-commit-msg`
+    /// hook. Errors are reported but never abort the commit.
+    pub(crate) async fn prepare_commit_msg(
+        &self,
+        file: &Path,
+        source: Option<&str>,
+    ) -> Result<(), Error> {
+        // The note of an aborted commit isn't recorded for the next one.
+        discard_stashed_note()?;
+        // The message has already been given by the user, e.g. with `-m`, `-F` or `-c`.
+        if matches!(source, Some("message" | "squash" | "commit")) {
+            return Ok(());
+        }
+
+        let result = match source {
+            Some("merge") => self.write_merge_msg(file).await,
+            // The generated message is the filled in template.
+            Some("template") => self.write_commit_msg(file, false).await,
+            _ => self.write_commit_msg(file, true).await,
+        };
+        if let Err(error) = result {
+            eprintln!("commitgpt: {error}");
+        }
+        Ok(())
+    }
+
+    /// Writes the first suggestion into the message file, either before its existing content
+    /// (e.g. git's instructions) or in place of it
+    async fn write_commit_msg(&self, file: &Path, keep_existing: bool) -> Result<(), Error> {
+        let diff = self.get_git_diff()?;
+        if diff.is_empty() {
+            return Err(Error::EmptyDiff);
+        }
+
+        let response = self.get_response(diff, None).await?;
+        let message = response.first().ok_or(Error::EmptySelection)?;
+        let existing = if keep_existing {
+            fs::read_to_string(file)?
+        } else {
+            String::new()
+        };
+        fs::write(file, format!("{}\n{existing}", message.trim_end()))?;
+        if self.notes() {
+            self.stash_note(message, response.len())?;
+        }
+        Ok(())
+    }
+
+    /// Adds a description of the merged, picked or reverted changes to the prepared message
+    async fn write_merge_msg(&self, file: &Path) -> Result<(), Error> {
+        let diff = self.get_git_diff()?;
+        if diff.is_empty() {
+            return Err(Error::EmptyDiff);
+        }
+
+        let response = self.get_response(diff, None).await?;
+        let description = response.first().ok_or(Error::EmptySelection)?;
+        let existing = fs::read_to_string(file)?;
+        let operation = self
+            .prepared_message()?
+            .map_or(Operation::Merge, |prepared| prepared.operation);
+        let comment_string = self.comment_string()?;
+        let comment_string = comment_string.as_deref().unwrap_or("#");
+        let message = augment_prepared_message(operation, &existing, description, comment_string);
+        fs::write(file, &message)?;
+        // Git strips the comments like the list of conflicts from the message.
+        if self.notes() {
+            let message = message
+                .lines()
+                .filter(|line| !line.starts_with(comment_string))
+                .collect::<Vec<_>>()
+                .join("\n");
+            self.stash_note(&message, response.len())?;
+        }
+        Ok(())
+    }
+}
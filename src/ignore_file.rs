@@ -0,0 +1,33 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+use crate::{Cli, Error};
+
+/// The file at the root of the working tree which lists the paths whose changes are never sent
+/// to the model, in the syntax of `.gitignore`
+const IGNORE_FILE: &str = ".commitgptignore";
+
+impl Cli {
+    /// The patterns of the `.commitgptignore` file, if the working tree has one
+    pub(crate) fn ignore_file(&self) -> Result<Option<Gitignore>, Error> {
+        let Some(workdir) = self.vcs.workdir()? else {
+            return Ok(None);
+        };
+        let path = workdir.join(IGNORE_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let mut builder = GitignoreBuilder::new(&workdir);
+        if let Some(error) = builder.add(&path) {
+            return Err(error.into());
+        }
+        Ok(Some(builder.build()?))
+    }
+}
+
+/// Whether the path, relative to the root of the working tree, or one of its parent directories
+/// is ignored
+pub(crate) fn is_ignored(ignore_file: &Gitignore, path: &str) -> bool {
+    ignore_file
+        .matched_path_or_any_parents(path, false)
+        .is_ignore()
+}
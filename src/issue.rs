@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{provider::response_error, Cli, Error};
+
+/// The most characters of the description of an issue which are sent
+const MAX_DESCRIPTION_CHARS: usize = 2000;
+
+/// The kind of an issue tracker
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum IssueTrackerKind {
+    Github,
+    Gitlab,
+    Jira,
+}
+
+/// The issue tracker which the details of the issues are fetched from
+#[derive(Deserialize)]
+pub(crate) struct IssueTracker {
+    /// Either `github`, `gitlab` or `jira`
+    pub(crate) kind: IssueTrackerKind,
+
+    /// The URL of the tracker, defaults to `https://api.github.com` and `https://gitlab.com`, e.g.
+    /// `https://acme.atlassian.net` for Jira
+    pub(crate) url: Option<String>,
+
+    /// The token of the API, optional for public GitHub and GitLab repositories
+    pub(crate) token: Option<String>,
+
+    /// The email address of the token for Jira Cloud, Jira Data Center uses the token alone
+    pub(crate) email: Option<String>,
+
+    /// The repository of GitHub like `owner/name` or the project of GitLab like `group/name`,
+    /// defaults to the repository of the `origin` remote
+    pub(crate) repository: Option<String>,
+}
+
+/// The title and description of an issue
+struct Issue {
+    title: String,
+    description: String,
+}
+
+/// The path of the repository of the remote URL, e.g. `owner/name` for
+/// `git@github.com:owner/name.git` or `https://github.com/owner/name`
+fn repository_of_remote(url: &str) -> Option<String> {
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let url = url.split_once('@').map_or(url, |(_, rest)| rest);
+    let (_, path) = url.split_once([':', '/'])?;
+    let path = path.trim_matches('/').trim_end_matches(".git");
+    // The port of an SSH URL precedes the path.
+    let path = match path.split_once('/') {
+        Some((port, rest)) if port.chars().all(|c| c.is_ascii_digit()) => rest,
+        _ => path,
+    };
+    path.contains('/').then(|| path.to_string())
+}
+
+/// The description shortened to at most `MAX_DESCRIPTION_CHARS` characters
+fn shorten(description: &str) -> String {
+    match description.char_indices().nth(MAX_DESCRIPTION_CHARS) {
+        Some((end, _)) => format!("{}…", &description[..end]),
+        None => description.to_string(),
+    }
+}
+
+impl IssueTracker {
+    /// Fetches the title and description of the issue, `repository` is the path of the
+    /// repository of the `origin` remote
+    async fn fetch(
+        &self,
+        client: &reqwest::Client,
+        id: &str,
+        repository: Option<String>,
+    ) -> Result<Issue, Error> {
+        let repository = || {
+            self.repository
+                .clone()
+                .or(repository.clone())
+                .ok_or_else(|| Error::FetchData("the repository of the issue is unknown".into()))
+        };
+        let number = id.trim_start_matches('#');
+        let request = match self.kind {
+            IssueTrackerKind::Github => {
+                let url = self.url.as_deref().unwrap_or("https://api.github.com");
+                let request = client
+                    .get(format!(
+                        "{}/repos/{}/issues/{number}",
+                        url.trim_end_matches('/'),
+                        repository()?
+                    ))
+                    .header("Accept", "application/vnd.github+json")
+                    .header("User-Agent", "commitgpt");
+                match &self.token {
+                    Some(token) => request.bearer_auth(token),
+                    None => request,
+                }
+            }
+            IssueTrackerKind::Gitlab => {
+                let url = self.url.as_deref().unwrap_or("https://gitlab.com");
+                let request = client.get(format!(
+                    "{}/api/v4/projects/{}/issues/{number}",
+                    url.trim_end_matches('/'),
+                    repository()?.replace('/', "%2F")
+                ));
+                match &self.token {
+                    Some(token) => request.header("PRIVATE-TOKEN", token),
+                    None => request,
+                }
+            }
+            IssueTrackerKind::Jira => {
+                let url = self.url.as_deref().ok_or(Error::MissingBaseUrl)?;
+                let request = client.get(format!(
+                    "{}/rest/api/2/issue/{id}?fields=summary,description",
+                    url.trim_end_matches('/')
+                ));
+                match (&self.email, &self.token) {
+                    (Some(email), Some(token)) => request.basic_auth(email, Some(token)),
+                    (None, Some(token)) => request.bearer_auth(token),
+                    _ => request,
+                }
+            }
+        };
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(response_error(response).await);
+        }
+        let issue = response.json::<Value>().await?;
+        let (title, description) = match self.kind {
+            IssueTrackerKind::Github => (&issue["title"], &issue["body"]),
+            IssueTrackerKind::Gitlab => (&issue["title"], &issue["description"]),
+            IssueTrackerKind::Jira => {
+                (&issue["fields"]["summary"], &issue["fields"]["description"])
+            }
+        };
+        Ok(Issue {
+            title: title.as_str().unwrap_or_default().trim().to_string(),
+            description: shorten(description.as_str().unwrap_or_default().trim()),
+        })
+    }
+}
+
+impl Cli {
+    /// The title and description of the issue of `--issue` or the ticket of the branch, to
+    /// ground the reasons of the change in the issue. `None` if no issue tracker is configured,
+    /// there is no issue or it can't be fetched, which is only warned about.
+    pub(crate) async fn issue_context(&self) -> Result<Option<String>, Error> {
+        let Some(tracker) = &self.config.issue_tracker else {
+            return Ok(None);
+        };
+        let id = match &self.args.issue {
+            Some(issue) => Some(issue.clone()),
+            None => self.ticket()?,
+        };
+        let Some(id) = id else {
+            return Ok(None);
+        };
+        let repository = self
+            .vcs
+            .config("remote.origin.url")?
+            .as_deref()
+            .and_then(repository_of_remote);
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(self.config.timeout))
+            .build()?;
+        match tracker.fetch(&client, &id, repository).await {
+            Ok(issue) => Ok(Some(
+                format!("{id}: {}\n\n{}", issue.title, issue.description)
+                    .trim_end()
+                    .to_string(),
+            )),
+            Err(err) => {
+                eprintln!("Unable to fetch the issue {id}: {err}");
+                Ok(None)
+            }
+        }
+    }
+}
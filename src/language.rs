@@ -0,0 +1,70 @@
+use crate::Cli;
+
+/// The names of common languages by their ISO 639-1 code
+const LANGUAGES: &[(&str, &str)] = &[
+    ("ar", "Arabic"),
+    ("cs", "Czech"),
+    ("da", "Danish"),
+    ("de", "German"),
+    ("el", "Greek"),
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fi", "Finnish"),
+    ("fr", "French"),
+    ("he", "Hebrew"),
+    ("hi", "Hindi"),
+    ("hu", "Hungarian"),
+    ("id", "Indonesian"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("nl", "Dutch"),
+    ("no", "Norwegian"),
+    ("pl", "Polish"),
+    ("pt", "Portuguese"),
+    ("ro", "Romanian"),
+    ("ru", "Russian"),
+    ("sv", "Swedish"),
+    ("th", "Thai"),
+    ("tr", "Turkish"),
+    ("uk", "Ukrainian"),
+    ("vi", "Vietnamese"),
+    ("zh", "Chinese"),
+];
+
+/// The name of the language by its code like `de` or `pt-BR`, other values are kept as they are,
+/// e.g. `German`
+fn language_name(language: &str) -> String {
+    let (code, region) = match language.split_once(['-', '_']) {
+        Some((code, region)) => (code, Some(region)),
+        None => (language, None),
+    };
+    let Some((_, name)) = LANGUAGES
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(code))
+    else {
+        return language.to_string();
+    };
+    match region {
+        Some(region) => format!("{name} ({})", region.to_uppercase()),
+        None => name.to_string(),
+    }
+}
+
+impl Cli {
+    /// The instruction to write the message in the configured language, `None` if no language
+    /// is configured
+    pub(crate) fn language_instruction(&self) -> Option<String> {
+        let language = self
+            .args
+            .lang
+            .as_deref()
+            .or(self.config.language.as_deref())?;
+        Some(format!(
+            "Write the commit message in {}, regardless of the language of the code, the diff and \
+            these instructions. Keep the conventional commit types like feat or fix, code \
+            identifiers and trailers as they are.",
+            language_name(language)
+        ))
+    }
+}
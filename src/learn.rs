@@ -0,0 +1,121 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Cli, Error};
+
+/// The most accepted messages which are kept, older ones are forgotten
+const MAX_REMEMBERED: usize = 500;
+
+/// A suggestion which was committed, possibly after editing it
+#[derive(Serialize, Deserialize)]
+struct Accepted {
+    /// The root of the working tree the commit was created in
+    repository: String,
+    /// The seconds since the Unix epoch when it was committed
+    time: u64,
+    suggestion: String,
+    /// The message which was committed, `None` if the suggestion wasn't edited
+    edited: Option<String>,
+}
+
+/// The file in which the accepted messages are remembered, in the data directory of XDG
+fn accepted_path() -> Option<PathBuf> {
+    let data_home = match std::env::var_os("XDG_DATA_HOME") {
+        Some(data_home) => PathBuf::from(data_home),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".local/share"),
+    };
+    Some(data_home.join("commitgpt/accepted.jsonl"))
+}
+
+/// The accepted messages which are remembered, oldest first
+fn read_accepted(path: &PathBuf) -> Vec<Accepted> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+impl Cli {
+    /// The root of the working tree, which identifies the repository of the accepted messages
+    fn repository(&self) -> Result<Option<String>, Error> {
+        // The backends differ in the trailing slash.
+        Ok(self
+            .vcs
+            .workdir()?
+            .map(|workdir| workdir.to_string_lossy().trim_end_matches('/').to_string()))
+    }
+
+    /// Remembers the suggestion of the commit which was just created and how it was edited, if
+    /// learning is enabled
+    pub(crate) fn remember_accepted(&self, suggestion: &str) -> Result<(), Error> {
+        if !self.config.learn {
+            return Ok(());
+        }
+        let (Some(path), Some(repository)) = (accepted_path(), self.repository()?) else {
+            return Ok(());
+        };
+        let committed = self.vcs.last_message()?;
+        let accepted = Accepted {
+            repository,
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+            suggestion: suggestion.trim().to_string(),
+            edited: (committed.trim() != suggestion.trim()).then(|| committed.trim().to_string()),
+        };
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let mut remembered = read_accepted(&path);
+        if remembered.len() >= MAX_REMEMBERED {
+            remembered.drain(..=remembered.len() - MAX_REMEMBERED);
+            remembered.push(accepted);
+            let lines = remembered
+                .iter()
+                .map(serde_json::to_string)
+                .collect::<Result<Vec<_>, _>>()?;
+            fs::write(&path, lines.join("\n") + "\n")?;
+        } else {
+            let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+            writeln!(file, "{}", serde_json::to_string(&accepted)?)?;
+        }
+        Ok(())
+    }
+
+    /// The recent messages which were accepted in this repository as examples of the personal
+    /// style, the edited ones first since they show what was corrected. `None` if learning is
+    /// disabled or nothing was accepted yet.
+    pub(crate) fn learned_examples(&self) -> Result<Option<String>, Error> {
+        if !self.config.learn {
+            return Ok(None);
+        }
+        let (Some(path), Some(repository)) = (accepted_path(), self.repository()?) else {
+            return Ok(None);
+        };
+        let mut accepted = read_accepted(&path)
+            .into_iter()
+            .filter(|accepted| accepted.repository == repository)
+            .collect::<Vec<_>>();
+        accepted.sort_by_key(|accepted| (accepted.edited.is_some(), accepted.time));
+        let examples = accepted
+            .iter()
+            .rev()
+            .take(self.config.learned_examples)
+            .map(|accepted| match &accepted.edited {
+                Some(edited) => {
+                    format!("Suggested:\n{}\nEdited to:\n{edited}", accepted.suggestion)
+                }
+                None => accepted.suggestion.clone(),
+            })
+            .collect::<Vec<_>>();
+        Ok((!examples.is_empty()).then(|| examples.join("\n---\n")))
+    }
+}
@@ -0,0 +1,84 @@
+/// The names of the lockfiles of common package managers, which are updated by tools instead of
+/// being written by hand
+const LOCKFILES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lock",
+    "bun.lockb",
+    "go.sum",
+    "go.work.sum",
+    "Gemfile.lock",
+    "poetry.lock",
+    "Pipfile.lock",
+    "pdm.lock",
+    "uv.lock",
+    "composer.lock",
+    "flake.lock",
+    "Package.resolved",
+    "Podfile.lock",
+    "pubspec.lock",
+    "mix.lock",
+    "packages.lock.json",
+    "gradle.lockfile",
+    "conan.lock",
+];
+
+/// The markers of generated files, which are written by their generators near the top
+const GENERATED_MARKERS: &[&str] = &["@generated", "DO NOT EDIT", "<auto-generated"];
+
+/// The lines at the start of a file diff which are searched for a generated marker
+const GENERATED_MARKER_LINES: usize = 20;
+
+/// Whether the file at the path is a lockfile of a package manager
+pub(crate) fn is_lockfile(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    LOCKFILES.contains(&name)
+}
+
+/// Whether the diff of a file shows that the file is generated, e.g. by a `@generated` or
+/// `Code generated … DO NOT EDIT.` comment at its top
+pub(crate) fn is_generated(file_diff: &str) -> bool {
+    file_diff
+        .lines()
+        .skip_while(|line| !line.starts_with("@@ "))
+        .take(GENERATED_MARKER_LINES)
+        .any(|line| GENERATED_MARKERS.iter().any(|marker| line.contains(marker)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_lockfiles_by_their_name() {
+        assert!(is_lockfile("Cargo.lock"));
+        assert!(is_lockfile("web/package-lock.json"));
+        assert!(!is_lockfile("src/Cargo.lock.rs"));
+        assert!(!is_lockfile("docs/lock.md"));
+    }
+
+    #[test]
+    fn detects_generated_markers_near_the_top() {
+        let diff = "diff --git a/x.go b/x.go\n--- a/x.go\n+++ b/x.go\n@@ -1,2 +1,2 @@\n // Code generated by protoc. DO NOT EDIT.\n-a\n+b\n";
+        assert!(is_generated(diff));
+    }
+
+    #[test]
+    fn ignores_markers_outside_of_the_hunks() {
+        let diff = "diff --git a/DO NOT EDIT b/DO NOT EDIT\n@@ -1 +1 @@\n-a\n+b\n";
+        assert!(!is_generated(diff));
+    }
+
+    #[test]
+    fn ignores_markers_far_from_the_top() {
+        let mut diff = "diff --git a/x b/x\n@@ -1,30 +1,30 @@\n".to_string();
+        for _ in 0..GENERATED_MARKER_LINES {
+            diff.push_str(" line\n");
+        }
+        diff.push_str("+// @generated\n");
+        assert!(!is_generated(&diff));
+    }
+}
@@ -23,43 +23,156 @@
  * and we cannot be held liable for any damage or operating failure.
  */
 use std::{
-    process::{Command, ExitCode},
+    borrow::Cow,
+    fs, io,
+    process::ExitCode,
+    sync::{Mutex, OnceLock},
     time::Duration,
 };
 
 use clap::Parser;
 use dialoguer::{theme::ColorfulTheme, Select};
-use indicatif::ProgressBar;
-use openai::chat::{ChatCompletionBuilder, ChatCompletionMessage, ChatCompletionMessageRole};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 
+mod anonymize;
 mod args;
+mod banned;
+mod binary;
+mod branch;
+mod breaking;
+mod budget;
+mod choose;
+mod co_author;
+mod comment;
+mod commit_type;
+mod commitlint;
 mod config;
+mod conventions;
+mod dedupe;
+mod dependency;
+mod diff;
 mod error;
+mod formatting;
+mod git;
+mod gitmoji;
+mod history;
+mod hook;
+mod ignore_file;
+mod issue;
+mod language;
+mod learn;
+mod lockfile;
+mod merge;
+mod message;
+mod mode;
+mod model;
+mod moved;
+mod note;
+mod oneline;
+mod outline;
+mod per_file;
+mod preview;
+mod privacy;
+mod prompt_template;
+mod provider;
+mod rank;
+mod refine;
+mod reword;
+mod sanitize;
+mod scope;
+#[cfg(feature = "tree-sitter")]
+mod semantic;
+mod split;
+mod spread;
+mod stack;
+mod subject;
+mod submodule;
+mod summary;
+mod template;
+mod ticket;
+mod trailer;
+mod untracked;
+mod validate;
+mod vcs;
+mod vendored;
+mod workspace;
+mod wrap;
 
 use args::*;
+use banned::*;
+use breaking::*;
+use budget::*;
+use comment::*;
+use commit_type::*;
+use commitlint::*;
 use config::*;
+use conventions::Convention;
+use dedupe::*;
+use dependency::*;
+use diff::{diffstat, file_diffs};
 use error::*;
+use hook::*;
+use ignore_file::*;
+use lockfile::*;
+use merge::*;
+use message::*;
+use mode::*;
+use model::*;
+use oneline::*;
+use privacy::*;
+use prompt_template::*;
+use provider::*;
+use rank::*;
+use sanitize::*;
+use scope::*;
+use subject::*;
+use ticket::*;
+use trailer::*;
+use vcs::*;
+use vendored::*;
+use workspace::*;
+use wrap::*;
 
-fn git_preflight_check() -> Result<(), ExitCode> {
-    let git_command_exists = match Command::new("git").arg("status").status() {
-        Ok(status) => status.success(),
-        Err(_) => false,
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+    // The hook never aborts the commit of the user, e.g. because CommitGPT isn't configured, it
+    // leaves the message as it is instead.
+    let hook = matches!(
+        args.command,
+        Some(Commands::Hook { .. } | Commands::PostCommit)
+    );
+    let failure = if hook {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
     };
-    if !git_command_exists {
-        eprintln!("Git is not installed or you are not in a git repository.");
-        return Err(ExitCode::FAILURE);
+    if let Some(directory) = &args.directory {
+        if let Err(err) = std::env::set_current_dir(directory) {
+            eprintln!("Unable to change into `{}`: {err}", directory.display());
+            return failure;
+        }
     }
-    Ok(())
-}
 
-#[tokio::main]
-async fn main() -> ExitCode {
-    if let Err(code) = git_preflight_check() {
-        return code;
+    // The `post-commit` hook runs after every commit, without notes there's nothing to record
+    // and the config isn't worth loading.
+    if matches!(args.command, Some(Commands::PostCommit)) && !note::has_stashed_note() {
+        return ExitCode::SUCCESS;
     }
 
-    let config = match read_config().await {
+    // The repository is only opened when it's needed, e.g. not for a diff of stdin.
+    let vcs: Box<dyn Vcs> = Box::new(Lazy::default());
+    let workdir = vcs.workdir().ok().flatten();
+    let config = match read_config(workdir.as_deref(), args.profile.as_deref()).await {
         Ok(config) => config,
+        Err(err @ (Error::UnknownProfile(_) | Error::InvalidConfig(_))) => {
+            eprintln!("{err}");
+            return failure;
+        }
+        Err(err) if hook => {
+            eprintln!("commitgpt: missing or invalid config, the message is left as it is: {err}");
+            return failure;
+        }
         Err(_) => {
             eprintln!(
                 r#"
@@ -75,9 +188,18 @@ async fn main() -> ExitCode {
 
 tldr; missing config `~/.config/commitgpt/config.toml`
 ```toml
-# (required) Your API key from https://platform.openai.com/account/api-keys
+# (optional) The backend which should be used: "openai", "ollama", "anthropic", "azure",
+# "gemini" or "mistral"
+provider = "openai"
+
+# (required for every provider except Ollama) The API key of the provider
 api_key = "YOUR_OPENAI_API_KEY"
 
+# (optional) The URL of the provider's API, e.g. "http://localhost:1234/v1" for an
+# OpenAI-compatible server like LM Studio or "http://localhost:11434" for Ollama
+# (required for Azure) The resource URL, e.g. "https://<resource>.openai.azure.com"
+# base_url = "http://localhost:11434"
+
 # (optional) The given context to let ChatGPT know what he should do with the git diff
 context_prefix = """{}"""
 
@@ -90,7 +212,7 @@ ignore_space = {}
 # (optional) The maximum amount of token which should be used for ChatGPT
 max_tokens = {}
 
-# (optional) The model which should be used for ChatGPT
+# (optional) The model which should be used, defaults to the provider's default model
 model = "{}"
 ```
 
@@ -116,21 +238,20 @@ If you continue to experience issues, please feel free to reach out to me under:
                 default_suggestions(),
                 default_ignore_space(),
                 default_tokens(),
-                default_model(),
+                ProviderKind::default().default_model(),
             );
-            return ExitCode::FAILURE;
+            return failure;
         }
     };
-    let args = Args::parse();
 
-    if let Err(err) = Cli::new(config, args).run().await {
+    if let Err(err) = Cli::new(config, args, vcs).run().await {
         match err {
             Error::Config(_) => {}
             err => {
                 eprintln!("{err}");
             }
         }
-        return ExitCode::FAILURE;
+        return failure;
     }
     ExitCode::SUCCESS
 }
@@ -138,28 +259,85 @@ If you continue to experience issues, please feel free to reach out to me under:
 struct Cli {
     config: Config,
     args: Args,
+    vcs: Box<dyn Vcs>,
+    /// The prompt of the last request, so it can be shown in the selection
+    prompt: Mutex<Option<String>>,
+    /// The type which was picked for `--type` without a value, so it's only asked once
+    picked_type: OnceLock<String>,
 }
 
 impl Cli {
-    fn new(config: Config, args: Args) -> Self {
-        Self { config, args }
+    fn new(config: Config, args: Args, vcs: Box<dyn Vcs>) -> Self {
+        Self {
+            config,
+            args,
+            vcs,
+            prompt: Mutex::default(),
+            picked_type: OnceLock::new(),
+        }
     }
 
     async fn run(&self) -> Result<(), Error> {
-        openai::set_key(self.config.api_key.clone());
+        match &self.args.command {
+            Some(Commands::Hook { file, source, .. }) => {
+                self.prepare_commit_msg(file, source.as_deref()).await
+            }
+            Some(Commands::PostCommit) => self.add_stashed_note(),
+            Some(Commands::InstallHook) => install_hook(),
+            Some(Commands::UninstallHook) => uninstall_hook(),
+            Some(Commands::Squash { .. }) => self.run_interactive().await,
+            Some(Commands::RewordRange { base }) => self.reword_range(base).await,
+            Some(Commands::Rewrite { range, dry_run }) => self.rewrite(range, *dry_run).await,
+            Some(Commands::Branch { print }) => self.suggest_branch(*print).await,
+            Some(Commands::Split) => self.split().await,
+            None if self.args.stdin => self.run_stdin().await,
+            None if self.args.per_file => self.commit_per_file().await,
+            None if self.oneline() => self.commit_oneline().await,
+            None => self.run_interactive().await,
+        }
+    }
 
+    async fn run_interactive(&self) -> Result<(), Error> {
         let diff = self.get_git_diff()?;
         if diff.is_empty() {
             return Err(Error::EmptyDiff);
         }
 
-        let response = self.get_response(diff).await?;
-        let selection = response
+        let mut patch = self.args.apply.then(|| diff.clone());
+        // Only the description is limited to the chosen files, all changes are committed.
+        let diff = if self.args.choose_files {
+            self.choose_files(&diff)?
+        } else {
+            diff
+        };
+        let diff = if self.args.choose_hunks {
+            self.choose_hunks(&diff)?
+        } else {
+            diff
+        };
+        let prepared_message = self.prepared_message()?;
+        let current_message = if self.args.amend {
+            Some(self.vcs.last_message()?)
+        } else {
+            None
+        };
+        let rank_context = self.rank_suggestions().then(|| self.rank_context(&diff));
+        let response = self.get_response(diff, current_message.as_deref()).await?;
+        let mut selection = response
             .clone()
             .into_iter()
-            .map(|message| message.split('\n').map(str::to_owned).collect::<Vec<_>>())
-            .filter_map(|message| message.first().cloned())
+            .map(|message| {
+                let subject = subject_line(&message).to_string();
+                match &rank_context {
+                    Some(rank_context) => format!("[{}] {subject}", rank_context.score(&message)),
+                    None => subject,
+                }
+            })
             .collect::<Vec<_>>();
+        let prompt = self.last_prompt();
+        if prompt.is_some() {
+            selection.push(preview::SHOW_PROMPT.to_string());
+        }
 
         loop {
             let selection = Select::with_theme(&ColorfulTheme::default())
@@ -169,11 +347,41 @@ impl Cli {
                 .interact();
 
             match selection {
+                Ok(index) if index == response.len() => {
+                    eprintln!("{}\n", prompt.as_deref().unwrap_or_default());
+                }
                 Ok(index) => {
-                    if self
-                        .commit(response.get(index).ok_or(Error::EmptySelection)?)
-                        .is_ok()
+                    let message = response.get(index).ok_or(Error::EmptySelection)?;
+                    let message = match &prepared_message {
+                        Some(prepared) => augment_prepared_message(
+                            prepared.operation,
+                            &prepared.message,
+                            message,
+                            self.comment_string()?.as_deref().unwrap_or("#"),
+                        ),
+                        None => message.clone(),
+                    };
+                    // The changes of a range are already committed and a patch is only committed
+                    // when it's applied.
+                    if self.range().is_some()
+                        || (self.args.patch_file.is_some() && !self.args.apply)
                     {
+                        println!("{message}");
+                        return Ok(());
+                    }
+                    // The patch is applied once, even if the commit is aborted.
+                    if let Some(patch) = patch.take() {
+                        self.vcs.apply(&patch)?;
+                    }
+                    if self.commit(&message).is_ok() {
+                        if let Err(err) = self.remember_accepted(&message) {
+                            eprintln!("Unable to remember the message: {err}");
+                        }
+                        if self.notes() {
+                            if let Err(err) = self.add_note(&message, index, response.len()) {
+                                eprintln!("Unable to add the note: {err}");
+                            }
+                        }
                         return Ok(());
                     }
                 }
@@ -182,83 +390,610 @@ impl Cli {
         }
     }
 
+    /// Prints the first suggestion for the diff of stdin, so it composes with other tools
+    async fn run_stdin(&self) -> Result<(), Error> {
+        let diff = self.get_git_diff()?;
+        if diff.trim().is_empty() {
+            return Err(Error::EmptyDiff);
+        }
+
+        let response = self.get_response(diff, None).await?;
+        let message = response.first().ok_or(Error::EmptySelection)?;
+        println!("{}", message.trim_end());
+        Ok(())
+    }
+
     fn get_git_diff(&self) -> Result<String, Error> {
-        let mut arguments = vec!["--no-pager", "diff", "--staged"];
-        if self.args.ignore_space.unwrap_or(self.config.ignore_space) {
-            arguments.push("--ignore-space-change");
-            arguments.push("--ignore-blank-lines");
+        if self.args.stdin {
+            return Ok(io::read_to_string(io::stdin())?);
         }
-        for path in &self.args.path {
-            arguments.push(path.as_str());
+        match self.args.patch_file.as_deref() {
+            Some(path) if path.as_os_str() == "-" => return Ok(io::read_to_string(io::stdin())?),
+            Some(path) => return Ok(fs::read_to_string(path)?),
+            None => {}
         }
-        let output = Command::new("git").args(&arguments).output()?;
-        if !output.status.success() {
-            return Err(Error::GitDiff);
+        self.vcs.diff(&DiffOptions {
+            amend: self.args.amend,
+            all: self.args.all,
+            range: self.range().as_deref(),
+            ..self.diff_options()
+        })
+    }
+
+    /// The options of every diff, regardless of which changes are diffed
+    fn diff_options(&self) -> DiffOptions<'_> {
+        DiffOptions {
+            ignore_space: self.args.ignore_space.unwrap_or(self.config.ignore_space),
+            context_lines: self.args.diff_context.unwrap_or(self.config.diff_context),
+            paths: &self.args.path,
+            excludes: &self.args.exclude,
+            rename_threshold: self.config.rename_threshold,
+            copy_threshold: self.config.copy_threshold,
+            ..Default::default()
         }
-        let respone = String::from_utf8(output.stdout)?;
-        Ok(respone)
     }
 
-    async fn get_response(&self, diff: String) -> Result<Vec<String>, Error> {
-        let progress_bar =
-            ProgressBar::new_spinner().with_message("🤖 Fetching responses from ChatGPT.");
-        progress_bar.enable_steady_tick(Duration::from_millis(120));
+    /// The revision range which is described instead of the staged changes
+    fn range(&self) -> Option<Cow<'_, str>> {
+        match &self.args.command {
+            Some(Commands::Squash { base }) => Some(Cow::Owned(format!("{base}..HEAD"))),
+            _ => self.args.range.as_deref().map(Cow::Borrowed),
+        }
+    }
 
-        let response = ChatCompletionBuilder::default()
-            .n(self
+    /// Generates the suggestions for the diff, improving the current message of the commit if
+    /// it's given
+    async fn get_response(
+        &self,
+        diff: String,
+        current_message: Option<&str>,
+    ) -> Result<Vec<String>, Error> {
+        let packages = self.touched_packages(&diff);
+        let scope = self.inferred_scope(&diff);
+        let breaking_changes = self.breaking_changes(&diff);
+        let rank_context = self.rank_suggestions().then(|| self.rank_context(&diff));
+        // A diff which only bumps dependencies gets a standardized message without the model,
+        // which is post-processed like the suggestions of the model.
+        let choices = match dependency_bump_message(&diff) {
+            Some(message) if self.convention() == Convention::Gitmoji => {
+                vec![message.replacen("chore(deps): bump", "⬆️ Bump", 1)]
+            }
+            Some(message) => vec![message],
+            None => self.complete_diff(diff, current_message).await?,
+        };
+        let choices = match &self.args.subject {
+            Some(subject) => choices
+                .iter()
+                .map(|choice| with_subject(choice, subject))
+                .collect(),
+            None => choices,
+        };
+        let choices = match self.commit_type()? {
+            Some(kind) => choices
+                .iter()
+                .map(|choice| force_type(choice, kind))
+                .collect(),
+            None => choices,
+        };
+        // The inferred scope replaces the one of the model, otherwise the package is the scope if
+        // only a single package of a monorepo is touched.
+        let choices = match (&scope, packages.as_slice()) {
+            (Some(scope), _) => choices
+                .iter()
+                .map(|choice| force_scope(choice, scope))
+                .collect(),
+            (None, [package]) => choices
+                .iter()
+                .map(|choice| inject_scope(choice, package))
+                .collect(),
+            _ => choices,
+        };
+        let choices = if breaking_changes.is_empty() {
+            choices
+        } else {
+            choices
+                .iter()
+                .map(|choice| mark_breaking(choice, &breaking_changes))
+                .collect()
+        };
+        let choices = self.validate(choices)?;
+        let choices = if self.config.strip_subject_period {
+            choices
+                .iter()
+                .map(|choice| strip_subject_period(choice))
+                .collect()
+        } else {
+            choices
+        };
+        let choices = match self.config.body_width {
+            0 => choices,
+            width => choices
+                .iter()
+                .map(|choice| wrap_body(choice, width))
+                .collect(),
+        };
+        let choices = if self.oneline() {
+            choices.iter().map(|choice| subject_only(choice)).collect()
+        } else {
+            choices
+        };
+        // Enforcing the type or scope might turn different suggestions into the same.
+        let choices = deduplicate(choices);
+        let choices = match self.commitlint() {
+            Some(commitlint) => order_by_commitlint(&commitlint, choices),
+            None => choices,
+        };
+        let choices = self.apply_gitmoji(choices);
+        let choices = match &rank_context {
+            Some(rank_context) => rank(rank_context, choices),
+            None => choices,
+        };
+        let choices = match self.ticket()? {
+            Some(ticket) if self.config.ticket_placement == TicketPlacement::Subject => choices
+                .iter()
+                .map(|choice| add_ticket_to_subject(choice, &ticket))
+                .collect(),
+            _ => choices,
+        };
+        let trailers = self.trailers()?;
+        let choices = choices
+            .iter()
+            .map(|choice| append_trailers(choice, &trailers))
+            .collect::<Vec<_>>();
+        // Escaping comes last, as the wrapped body, the ticket or a trailer might start a line
+        // with the comment string as well.
+        let comment_string = match self.strips_comments() {
+            true => self.comment_string()?,
+            false => None,
+        };
+        let choices = match comment_string {
+            Some(comment_string) => {
+                let template = self.commit_template()?.unwrap_or_default();
+                choices
+                    .iter()
+                    .map(|choice| escape_comments(choice, &comment_string, &template))
+                    .collect()
+            }
+            None => choices,
+        };
+        Ok(choices)
+    }
+
+    /// The suggestions of the model for the diff, restored and rendered as plain messages
+    async fn complete_diff(
+        &self,
+        diff: String,
+        current_message: Option<&str>,
+    ) -> Result<Vec<String>, Error> {
+        let structured = self.args.structured || self.config.structured;
+        let anonymizer = self.anonymizer(&diff);
+        let request = self.completion_request(
+            self.get_system_message(&diff)?,
+            self.get_user_message(diff, current_message).await?,
+            structured,
+        );
+        let choices = self.complete(&request).await?;
+        let choices = self.refine(&request, choices).await?;
+        let choices = self.shorten_subjects(&request, choices).await?;
+        let choices = self.distinct_responses(&request, choices).await?;
+        let choices = self.without_banned_phrases(&request, choices).await?;
+        Ok(choices
+            .iter()
+            .map(|choice| render_response(&anonymizer.restore(choice), structured))
+            .collect())
+    }
+
+    /// The request for the configured model with the given prompts
+    fn completion_request(
+        &self,
+        system: String,
+        user: String,
+        structured: bool,
+    ) -> CompletionRequest {
+        CompletionRequest {
+            model: self.model(),
+            system,
+            user,
+            suggestions: self
                 .args
                 .suggestions
                 .map(|suggestions| suggestions as u8)
-                .unwrap_or(self.config.suggestions))
-            .model(self.args.model.clone().unwrap_or(self.config.model.clone()))
-            .max_tokens(
-                self.args
-                    .max_tokens
-                    .map(|suggestions| suggestions as u64)
-                    .unwrap_or(self.config.max_tokens),
-            )
-            .messages(vec![
-                self.get_system_message(self.config.context_prefix.clone()),
-                self.get_user_message(diff),
-            ])
-            .create()
-            .await
-            .map_err(|error| Error::FetchData(error.message))?;
-
-        let choices = response
-            .choices
-            .into_iter()
-            .map(|choice| {
-                choice
-                    .message
-                    .content
-                    .expect("expect content data from ChatGPT")
-            })
-            .collect::<Vec<_>>();
+                .unwrap_or(if self.oneline() {
+                    1
+                } else {
+                    self.config.suggestions
+                }),
+            max_tokens: self.max_tokens(),
+            temperature: self.temperature(),
+            top_p: self.top_p(),
+            presence_penalty: self.presence_penalty(),
+            frequency_penalty: self.frequency_penalty(),
+            seed: self.seed(),
+            timeout: Duration::from_secs(self.config.timeout),
+            reasoning_effort: self.args.reasoning_effort.or(self.config.reasoning_effort),
+            structured,
+        }
+    }
+
+    /// The configured maximum of tokens of a response
+    fn max_tokens(&self) -> u64 {
+        self.args
+            .max_tokens
+            .map(|max_tokens| max_tokens as u64)
+            .unwrap_or(self.config.max_tokens)
+    }
+
+    /// The configured sampling temperature, `None` for the default of the provider
+    fn temperature(&self) -> Option<f32> {
+        self.args.temperature.or(self.config.temperature)
+    }
+
+    /// The configured nucleus sampling probability, `None` for the default of the provider
+    fn top_p(&self) -> Option<f32> {
+        self.args.top_p.or(self.config.top_p)
+    }
+
+    /// The configured presence penalty, `None` for the default of the provider
+    fn presence_penalty(&self) -> Option<f32> {
+        self.args.presence_penalty.or(self.config.presence_penalty)
+    }
+
+    /// The configured frequency penalty, `None` for the default of the provider
+    fn frequency_penalty(&self) -> Option<f32> {
+        self.args
+            .frequency_penalty
+            .or(self.config.frequency_penalty)
+    }
+
+    /// The configured seed of the sampling, `None` for a random one
+    fn seed(&self) -> Option<u32> {
+        self.args.seed.or(self.config.seed)
+    }
+
+    /// The configured provider
+    fn provider(&self) -> ProviderKind {
+        self.args.provider.unwrap_or(self.config.provider)
+    }
+
+    /// The configured model with its alias resolved, defaults to the model of the provider
+    fn model(&self) -> String {
+        resolve_model(
+            self.args
+                .model
+                .as_deref()
+                .or(self.config.model.as_deref())
+                .unwrap_or(self.provider().default_model()),
+            &self.config.aliases,
+        )
+    }
+
+    /// Sends the request to the configured provider, while rendering the progress
+    async fn complete(&self, request: &CompletionRequest) -> Result<Vec<String>, Error> {
+        self.preview_prompt(request)?;
+        let provider = from_config(self.provider(), &self.config, &self.args).await?;
+        // The spread temperatures need a request per suggestion, which isn't streamed.
+        let temperatures = self.temperature_spread(request);
+        if temperatures.is_none() && (self.args.stream || self.config.stream) {
+            return self.get_streamed_response(provider.as_ref(), request).await;
+        }
+        let progress_bar =
+            ProgressBar::new_spinner().with_message("🤖 Fetching commit message suggestions.");
+        progress_bar.enable_steady_tick(Duration::from_millis(120));
+        let choices = match temperatures {
+            Some(temperatures) => {
+                self.complete_spread(provider.as_ref(), request, &temperatures)
+                    .await?
+            }
+            None => provider.complete(request).await?,
+        };
         progress_bar.finish();
         Ok(choices)
     }
 
-    fn get_system_message(&self, context_prefix: String) -> ChatCompletionMessage {
-        ChatCompletionMessage {
-            role: ChatCompletionMessageRole::System,
-            content: Some(context_prefix),
-            name: None,
-            function_call: None,
+    /// Streams the suggestions, rendering the latest line of every suggestion while it arrives
+    async fn get_streamed_response(
+        &self,
+        provider: &dyn CompletionProvider,
+        request: &CompletionRequest,
+    ) -> Result<Vec<String>, Error> {
+        let multi_progress = MultiProgress::new();
+        let style = ProgressStyle::with_template("{spinner} {prefix} {wide_msg}")
+            .expect("expect a valid progress bar template");
+        let progress_bars = (1..=request.suggestions)
+            .map(|suggestion| {
+                let progress_bar = multi_progress.add(
+                    ProgressBar::new_spinner()
+                        .with_style(style.clone())
+                        .with_prefix(format!("{suggestion}."))
+                        .with_message("🤖"),
+                );
+                progress_bar.enable_steady_tick(Duration::from_millis(120));
+                progress_bar
+            })
+            .collect::<Vec<_>>();
+
+        let suggestions = Mutex::new(vec![String::new(); progress_bars.len()]);
+        let on_delta = |index: usize, delta: &str| {
+            let mut suggestions = suggestions.lock().expect("expect an unpoisoned lock");
+            if let (Some(suggestion), Some(progress_bar)) =
+                (suggestions.get_mut(index), progress_bars.get(index))
+            {
+                suggestion.push_str(delta);
+                let line = suggestion
+                    .lines()
+                    .rev()
+                    .find(|line| !line.trim().is_empty())
+                    .unwrap_or_default();
+                progress_bar.set_message(line.to_string());
+            }
+        };
+        let choices = provider.complete_streaming(request, &on_delta).await;
+        for progress_bar in &progress_bars {
+            progress_bar.finish_and_clear();
         }
+        choices
     }
 
-    fn get_user_message(&self, diff: String) -> ChatCompletionMessage {
-        let mut content = format!(
-            r#"
+    fn get_system_message(&self, diff: &str) -> Result<String, Error> {
+        let mut content = match &self.config.project_description {
+            Some(description) => {
+                format!("{}\n\n{}", description.trim(), self.config.context_prefix)
+            }
+            None => self.config.context_prefix.clone(),
+        };
+        match &self.args.subject {
+            Some(subject) => content.push_str(&format!(
+                "\n\nThe subject line is given: `{}`. Start the message with exactly this subject \
+                line and only write the body, which explains the changes.",
+                subject.trim()
+            )),
+            None => {
+                if let Some(max_length) = self.max_subject_length() {
+                    content.push_str(&format!(
+                        "\n\nThe subject line has at most {max_length} characters."
+                    ));
+                }
+            }
+        }
+        if !self.config.banned_phrases.is_empty() {
+            content.push_str(&format!(
+                "\n\nNever use these phrases: \"{}\".",
+                self.config.banned_phrases.join("\", \"")
+            ));
+        }
+        if self.config.strip_subject_period {
+            content.push_str("\n\nDon't end the subject line with a period.");
+        }
+        if self.oneline() {
+            content.push_str("\n\nOnly write the subject line, without a body.");
+        }
+        let stack_hint = self.stack_hint(diff);
+        if !stack_hint.is_empty() {
+            content.push_str(&format!("\n\n{stack_hint}"));
+        }
+        if let Some(template) = self.commit_template()? {
+            content.push_str(&format!(
+                r#"
+
+The repository has a commit template. Fill in its structure and keep its comment lines as they are:
+{}"#,
+                template.trim_end()
+            ));
+        }
+        if let Some(instruction) = self.gitmoji_instruction() {
+            content.push_str(&format!("\n\n{instruction}"));
+        }
+        if let Some(kind) = self.commit_type()? {
+            content.push_str(&format!(
+                "\n\nThe type of the commit is `{kind}`, use it regardless of what the changes look like."
+            ));
+        }
+        if let Some(scope) = self.inferred_scope(diff) {
+            content.push_str(&format!(
+                "\n\nThe scope of the commit is `{scope}`, use it as the scope of the subject."
+            ));
+        }
+        let breaking_changes = self.breaking_changes(diff);
+        if !breaking_changes.is_empty() {
+            content.push_str(&format!(
+                r#"
+
+The changes break the public API:
+- {}
+Mark the type with `!`, e.g. `feat!: ...`, and add a `BREAKING CHANGE:` footer which explains what breaks and how to migrate."#,
+                breaking_changes.join("\n- ")
+            ));
+        }
+        if let Some(commitlint) = self.commitlint() {
+            content.push_str(&format!(
+                r#"
+
+The repository lints commit messages with commitlint, follow its rules:
+{}"#,
+                commitlint.constraints()
+            ));
+        }
+        if let Some(examples) = self.history_examples()? {
+            content.push_str(&format!(
+                r#"
+
+These are the latest commit messages of the repository, match their style, tone and conventions:
+{}"#,
+                examples
+            ));
+        }
+        if let Some(examples) = self.learned_examples()? {
+            content.push_str(&format!(
+                r#"
+
+These are messages I committed before, some of them edited by me, write in the same personal style:
+{}"#,
+                examples
+            ));
+        }
+        if let Some(conventions) = self.repo_conventions()? {
+            content.push_str(&format!(
+                r#"
+
+The repository documents its commit conventions, follow them:
+{}"#,
+                conventions
+            ));
+        }
+        if self.args.structured || self.config.structured {
+            content.push_str(
+                r#"
+
+Respond with a JSON object with the following fields:
+- "type": the conventional commit type, e.g. feat, fix, refactor, docs or chore
+- "scope": the optional scope of the change or null
+- "subject": a short summary of the change in imperative mood
+- "body": why the change was made and what has been changed, or null
+- "breaking": whether the change breaks backwards compatibility"#,
+            );
+        }
+        // The language comes last, so it isn't drowned out by the English instructions.
+        if let Some(instruction) = self.language_instruction() {
+            content.push_str(&format!("\n\n{instruction}"));
+        }
+        Ok(content)
+    }
+
+    async fn get_user_message(
+        &self,
+        diff: String,
+        current_message: Option<&str>,
+    ) -> Result<String, Error> {
+        let submodule_summary = self.submodule_summary(&diff);
+        let packages = self.touched_packages(&diff);
+        let untracked_summary = self.untracked_summary()?;
+        let anonymizer = self.anonymizer(&diff);
+        let stats = match self.privacy() {
+            Privacy::Full => None,
+            Privacy::Stats => Some(anonymizer.anonymize(&self.diff_stats(&diff)?)),
+        };
+        // The stat covers every change, even if the diff is summarized.
+        let diffstat = anonymizer.anonymize(&diffstat(&diff));
+        // The diff is anonymized before its parts might be summarized.
+        let mut diff = anonymizer.anonymize(&self.prompt_diff(&diff)?);
+        // Nothing of the diff is sent with stats only.
+        let handling = match stats {
+            Some(_) => None,
+            None => self.large_diff_handling(&diff)?,
+        };
+        if handling == Some(LargeDiff::Truncate) {
+            diff = truncate_diff(&diff, self.max_diff_chars());
+        }
+        let summaries = match handling {
+            Some(LargeDiff::Summarize) => self.summarize_diff(&diff).await?,
+            _ => None,
+        };
+        let template = self.config.prompt_template.as_deref();
+        let mut content = if let Some(template) = template {
+            let files = file_diffs(&diff)
+                .iter()
+                .map(|file| file.path.as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let branch = self.vcs.branch()?.unwrap_or_default();
+            let author = self.vcs.config("user.name")?.unwrap_or_default();
+            let changes = stats.as_ref().or(summaries.as_ref()).unwrap_or(&diff);
+            render_template(
+                template,
+                &[
+                    ("diff", changes),
+                    ("diffstat", diffstat.trim_end()),
+                    ("files", &files),
+                    ("branch", &branch),
+                    ("reason", self.args.hint.as_deref().unwrap_or_default()),
+                    ("author", &author),
+                ],
+            )?
+        } else if let Some(stats) = stats {
+            format!(
+                r#"
+The code is private, only the changed files with the amount of added and removed lines and the headers of their hunks are known:
+{}
+"#,
+                stats.trim_end()
+            )
+        } else if let Some(summaries) = summaries {
+            format!(
+                r#"
+Changed files:
+{}
+
+The diff is too large to be sent as a whole, these are the summaries of its parts:
+{summaries}
+"#,
+                diffstat.trim_end()
+            )
+        } else {
+            format!(
+                r#"
+Changed files:
+{}
+
 Diff: ```diff
 {}
 ```
 "#,
-            diff.chars().collect::<String>()
-        );
+                diffstat.trim_end(),
+                diff.chars().collect::<String>()
+            )
+        };
 
-        if let Some(hint) = &self.args.hint {
+        if !submodule_summary.is_empty() {
+            content.push_str(&format!(
+                r#"
+
+Changes of the submodules:
+{}
+"#,
+                submodule_summary.trim_end()
+            ));
+        }
+
+        if !untracked_summary.is_empty() {
+            content.push_str(&format!(
+                r#"
+
+Untracked files, which aren't part of the commit but give context about new files:
+{}
+"#,
+                untracked_summary.trim_end()
+            ));
+        }
+
+        if !packages.is_empty() {
+            content.push_str(&format!(
+                r#"
+
+The changes touch these packages of the monorepo, use them as scope of the commit message, separated by commas:
+{}
+"#,
+                packages.join(", ")
+            ));
+        }
+
+        if let Some(issue) = self.issue_context().await? {
+            content.push_str(&format!(
+                r#"
+
+The changes belong to this issue, use it to explain why they were made:
+{}
+"#,
+                issue
+            ));
+        }
+
+        // A template which uses the hint places it itself.
+        if let Some(hint) = self
+            .args
+            .hint
+            .as_ref()
+            .filter(|_| !template.is_some_and(|template| uses_variable(template, "reason")))
+        {
             content.push_str(&format!(
                 r#"
 
@@ -269,21 +1004,61 @@ Hint to consider for the commit message:
             ));
         }
 
-        ChatCompletionMessage {
-            role: ChatCompletionMessageRole::User,
-            content: Some(content),
-            name: None,
-            function_call: None,
+        if let Some(Commands::Squash { base }) = &self.args.command {
+            content.push_str(&format!(
+                r#"
+
+The commits are squashed into a single commit, consolidate their messages:
+{}
+"#,
+                self.vcs
+                    .commits(&format!("{base}..HEAD"))?
+                    .into_iter()
+                    .map(|commit| commit.message)
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            ));
+        }
+
+        if let Some(prepared) = self.prepared_message()? {
+            let instruction = match prepared.operation {
+                Operation::Merge => "A merge is in progress and its message is kept. Only write a description of the merged changes, without a subject line, which is inserted below the subject of the merge message:",
+                Operation::CherryPick => "A cherry-pick is in progress and the message of the picked commit is kept. Only write a short paragraph explaining why the commit is picked, e.g. as a backport, without a subject line, which is added to the message:",
+                Operation::Revert => "A revert is in progress and its message is kept. Only write a short paragraph explaining why the commit is reverted, without a subject line, which is added below the message:",
+            };
+            content.push_str(&format!(
+                "\n\n{instruction}\n{}\n",
+                prepared.message.trim_end()
+            ));
+        }
+
+        if let Some(current_message) = current_message {
+            content.push_str(&format!(
+                r#"
+
+The commit already exists, improve its current message:
+{}
+"#,
+                current_message
+            ));
         }
+
+        Ok(anonymizer.anonymize(&content))
     }
 
     fn commit(&self, message: &str) -> Result<(), Error> {
-        let status = Command::new("git")
-            .args(["commit", "--message", message, "--edit"])
-            .status()?;
-        if !status.success() {
-            return Err(Error::GitCommit);
-        }
-        Ok(())
+        self.vcs.commit(
+            message,
+            &CommitOptions {
+                amend: self.args.amend,
+                all: self.args.all,
+                edit: !self.oneline(),
+                arguments: &[
+                    self.args.git_args.as_slice(),
+                    self.args.git_commit_args.as_slice(),
+                ]
+                .concat(),
+            },
+        )
     }
 }
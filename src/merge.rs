@@ -0,0 +1,67 @@
+use crate::{
+    vcs::{Operation, PreparedMessage},
+    Cli, Error,
+};
+
+/// The line git appends to the message of a commit picked with `git cherry-pick -x`
+const CHERRY_PICKED_FROM: &str = "(cherry picked from commit ";
+
+impl Cli {
+    /// The prepared message of a merge, cherry-pick or revert in progress, if the staged changes
+    /// are described
+    pub(crate) fn prepared_message(&self) -> Result<Option<PreparedMessage>, Error> {
+        if self.args.stdin
+            || self.args.patch_file.is_some()
+            || self.range().is_some()
+            || self.args.amend
+        {
+            return Ok(None);
+        }
+        self.vcs.prepared_message()
+    }
+}
+
+/// Adds the generated description to the prepared message, which is otherwise kept as is,
+/// including the list of conflicts. For a merge, the description is inserted below the subject.
+/// For a cherry-pick it's inserted above the `(cherry picked from commit …)` line and for a
+/// revert below the `This reverts commit …` line.
+pub(crate) fn augment_prepared_message(
+    operation: Operation,
+    prepared_message: &str,
+    description: &str,
+    comment_string: &str,
+) -> String {
+    let description = description.trim();
+    if operation == Operation::Merge {
+        let (subject, rest) = prepared_message
+            .split_once('\n')
+            .unwrap_or((prepared_message, ""));
+        let mut message = format!("{}\n\n{description}", subject.trim_end());
+        if !rest.trim().is_empty() {
+            message.push_str("\n\n");
+            message.push_str(rest.trim_matches('\n'));
+        }
+        return message;
+    }
+
+    let lines = prepared_message.lines().collect::<Vec<_>>();
+    let comments = lines
+        .iter()
+        .position(|line| line.starts_with(comment_string))
+        .unwrap_or(lines.len());
+    let position = match operation {
+        Operation::CherryPick => lines[..comments]
+            .iter()
+            .position(|line| line.starts_with(CHERRY_PICKED_FROM))
+            .unwrap_or(comments),
+        _ => comments,
+    };
+    let before = lines[..position].join("\n");
+    let after = lines[position..].join("\n");
+    let mut message = format!("{}\n\n{description}", before.trim_end());
+    if !after.trim().is_empty() {
+        message.push_str("\n\n");
+        message.push_str(after.trim_matches('\n'));
+    }
+    message
+}
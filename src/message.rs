@@ -0,0 +1,94 @@
+use serde::Deserialize;
+
+/// The text with every run of whitespace, including line breaks, replaced by a single space
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A commit message split into the parts of a conventional commit, as requested by structured
+/// output
+#[derive(Deserialize)]
+pub(crate) struct CommitParts {
+    #[serde(rename = "type")]
+    pub(crate) kind: String,
+    pub(crate) scope: Option<String>,
+    pub(crate) subject: String,
+    pub(crate) body: Option<String>,
+    pub(crate) breaking: bool,
+}
+
+impl CommitParts {
+    /// The JSON schema of the commit parts, strict enough for OpenAI's structured outputs
+    pub(crate) fn schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "type": {
+                    "type": "string",
+                    "description": "The conventional commit type, e.g. feat, fix, refactor, docs or chore"
+                },
+                "scope": {
+                    "type": ["string", "null"],
+                    "description": "The optional scope of the change, e.g. the affected module"
+                },
+                "subject": {
+                    "type": "string",
+                    "description": "A short summary of the change in imperative mood"
+                },
+                "body": {
+                    "type": ["string", "null"],
+                    "description": "Why the change was made and what has been changed"
+                },
+                "breaking": {
+                    "type": "boolean",
+                    "description": "Whether the change breaks backwards compatibility"
+                }
+            },
+            "required": ["type", "scope", "subject", "body", "breaking"],
+            "additionalProperties": false
+        })
+    }
+
+    /// Parses the commit parts of a structured response. Models without native support for
+    /// structured output tend to wrap the JSON in a code block, which is ignored.
+    pub(crate) fn parse(response: &str) -> Option<Self> {
+        let start = response.find('{')?;
+        let end = response.rfind('}')?;
+        serde_json::from_str(response.get(start..=end)?).ok()
+    }
+
+    /// The header of the commit message, e.g. `feat(cli)!: add structured output`, always on a
+    /// single line even if the model breaks the subject across lines
+    pub(crate) fn header(&self) -> String {
+        let mut header = single_line(&self.kind);
+        let scope = self.scope.as_deref().map(single_line).unwrap_or_default();
+        if !scope.is_empty() {
+            header.push_str(&format!("({scope})"));
+        }
+        if self.breaking {
+            header.push('!');
+        }
+        header.push_str(": ");
+        header.push_str(&single_line(&self.subject));
+        header
+    }
+
+    /// Renders the commit message, the header followed by the body
+    pub(crate) fn render(&self) -> String {
+        let mut message = self.header();
+        if let Some(body) = self.body.as_deref().map(str::trim) {
+            if !body.is_empty() {
+                message.push_str("\n\n");
+                message.push_str(body);
+            }
+        }
+        message
+    }
+}
+
+/// The subject line of the message as shown in the selection. Structured responses render the
+/// header from their `subject` field on the first line, so it's the subject of the model, never
+/// a line of the body.
+pub(crate) fn subject_line(message: &str) -> &str {
+    message.lines().next().unwrap_or_default()
+}
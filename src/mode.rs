@@ -0,0 +1,66 @@
+use crate::diff::FileDiff;
+
+/// The mode of symlinks in git
+const SYMLINK_MODE: &str = "120000";
+
+/// The mode of executable files in git
+const EXECUTABLE_MODE: &str = "100755";
+
+/// Describes mode changes like `chmod +x` and symlinks as facts, as their diffs are easily
+/// misread. A symlink or a file whose content didn't change is replaced by the facts, otherwise
+/// they're followed by the changes of the content.
+pub(crate) fn describe_mode_change(file: &FileDiff) -> Option<String> {
+    let mut old_mode = None;
+    let mut new_mode = None;
+    let mut symlink = None;
+    for line in file.text.lines().skip(1) {
+        if line.starts_with("@@ ") {
+            break;
+        }
+        if let Some(mode) = line.strip_prefix("old mode ") {
+            old_mode = Some(mode.trim());
+        } else if let Some(mode) = line.strip_prefix("new mode ") {
+            new_mode = Some(mode.trim());
+        } else if line.starts_with("new file mode ") && line.ends_with(SYMLINK_MODE) {
+            symlink = Some("new");
+        } else if line.starts_with("deleted file mode ") && line.ends_with(SYMLINK_MODE) {
+            symlink = Some("deleted");
+        } else if line.starts_with("index ") && line.ends_with(&format!(" {SYMLINK_MODE}")) {
+            symlink = Some("changed");
+        }
+    }
+
+    let path = &file.path;
+    if let Some(change) = symlink {
+        // The content of a symlink is its target, without a trailing newline.
+        let old_target = file.changed_lines('-').first().copied().unwrap_or_default();
+        let new_target = file.changed_lines('+').first().copied().unwrap_or_default();
+        return Some(match change {
+            "new" => format!("(added the symlink {path} pointing to {new_target})"),
+            "deleted" => format!("(removed the symlink {path}, which pointed to {old_target})"),
+            _ => format!(
+                "(changed the target of the symlink {path} from {old_target} to {new_target})"
+            ),
+        });
+    }
+
+    let (Some(old_mode), Some(new_mode)) = (old_mode, new_mode) else {
+        return None;
+    };
+    let fact = if new_mode == EXECUTABLE_MODE {
+        format!("(made {path} executable)")
+    } else if old_mode == EXECUTABLE_MODE {
+        format!("(made {path} no longer executable)")
+    } else {
+        format!("(changed the mode of {path} from {old_mode} to {new_mode})")
+    };
+    let hunks = file
+        .text
+        .find("\n@@ ")
+        .map(|start| &file.text[start + 1..])
+        .unwrap_or_default();
+    if hunks.is_empty() {
+        return Some(fact);
+    }
+    Some(format!("{fact}\n{}", hunks.trim_end()))
+}
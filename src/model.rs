@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Well known models which can be referenced by a short alias, e.g. `--model 4o-mini`.
+///
+/// This is not an exhaustive list, any other model name is passed through as is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Model {
+    Gpt35Turbo,
+    Gpt4,
+    Gpt4Turbo,
+    Gpt4o,
+    #[default]
+    Gpt4oMini,
+}
+
+impl Model {
+    const ALL: [Model; 5] = [
+        Model::Gpt35Turbo,
+        Model::Gpt4,
+        Model::Gpt4Turbo,
+        Model::Gpt4o,
+        Model::Gpt4oMini,
+    ];
+
+    /// The model name which is sent to the API
+    pub(crate) fn id(self) -> &'static str {
+        match self {
+            Model::Gpt35Turbo => "gpt-3.5-turbo",
+            Model::Gpt4 => "gpt-4",
+            Model::Gpt4Turbo => "gpt-4-turbo",
+            Model::Gpt4o => "gpt-4o",
+            Model::Gpt4oMini => "gpt-4o-mini",
+        }
+    }
+
+    /// The price in USD per million input tokens
+    pub(crate) fn input_price(self) -> f64 {
+        match self {
+            Model::Gpt35Turbo => 0.5,
+            Model::Gpt4 => 30.0,
+            Model::Gpt4Turbo => 10.0,
+            Model::Gpt4o => 2.5,
+            Model::Gpt4oMini => 0.15,
+        }
+    }
+
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            Model::Gpt35Turbo => &["3.5", "3.5-turbo"],
+            Model::Gpt4 => &["4"],
+            Model::Gpt4Turbo => &["4-turbo"],
+            Model::Gpt4o => &["4o"],
+            Model::Gpt4oMini => &["4o-mini", "mini"],
+        }
+    }
+
+    pub(crate) fn from_alias(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|model| model.id() == name || model.aliases().contains(&name))
+    }
+}
+
+/// The prefix of fine-tuned models, e.g. `ft:gpt-4o-mini-2024-07-18:my-org::abc123`
+const FINE_TUNED_PREFIX: &str = "ft:";
+
+pub(crate) fn is_fine_tuned(name: &str) -> bool {
+    name.starts_with(FINE_TUNED_PREFIX)
+}
+
+/// The model a fine-tuned model is based on, or the model itself
+pub(crate) fn base_model(name: &str) -> &str {
+    match name.strip_prefix(FINE_TUNED_PREFIX) {
+        Some(fine_tuned) => fine_tuned.split(':').next().unwrap_or(fine_tuned),
+        None => name,
+    }
+}
+
+/// Whether the model is a reasoning model like o1 or o3, which need to be requested differently
+pub(crate) fn is_reasoning(name: &str) -> bool {
+    let base_model = base_model(name);
+    ["o1", "o3", "o4"].into_iter().any(|family| {
+        base_model
+            .strip_prefix(family)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+    })
+}
+
+/// How much effort a reasoning model should spend on reasoning before answering
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ReasoningEffort {
+    Low,
+    Medium,
+    High,
+}
+
+/// Resolves the model name given in the config or on the command line. The aliases of the config
+/// and the known aliases are expanded, fine-tuned models and every other name are used as is.
+pub(crate) fn resolve_model(name: &str, aliases: &HashMap<String, String>) -> String {
+    let name = aliases.get(name).map(String::as_str).unwrap_or(name);
+    if is_fine_tuned(name) {
+        return name.to_string();
+    }
+    Model::from_alias(name)
+        .map(|model| model.id().to_string())
+        .unwrap_or_else(|| name.to_string())
+}
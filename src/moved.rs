@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use crate::{
+    diff::{file_diffs, split_before},
+    outline::Outline,
+};
+
+/// The fewest non-blank lines a block needs to be detected as moved
+const MIN_MOVED_LINES: usize = 3;
+
+/// The fewest alphanumeric characters a block needs to be detected as moved, like git's
+/// `--color-moved`, so blocks of braces don't count
+const MIN_MOVED_CHARS: usize = 20;
+
+/// Consecutive added or removed lines of a hunk
+struct Block {
+    file: usize,
+    /// The index of the first line in the diff of the file
+    start: usize,
+    len: usize,
+}
+
+/// The text of the block which is compared, i.e. its trimmed non-blank lines without the marker.
+/// `None` if the block is too small to be detected as moved.
+fn moved_key(lines: &[&str]) -> Option<String> {
+    let lines = lines
+        .iter()
+        .map(|line| line[1..].trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    let chars = lines
+        .iter()
+        .flat_map(|line| line.chars())
+        .filter(|c| c.is_alphanumeric())
+        .count();
+    (lines.len() >= MIN_MOVED_LINES && chars >= MIN_MOVED_CHARS).then(|| lines.join("\n"))
+}
+
+/// What the block defines, e.g. ``function `fn parse(text: &str)` ``, or its amount of lines
+fn describe(lines: &[&str]) -> String {
+    let outline = Outline::of(lines.iter().map(|line| line[1..].trim_end()));
+    if outline.declarations.is_empty() {
+        return format!("{} lines", lines.len());
+    }
+    outline
+        .declarations
+        .iter()
+        .map(|declaration| format!("`{declaration}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Finds the blocks of lines which were only moved, within a file or to another file, and
+/// leaves them out of the diff. The removed lines of deleted files are kept, since deleted files
+/// are summarized anyway. Returns the reduced diff with a description of every move like
+/// ``moved `fn parse(text: &str)` from src/main.rs to src/parser.rs``.
+pub(crate) fn strip_moved_code(diff: &str) -> (String, Vec<String>) {
+    let files = file_diffs(diff);
+    let lines = files
+        .iter()
+        .map(|file| file.text.split_inclusive('\n').collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let mut removed = Vec::new();
+    let mut added = HashMap::<String, Vec<Block>>::new();
+    for (file, lines) in lines.iter().enumerate() {
+        let Some(first_hunk) = lines.iter().position(|line| line.starts_with("@@ ")) else {
+            continue;
+        };
+        let mut index = first_hunk;
+        while index < lines.len() {
+            let Some(marker @ ('+' | '-')) = lines[index].chars().next() else {
+                index += 1;
+                continue;
+            };
+            let len = lines[index..]
+                .iter()
+                .take_while(|line| line.starts_with(marker))
+                .count();
+            let block = Block {
+                file,
+                start: index,
+                len,
+            };
+            if let Some(key) = moved_key(&lines[index..index + len]) {
+                if marker == '-' {
+                    removed.push((key, block));
+                } else {
+                    added.entry(key).or_default().push(block);
+                }
+            }
+            index += len;
+        }
+    }
+
+    let mut dropped = lines
+        .iter()
+        .map(|lines| vec![false; lines.len()])
+        .collect::<Vec<_>>();
+    let mut moves = Vec::new();
+    for (key, from) in removed {
+        let Some(to) = added.get_mut(&key).and_then(Vec::pop) else {
+            continue;
+        };
+        let from_lines = &lines[from.file][from.start..from.start + from.len];
+        let (from_path, to_path) = (&files[from.file].path, &files[to.file].path);
+        moves.push(if from.file == to.file {
+            format!("moved {} within {from_path}", describe(from_lines))
+        } else {
+            format!(
+                "moved {} from {from_path} to {to_path}",
+                describe(from_lines)
+            )
+        });
+        if !files[from.file].is_deleted() {
+            dropped[from.file][from.start..from.start + from.len].fill(true);
+        }
+        dropped[to.file][to.start..to.start + to.len].fill(true);
+    }
+    if moves.is_empty() {
+        return (diff.to_string(), moves);
+    }
+
+    let mut stripped = String::with_capacity(diff.len());
+    for (lines, dropped) in lines.iter().zip(&dropped) {
+        let text = lines
+            .iter()
+            .zip(dropped)
+            .filter(|(_, dropped)| !**dropped)
+            .map(|(line, _)| *line)
+            .collect::<String>();
+        let mut parts = split_before(&text, "@@ ").into_iter();
+        stripped.push_str(&parts.next().unwrap_or_default());
+        // Hunks whose changes were all moved are left out completely.
+        for hunk in parts.filter(|hunk| {
+            hunk.lines()
+                .skip(1)
+                .any(|line| line.starts_with(['+', '-']))
+        }) {
+            stripped.push_str(&hunk);
+        }
+    }
+    (stripped, moves)
+}
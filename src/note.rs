@@ -0,0 +1,107 @@
+use std::{fs, io};
+
+use clap::ValueEnum;
+use sha2::{Digest, Sha256};
+
+use crate::{git::git_path, Cli, Error};
+
+/// The notes ref the generation metadata is recorded in
+pub(crate) const NOTES_REF: &str = "refs/notes/commitgpt";
+
+/// The file in the git directory the note of a message written by the hook is kept in, until
+/// the `post-commit` hook records it
+const STASHED_NOTE: &str = "COMMITGPT_NOTE";
+
+impl Cli {
+    /// Whether the generation metadata is recorded in git notes
+    pub(crate) fn notes(&self) -> bool {
+        self.args.notes || self.config.notes
+    }
+
+    /// The generation metadata of a message, without whether it was edited
+    fn generation_note(&self, index: usize, suggestions: usize) -> String {
+        let provider = self
+            .provider()
+            .to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default();
+        // The hash identifies the prompt without disclosing it, `sha256sum` computes the same.
+        let mut template = self.config.context_prefix.clone();
+        if let Some(prompt_template) = &self.config.prompt_template {
+            template.push('\n');
+            template.push_str(prompt_template);
+        }
+        let prompt = Sha256::digest(template.as_bytes())
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        let mut note = format!(
+            "Generator: commitgpt {}\nProvider: {provider}\nModel: {}\nPrompt: sha256:{prompt}\nSuggestion: {} of {suggestions}\n",
+            env!("CARGO_PKG_VERSION"),
+            self.model(),
+            index + 1,
+        );
+        // The seed reproduces the suggestions of the same diff.
+        if let Some(seed) = self.seed() {
+            note.push_str(&format!("Seed: {seed}\n"));
+        }
+        note
+    }
+
+    /// Records how the message of the last commit was generated in a git note, so AI-assisted
+    /// commits can be audited without touching their messages
+    pub(crate) fn add_note(
+        &self,
+        message: &str,
+        index: usize,
+        suggestions: usize,
+    ) -> Result<(), Error> {
+        let note = self.generation_note(index, suggestions);
+        self.add_edited_note(note, message)
+    }
+
+    /// Adds the note with whether the message of the last commit differs from the generated one
+    fn add_edited_note(&self, mut note: String, message: &str) -> Result<(), Error> {
+        let edited = if self.vcs.last_message()?.trim() == message.trim() {
+            "no"
+        } else {
+            "yes"
+        };
+        note.push_str(&format!("Edited: {edited}\n"));
+        self.vcs.add_note(NOTES_REF, &note)
+    }
+
+    /// Keeps the note of the first suggestion the hook wrote, followed by the message, as the
+    /// commit only exists after the hook
+    pub(crate) fn stash_note(&self, message: &str, suggestions: usize) -> Result<(), Error> {
+        let note = self.generation_note(0, suggestions);
+        fs::write(git_path(STASHED_NOTE)?, format!("{note}\n{message}"))?;
+        Ok(())
+    }
+
+    /// Records the note the hook stashed for the last commit, if any
+    pub(crate) fn add_stashed_note(&self) -> Result<(), Error> {
+        let path = git_path(STASHED_NOTE)?;
+        let stashed = match fs::read_to_string(&path) {
+            Ok(stashed) => stashed,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+        fs::remove_file(&path)?;
+        let (note, message) = stashed.split_once("\n\n").unwrap_or((&stashed, ""));
+        self.add_edited_note(format!("{note}\n"), message)
+    }
+}
+
+/// Whether the hook stashed a note, which it only does with `notes`
+pub(crate) fn has_stashed_note() -> bool {
+    git_path(STASHED_NOTE).is_ok_and(|path| path.exists())
+}
+
+/// Discards the note the hook stashed for a commit which didn't happen, e.g. it was aborted
+pub(crate) fn discard_stashed_note() -> Result<(), Error> {
+    match fs::remove_file(git_path(STASHED_NOTE)?) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
+    }
+}
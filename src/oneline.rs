@@ -0,0 +1,55 @@
+use crate::{Cli, Error};
+
+/// The subject line of the message, without the body
+pub(crate) fn subject_only(message: &str) -> String {
+    message
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim_end()
+        .to_string()
+}
+
+impl Cli {
+    /// Whether only a subject line is generated and committed without the selection and editor
+    pub(crate) fn oneline(&self) -> bool {
+        self.args.oneline || self.config.oneline
+    }
+
+    /// Commits the first suggestion as it is, for quick commits like work in progress
+    pub(crate) async fn commit_oneline(&self) -> Result<(), Error> {
+        let diff = self.get_git_diff()?;
+        if diff.is_empty() {
+            return Err(Error::EmptyDiff);
+        }
+
+        let current_message = if self.args.amend {
+            Some(self.vcs.last_message()?)
+        } else {
+            None
+        };
+        let response = self
+            .get_response(diff.clone(), current_message.as_deref())
+            .await?;
+        let message = response.first().ok_or(Error::EmptySelection)?;
+        // The changes of a range are already committed and a patch is only committed when it's
+        // applied.
+        if self.range().is_some() || (self.args.patch_file.is_some() && !self.args.apply) {
+            println!("{message}");
+            return Ok(());
+        }
+        if self.args.apply {
+            self.vcs.apply(&diff)?;
+        }
+        self.commit(message)?;
+        if let Err(err) = self.remember_accepted(message) {
+            eprintln!("Unable to remember the message: {err}");
+        }
+        if self.notes() {
+            if let Err(err) = self.add_note(message, 0, response.len()) {
+                eprintln!("Unable to add the note: {err}");
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+/// The keywords which start an import, e.g. `use std::fs;` or `from os import path`
+const IMPORT_KEYWORDS: &[&str] = &[
+    "use ",
+    "import ",
+    "from ",
+    "#include ",
+    "require ",
+    "require(",
+    "extern crate ",
+    "using ",
+];
+
+/// Whether the trimmed line is an import by its keyword
+pub(crate) fn is_import(line: &str) -> bool {
+    IMPORT_KEYWORDS
+        .iter()
+        .any(|keyword| line.starts_with(keyword))
+}
+
+/// The keywords which start a declaration of a function or type in common languages
+const DECLARATION_KEYWORDS: &[&str] = &[
+    "fn ",
+    "struct ",
+    "enum ",
+    "trait ",
+    "impl ",
+    "mod ",
+    "type ",
+    "class ",
+    "interface ",
+    "def ",
+    "function ",
+    "func ",
+    "module ",
+    "object ",
+];
+
+/// The modifiers which precede the keyword of a declaration, e.g. `pub` or `export`
+const MODIFIERS: &[&str] = &[
+    "pub ",
+    "pub(crate) ",
+    "pub(super) ",
+    "export ",
+    "default ",
+    "async ",
+    "unsafe ",
+    "const ",
+    "public ",
+    "private ",
+    "protected ",
+    "internal ",
+    "static ",
+    "abstract ",
+    "final ",
+    "sealed ",
+    "data ",
+    "open ",
+];
+
+/// The structure of a file, i.e. its imports and the declarations of its functions and types
+#[derive(Default)]
+pub(crate) struct Outline {
+    pub(crate) imports: Vec<String>,
+    pub(crate) declarations: Vec<String>,
+}
+
+impl Outline {
+    /// Finds the imports and declarations by the keywords their lines start with. Only top-level
+    /// and one level of nesting, e.g. the methods of a class, are considered.
+    pub(crate) fn of<'a>(lines: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut outline = Self::default();
+        for line in lines {
+            let indentation = line.len() - line.trim_start().len();
+            if indentation > 4 {
+                continue;
+            }
+            let line = line.trim();
+            if is_import(line) {
+                outline.imports.push(line.to_string());
+                continue;
+            }
+            let mut declaration = line;
+            while let Some(rest) = MODIFIERS
+                .iter()
+                .find_map(|modifier| declaration.strip_prefix(modifier))
+            {
+                declaration = rest;
+            }
+            if DECLARATION_KEYWORDS
+                .iter()
+                .any(|keyword| declaration.starts_with(keyword))
+            {
+                let signature = line.trim_end_matches(['{', ':', ' ']);
+                outline.declarations.push(signature.to_string());
+            }
+        }
+        outline
+    }
+}
@@ -0,0 +1,93 @@
+use dialoguer::{theme::ColorfulTheme, Select};
+use serde::Deserialize;
+
+use crate::{
+    diff::{file_diffs, FileDiff},
+    package_of, Cli, Error, Package,
+};
+
+/// How the staged files are grouped into commits by `--per-file`
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Grouping {
+    /// Every file is committed on its own
+    #[default]
+    File,
+    /// The files of a directory are committed together
+    Directory,
+    /// The files of a package of a monorepo are committed together, the other files by
+    /// directory
+    Package,
+}
+
+impl Grouping {
+    /// The name of the group of the file, e.g. its directory
+    fn group(self, file: &FileDiff, packages: &[Package]) -> String {
+        let directory = || {
+            file.path
+                .rsplit_once('/')
+                .map_or(".", |(directory, _)| directory)
+                .to_string()
+        };
+        match self {
+            Grouping::File => file.path.clone(),
+            Grouping::Directory => directory(),
+            Grouping::Package => package_of(packages, &file.path)
+                .map_or_else(directory, |package| package.directory.clone()),
+        }
+    }
+}
+
+impl Cli {
+    /// Commits the staged changes one file or group at a time, each with its own generated
+    /// message. The changes which aren't committed, e.g. because a message wasn't picked, stay
+    /// staged.
+    pub(crate) async fn commit_per_file(&self) -> Result<(), Error> {
+        let diff = self.get_git_diff()?;
+        if diff.is_empty() {
+            return Err(Error::EmptyDiff);
+        }
+
+        let packages = self.packages();
+        let mut groups = Vec::<(String, Vec<FileDiff>)>::new();
+        for file in file_diffs(&diff) {
+            let group = self.config.group_by.group(&file, &packages);
+            match groups.iter_mut().find(|(name, _)| *name == group) {
+                Some((_, files)) => files.push(file),
+                None => groups.push((group, vec![file])),
+            }
+        }
+
+        let tree = self.vcs.write_index_tree()?;
+        for (name, files) in groups {
+            let diff = files.iter().map(|file| file.text).collect::<String>();
+            let response = self.get_response(diff, None).await?;
+            let selection = response
+                .iter()
+                .map(|message| message.lines().next().unwrap_or_default())
+                .collect::<Vec<_>>();
+            let Ok(index) = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!("Pick commit message for {name}"))
+                .default(0)
+                .items(&selection)
+                .interact()
+            else {
+                return Ok(());
+            };
+            let message = response.get(index).ok_or(Error::EmptySelection)?;
+            // The old path of a renamed file is committed as well.
+            let paths = files
+                .iter()
+                .flat_map(|file| [Some(file.path.as_str()), file.renamed_from()])
+                .flatten()
+                .collect::<Vec<_>>();
+            // The index only contains the changes of the group while they're committed, so it's
+            // intact if the user cancels while picking a message.
+            self.vcs.stage_only(&tree, &paths)?;
+            let committed = self.commit(message);
+            self.vcs.restore_index(&tree)?;
+            committed?;
+        }
+        Ok(())
+    }
+}
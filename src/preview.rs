@@ -0,0 +1,51 @@
+use std::io::{self, IsTerminal};
+
+use dialoguer::{theme::ColorfulTheme, Confirm};
+
+use crate::{Cli, CompletionRequest, Error};
+
+/// The item of the selection which shows the prompt of the suggestions
+pub(crate) const SHOW_PROMPT: &str = "(show the prompt)";
+
+/// The system and user message of the request as they're sent
+fn render(request: &CompletionRequest) -> String {
+    format!(
+        "System message:\n{}\n\nUser message:\n{}",
+        request.system.trim(),
+        request.user.trim()
+    )
+}
+
+impl Cli {
+    /// Remembers the prompt of the request for the selection and prints it with `--show-prompt`.
+    /// If there is a terminal to ask, the request is only sent after a confirmation.
+    pub(crate) fn preview_prompt(&self, request: &CompletionRequest) -> Result<(), Error> {
+        let prompt = render(request);
+        *self.prompt.lock().expect("expect an unpoisoned lock") = Some(prompt.clone());
+        if !self.args.show_prompt {
+            return Ok(());
+        }
+        eprintln!("{prompt}\n");
+        if self.args.stdin || !io::stdin().is_terminal() {
+            return Ok(());
+        }
+        let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Send this prompt to {}?", request.model))
+            .default(true)
+            .interact()
+            .unwrap_or(false);
+        if confirmed {
+            Ok(())
+        } else {
+            Err(Error::Aborted)
+        }
+    }
+
+    /// The prompt of the last request, `None` if nothing was sent yet
+    pub(crate) fn last_prompt(&self) -> Option<String> {
+        self.prompt
+            .lock()
+            .expect("expect an unpoisoned lock")
+            .clone()
+    }
+}
@@ -0,0 +1,43 @@
+use serde::Deserialize;
+
+use crate::{diff::file_diffs, is_ignored, Cli, Error};
+
+/// How much of the changed code may leave the machine
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Privacy {
+    /// The complete diff is sent
+    #[default]
+    Full,
+    /// Only the names of the changed files, the amount of changed lines and the hunk headers
+    /// are sent
+    Stats,
+}
+
+impl Cli {
+    /// The configured privacy mode
+    pub(crate) fn privacy(&self) -> Privacy {
+        self.args.privacy.unwrap_or(self.config.privacy)
+    }
+
+    /// The statistics of the diff without any code, i.e. like `git diff --stat` the changed
+    /// files with the amount of added and removed lines, followed by the headers of their hunks.
+    /// The hunk headers of the files in `.commitgptignore` are left out.
+    pub(crate) fn diff_stats(&self, diff: &str) -> Result<String, Error> {
+        let ignore_file = self.ignore_file()?;
+        let mut stats = String::new();
+        for file in file_diffs(diff) {
+            stats.push_str(&format!("{}\n", file.stat()));
+            if ignore_file
+                .as_ref()
+                .is_some_and(|ignore_file| is_ignored(ignore_file, &file.path))
+            {
+                continue;
+            }
+            for hunk in file.text.lines().filter(|line| line.starts_with("@@ ")) {
+                stats.push_str(&format!("    {hunk}\n"));
+            }
+        }
+        Ok(stats)
+    }
+}
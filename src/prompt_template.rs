@@ -0,0 +1,57 @@
+use crate::Error;
+
+/// The variables which can be used in the prompt template
+pub(crate) const TEMPLATE_VARIABLES: &[&str] =
+    &["diff", "diffstat", "files", "branch", "reason", "author"];
+
+/// The names of the `{{variable}}` placeholders of the template with their positions
+fn placeholders(template: &str) -> Vec<(usize, usize, &str)> {
+    let mut placeholders = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = template[offset..].find("{{") {
+        let start = offset + start;
+        let Some(length) = template[start..].find("}}") else {
+            break;
+        };
+        let end = start + length + 2;
+        placeholders.push((start, end, template[start + 2..end - 2].trim()));
+        offset = end;
+    }
+    placeholders
+}
+
+/// Whether the template uses the variable
+pub(crate) fn uses_variable(template: &str, name: &str) -> bool {
+    placeholders(template)
+        .iter()
+        .any(|(_, _, variable)| *variable == name)
+}
+
+/// Replaces the `{{variable}}` placeholders of the template with the values of the variables,
+/// like in Tera or Handlebars templates
+pub(crate) fn render_template(template: &str, variables: &[(&str, &str)]) -> Result<String, Error> {
+    render_with(template, |name| {
+        variables
+            .iter()
+            .find(|(variable, _)| *variable == name)
+            .map(|(_, value)| value.to_string())
+            .ok_or_else(|| Error::UnknownTemplateVariable(name.to_string()))
+    })
+}
+
+/// Replaces the `{{variable}}` placeholders of the template with the values `value` looks up
+/// by the names of the variables
+pub(crate) fn render_with(
+    template: &str,
+    mut value: impl FnMut(&str) -> Result<String, Error>,
+) -> Result<String, Error> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut offset = 0;
+    for (start, end, name) in placeholders(template) {
+        rendered.push_str(&template[offset..start]);
+        rendered.push_str(&value(name)?);
+        offset = end;
+    }
+    rendered.push_str(&template[offset..]);
+    Ok(rendered)
+}
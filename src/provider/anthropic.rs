@@ -0,0 +1,103 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::{response_error, CompletionProvider, CompletionRequest};
+use crate::Error;
+
+const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+const API_VERSION: &str = "2023-06-01";
+
+/// Anthropic's messages API, see https://docs.anthropic.com/en/api/messages
+pub(crate) struct Anthropic {
+    client: reqwest::Client,
+    api_key: String,
+    base_url: String,
+}
+
+#[derive(Serialize)]
+struct MessagesRequest<'a> {
+    model: &'a str,
+    max_tokens: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    // The messages API has no penalties.
+    system: &'a str,
+    messages: [Message<'a>; 1],
+}
+
+#[derive(Serialize)]
+struct Message<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct MessagesResponse {
+    content: Vec<ContentBlock>,
+}
+
+#[derive(Deserialize)]
+struct ContentBlock {
+    #[serde(default)]
+    text: String,
+}
+
+impl Anthropic {
+    pub(crate) fn new(client: reqwest::Client, api_key: String, base_url: Option<String>) -> Self {
+        Self {
+            client,
+            api_key,
+            base_url: base_url
+                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for Anthropic {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Vec<String>, Error> {
+        let body = MessagesRequest {
+            model: &request.model,
+            max_tokens: request.max_tokens,
+            // The messages API only accepts temperatures up to 1, unlike OpenAI's 2.
+            temperature: request.temperature.map(|temperature| temperature.min(1.0)),
+            top_p: request.top_p,
+            system: &request.system,
+            messages: [Message {
+                role: "user",
+                content: &request.user,
+            }],
+        };
+
+        // The messages API always returns a single message, so every suggestion is a request
+        // on its own.
+        let mut choices = Vec::with_capacity(request.suggestions.into());
+        for _ in 0..request.suggestions {
+            let response = self
+                .client
+                .post(format!("{}/v1/messages", self.base_url))
+                .header("x-api-key", &self.api_key)
+                .header("anthropic-version", API_VERSION)
+                .json(&body)
+                .timeout(request.timeout)
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                return Err(response_error(response).await);
+            }
+            let message = response.json::<MessagesResponse>().await?;
+            choices.push(
+                message
+                    .content
+                    .into_iter()
+                    .map(|block| block.text)
+                    .collect::<String>(),
+            );
+        }
+        Ok(choices)
+    }
+}
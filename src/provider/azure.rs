@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use super::{
+    chat_completions::{self, ChatCompletionRequest},
+    CompletionProvider, CompletionRequest, OnDelta,
+};
+use crate::Error;
+
+/// Azure OpenAI, see https://learn.microsoft.com/en-us/azure/ai-services/openai/reference
+pub(crate) struct Azure {
+    client: reqwest::Client,
+    api_key: String,
+    resource_url: String,
+    api_version: String,
+    deployment: Option<String>,
+    deployments: HashMap<String, String>,
+    default_deployment: Option<String>,
+}
+
+impl Azure {
+    pub(crate) fn new(
+        client: reqwest::Client,
+        api_key: String,
+        resource_url: String,
+        api_version: String,
+        deployment: Option<String>,
+    ) -> Self {
+        Self {
+            client,
+            api_key,
+            resource_url: resource_url.trim_end_matches('/').to_string(),
+            api_version,
+            deployment,
+            deployments: HashMap::new(),
+            default_deployment: None,
+        }
+    }
+
+    /// Maps models to the deployments of this resource, models without a mapping use the default
+    /// deployment if there is one.
+    pub(crate) fn with_deployments(
+        mut self,
+        deployments: HashMap<String, String>,
+        default_deployment: Option<String>,
+    ) -> Self {
+        self.deployments = deployments;
+        self.default_deployment = default_deployment;
+        self
+    }
+
+    fn post(&self, request: &CompletionRequest) -> reqwest::RequestBuilder {
+        // Azure addresses models by their deployment name, which is commonly named after the model.
+        let deployment = self
+            .deployment
+            .as_ref()
+            .or(self.deployments.get(&request.model))
+            .or(self.default_deployment.as_ref())
+            .unwrap_or(&request.model);
+        let url = format!(
+            "{}/openai/deployments/{}/chat/completions",
+            self.resource_url, deployment
+        );
+        self.client
+            .post(url)
+            .query(&[("api-version", &self.api_version)])
+            .header("api-key", &self.api_key)
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for Azure {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Vec<String>, Error> {
+        chat_completions::create(
+            self.post(request),
+            &ChatCompletionRequest::new(request).without_model(),
+        )
+        .await
+    }
+
+    async fn complete_streaming(
+        &self,
+        request: &CompletionRequest,
+        on_delta: &OnDelta<'_>,
+    ) -> Result<Vec<String>, Error> {
+        chat_completions::create_streaming(
+            self.post(request),
+            &ChatCompletionRequest::new(request)
+                .without_model()
+                .streaming(),
+            on_delta,
+        )
+        .await
+    }
+}
@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use aws_sdk_bedrockruntime::{
+    error::DisplayErrorContext,
+    types::{
+        ContentBlock, ConversationRole, ConverseOutput, InferenceConfiguration, Message,
+        SystemContentBlock,
+    },
+    Client,
+};
+
+use super::{CompletionProvider, CompletionRequest};
+use crate::Error;
+
+/// AWS Bedrock via the converse API, authenticated with the AWS SDK credential chain
+pub(crate) struct Bedrock {
+    client: Client,
+}
+
+impl Bedrock {
+    pub(crate) async fn new(region: Option<String>, profile: Option<String>) -> Self {
+        let mut loader = aws_config::defaults(aws_config::BehaviorVersion::latest());
+        if let Some(region) = region {
+            loader = loader.region(aws_config::Region::new(region));
+        }
+        if let Some(profile) = profile {
+            loader = loader.profile_name(profile);
+        }
+        Self {
+            client: Client::new(&loader.load().await),
+        }
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for Bedrock {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Vec<String>, Error> {
+        let message = Message::builder()
+            .role(ConversationRole::User)
+            .content(ContentBlock::Text(request.user.clone()))
+            .build()
+            .map_err(|error| Error::FetchData(error.to_string()))?;
+        let inference_config = InferenceConfiguration::builder()
+            .max_tokens(i32::try_from(request.max_tokens).unwrap_or(i32::MAX))
+            .set_temperature(request.temperature)
+            .set_top_p(request.top_p)
+            .build();
+
+        // The converse API always returns a single message, so every suggestion is a request on
+        // its own.
+        let mut choices = Vec::with_capacity(request.suggestions.into());
+        for _ in 0..request.suggestions {
+            let response = self
+                .client
+                .converse()
+                .model_id(&request.model)
+                .system(SystemContentBlock::Text(request.system.clone()))
+                .messages(message.clone())
+                .inference_config(inference_config.clone())
+                .send()
+                .await
+                .map_err(|error| Error::FetchData(DisplayErrorContext(error).to_string()))?;
+            if let Some(ConverseOutput::Message(message)) = response.output {
+                choices.push(
+                    message
+                        .content
+                        .into_iter()
+                        .filter_map(|block| match block {
+                            ContentBlock::Text(text) => Some(text),
+                            _ => None,
+                        })
+                        .collect::<String>(),
+                );
+            }
+        }
+        Ok(choices)
+    }
+}
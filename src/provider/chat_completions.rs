@@ -0,0 +1,203 @@
+//! The wire format of OpenAI's chat completions API, which is shared by several providers.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::{response_error, CompletionRequest, OnDelta};
+use crate::{
+    message::CommitParts,
+    model::{is_reasoning, ReasoningEffort},
+    Error,
+};
+
+#[derive(Serialize)]
+pub(super) struct ChatCompletionRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<&'a str>,
+    messages: [Message<'a>; 2],
+    n: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_completion_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    random_seed: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<ReasoningEffort>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+    /// The timeout of the response, or between two chunks of a streamed response
+    #[serde(skip)]
+    timeout: Duration,
+}
+
+#[derive(Serialize)]
+struct Message<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<Choice>,
+}
+
+#[derive(Deserialize)]
+struct Choice {
+    message: ResponseMessage,
+}
+
+#[derive(Default, Deserialize)]
+struct ResponseMessage {
+    content: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChatCompletionChunk {
+    choices: Vec<ChunkChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChunkChoice {
+    index: usize,
+    // Azure sends the results of its content filter as chunks without a delta.
+    #[serde(default)]
+    delta: ResponseMessage,
+}
+
+impl<'a> ChatCompletionRequest<'a> {
+    pub(super) fn new(request: &'a CompletionRequest) -> Self {
+        // Reasoning models replace the system role by the developer role and count the reasoning
+        // tokens towards `max_completion_tokens`, they reject `max_tokens` and the sampling
+        // parameters altogether.
+        let reasoning = is_reasoning(&request.model);
+        Self {
+            model: Some(&request.model),
+            messages: [
+                Message {
+                    role: if reasoning { "developer" } else { "system" },
+                    content: &request.system,
+                },
+                Message {
+                    role: "user",
+                    content: &request.user,
+                },
+            ],
+            n: request.suggestions,
+            max_tokens: (!reasoning).then_some(request.max_tokens),
+            max_completion_tokens: reasoning.then_some(request.max_tokens),
+            temperature: request.temperature.filter(|_| !reasoning),
+            top_p: request.top_p.filter(|_| !reasoning),
+            presence_penalty: request.presence_penalty.filter(|_| !reasoning),
+            frequency_penalty: request.frequency_penalty.filter(|_| !reasoning),
+            seed: request.seed,
+            random_seed: None,
+            reasoning_effort: request.reasoning_effort.filter(|_| reasoning),
+            response_format: request.structured.then(|| {
+                serde_json::json!({
+                    "type": "json_schema",
+                    "json_schema": {
+                        "name": "commit_message",
+                        "strict": true,
+                        "schema": CommitParts::schema(),
+                    },
+                })
+            }),
+            stream: false,
+            timeout: request.timeout,
+        }
+    }
+
+    /// Requests the response as server-sent events
+    pub(super) fn streaming(mut self) -> Self {
+        self.stream = true;
+        self
+    }
+
+    /// Sends the seed as `random_seed`, like Mistral expects it
+    pub(super) fn with_random_seed(mut self) -> Self {
+        self.random_seed = self.seed.take();
+        self
+    }
+
+    /// Omits the model from the request body, for APIs which encode it in the URL
+    pub(super) fn without_model(mut self) -> Self {
+        self.model = None;
+        self
+    }
+}
+
+/// Sends the chat completion request and returns the content of every choice
+pub(super) async fn create(
+    request: reqwest::RequestBuilder,
+    body: &ChatCompletionRequest<'_>,
+) -> Result<Vec<String>, Error> {
+    let response = request.json(body).timeout(body.timeout).send().await?;
+    if !response.status().is_success() {
+        return Err(response_error(response).await);
+    }
+    Ok(response
+        .json::<ChatCompletionResponse>()
+        .await?
+        .choices
+        .into_iter()
+        .filter_map(|choice| choice.message.content)
+        .collect())
+}
+
+/// Sends the chat completion request as a stream, calling `on_delta` for every received part of a
+/// choice, and returns the content of every choice
+pub(super) async fn create_streaming(
+    request: reqwest::RequestBuilder,
+    body: &ChatCompletionRequest<'_>,
+    on_delta: &OnDelta<'_>,
+) -> Result<Vec<String>, Error> {
+    let mut response = request.json(body).send().await?;
+    if !response.status().is_success() {
+        return Err(response_error(response).await);
+    }
+
+    let mut choices = vec![String::new(); body.n.into()];
+    let mut buffer = Vec::new();
+    // A long response isn't cut off as long as its chunks keep arriving.
+    while let Some(chunk) = tokio::time::timeout(body.timeout, response.chunk())
+        .await
+        .map_err(|_| Error::Unavailable("the stream stopped sending".to_string()))??
+    {
+        buffer.extend_from_slice(&chunk);
+        while let Some(position) = buffer.iter().position(|byte| *byte == b'\n') {
+            let line = buffer.drain(..=position).collect::<Vec<_>>();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim().strip_prefix("data:").map(str::trim) else {
+                continue;
+            };
+            if data == "[DONE]" {
+                return Ok(choices);
+            }
+            for choice in serde_json::from_str::<ChatCompletionChunk>(data)?.choices {
+                let Some(content) = choice.delta.content else {
+                    continue;
+                };
+                if choice.index >= choices.len() {
+                    choices.resize(choice.index + 1, String::new());
+                }
+                choices[choice.index].push_str(&content);
+                on_delta(choice.index, &content);
+            }
+        }
+    }
+    Ok(choices)
+}
@@ -0,0 +1,60 @@
+use std::borrow::Cow;
+
+use async_trait::async_trait;
+
+use super::{CompletionProvider, CompletionRequest, OnDelta};
+use crate::Error;
+
+/// Tries the providers in order, falling back to the next one if a provider is unavailable
+pub(crate) struct Fallback {
+    /// The providers with the model which should be used, `None` keeps the requested model
+    providers: Vec<(Box<dyn CompletionProvider>, Option<String>)>,
+}
+
+impl Fallback {
+    pub(crate) fn new(providers: Vec<(Box<dyn CompletionProvider>, Option<String>)>) -> Self {
+        Self { providers }
+    }
+
+    async fn complete_with(
+        &self,
+        request: &CompletionRequest,
+        on_delta: Option<&OnDelta<'_>>,
+    ) -> Result<Vec<String>, Error> {
+        for (index, (provider, model)) in self.providers.iter().enumerate() {
+            let request = match model {
+                Some(model) => Cow::Owned(CompletionRequest {
+                    model: model.clone(),
+                    ..request.clone()
+                }),
+                None => Cow::Borrowed(request),
+            };
+            let result = match on_delta {
+                Some(on_delta) => provider.complete_streaming(&request, on_delta).await,
+                None => provider.complete(&request).await,
+            };
+            match result {
+                Err(error) if error.is_retryable() && index + 1 < self.providers.len() => {
+                    eprintln!("{error}, falling back to the next provider");
+                }
+                result => return result,
+            }
+        }
+        unreachable!("the fallback chain always contains the configured provider")
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for Fallback {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Vec<String>, Error> {
+        self.complete_with(request, None).await
+    }
+
+    async fn complete_streaming(
+        &self,
+        request: &CompletionRequest,
+        on_delta: &OnDelta<'_>,
+    ) -> Result<Vec<String>, Error> {
+        self.complete_with(request, Some(on_delta)).await
+    }
+}
@@ -0,0 +1,150 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::{response_error, CompletionProvider, CompletionRequest};
+use crate::Error;
+
+const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+
+/// Google's Gemini API, see https://ai.google.dev/api/generate-content
+pub(crate) struct Gemini {
+    client: reqwest::Client,
+    api_key: String,
+    base_url: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateContentRequest<'a> {
+    system_instruction: Content<'a>,
+    contents: [Content<'a>; 1],
+    generation_config: GenerationConfig,
+}
+
+#[derive(Serialize)]
+struct Content<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<&'a str>,
+    parts: [Part<'a>; 1],
+}
+
+#[derive(Serialize)]
+struct Part<'a> {
+    text: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerationConfig {
+    candidate_count: u8,
+    max_output_tokens: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<&'static str>,
+}
+
+#[derive(Deserialize)]
+struct GenerateContentResponse {
+    #[serde(default)]
+    candidates: Vec<Candidate>,
+}
+
+#[derive(Deserialize)]
+struct Candidate {
+    content: Option<CandidateContent>,
+}
+
+#[derive(Deserialize)]
+struct CandidateContent {
+    #[serde(default)]
+    parts: Vec<CandidatePart>,
+}
+
+#[derive(Deserialize)]
+struct CandidatePart {
+    #[serde(default)]
+    text: String,
+}
+
+impl Gemini {
+    pub(crate) fn new(client: reqwest::Client, api_key: String, base_url: Option<String>) -> Self {
+        Self {
+            client,
+            api_key,
+            base_url: base_url
+                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for Gemini {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Vec<String>, Error> {
+        let body = GenerateContentRequest {
+            system_instruction: Content {
+                role: None,
+                parts: [Part {
+                    text: &request.system,
+                }],
+            },
+            contents: [Content {
+                role: Some("user"),
+                parts: [Part {
+                    text: &request.user,
+                }],
+            }],
+            generation_config: GenerationConfig {
+                candidate_count: request.suggestions,
+                max_output_tokens: request.max_tokens,
+                temperature: request.temperature,
+                top_p: request.top_p,
+                presence_penalty: request.presence_penalty,
+                frequency_penalty: request.frequency_penalty,
+                seed: request.seed,
+                // Gemini's response schemas are an OpenAPI subset, the JSON mode together with the
+                // instructions of the prompt is good enough.
+                response_mime_type: request.structured.then_some("application/json"),
+            },
+        };
+
+        let response = self
+            .client
+            .post(format!(
+                "{}/models/{}:generateContent",
+                self.base_url, request.model
+            ))
+            .header("x-goog-api-key", &self.api_key)
+            .json(&body)
+            .timeout(request.timeout)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(response_error(response).await);
+        }
+        Ok(response
+            .json::<GenerateContentResponse>()
+            .await?
+            .candidates
+            .into_iter()
+            .filter_map(|candidate| candidate.content)
+            .map(|content| {
+                content
+                    .parts
+                    .into_iter()
+                    .map(|part| part.text)
+                    .collect::<String>()
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+
+use super::{
+    chat_completions::{self, ChatCompletionRequest},
+    CompletionProvider, CompletionRequest, OnDelta,
+};
+use crate::Error;
+
+const DEFAULT_BASE_URL: &str = "https://api.mistral.ai/v1";
+
+/// Mistral's hosted API, see https://docs.mistral.ai/api/#tag/chat
+pub(crate) struct Mistral {
+    client: reqwest::Client,
+    api_key: String,
+    base_url: String,
+}
+
+impl Mistral {
+    pub(crate) fn new(client: reqwest::Client, api_key: String, base_url: Option<String>) -> Self {
+        Self {
+            client,
+            api_key,
+            base_url: base_url
+                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+        }
+    }
+
+    fn post(&self) -> reqwest::RequestBuilder {
+        self.client
+            .post(format!("{}/chat/completions", self.base_url))
+            .bearer_auth(&self.api_key)
+    }
+}
+
+// The chat completions API of Mistral is compatible with OpenAI's, including `n`, except for the
+// name of the seed.
+#[async_trait]
+impl CompletionProvider for Mistral {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Vec<String>, Error> {
+        chat_completions::create(
+            self.post(),
+            &ChatCompletionRequest::new(request).with_random_seed(),
+        )
+        .await
+    }
+
+    async fn complete_streaming(
+        &self,
+        request: &CompletionRequest,
+        on_delta: &OnDelta<'_>,
+    ) -> Result<Vec<String>, Error> {
+        chat_completions::create_streaming(
+            self.post(),
+            &ChatCompletionRequest::new(request)
+                .with_random_seed()
+                .streaming(),
+            on_delta,
+        )
+        .await
+    }
+}
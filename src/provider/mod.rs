@@ -0,0 +1,269 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::{
+    args::Args,
+    config::Config,
+    model::{resolve_model, Model, ReasoningEffort},
+    Error,
+};
+
+mod anthropic;
+mod azure;
+#[cfg(feature = "bedrock")]
+mod bedrock;
+mod chat_completions;
+mod fallback;
+mod gemini;
+mod mistral;
+mod ollama;
+mod openai;
+mod responses;
+
+use self::anthropic::Anthropic;
+use self::azure::Azure;
+#[cfg(feature = "bedrock")]
+use self::bedrock::Bedrock;
+use self::fallback::Fallback;
+use self::gemini::Gemini;
+use self::mistral::Mistral;
+use self::ollama::Ollama;
+use self::openai::OpenAi;
+pub(crate) use self::openai::OpenAiApi;
+
+/// The backend which generates the commit messages
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ProviderKind {
+    #[default]
+    #[value(name = "openai")]
+    OpenAi,
+    Ollama,
+    Anthropic,
+    Azure,
+    Gemini,
+    Mistral,
+    #[cfg(feature = "bedrock")]
+    Bedrock,
+}
+
+impl ProviderKind {
+    /// The model which is used if neither the config nor the command line specify one
+    pub(crate) fn default_model(self) -> &'static str {
+        match self {
+            ProviderKind::OpenAi | ProviderKind::Azure => Model::default().id(),
+            ProviderKind::Ollama => "llama3.2",
+            ProviderKind::Anthropic => "claude-3-5-haiku-latest",
+            ProviderKind::Gemini => "gemini-1.5-flash",
+            ProviderKind::Mistral => "mistral-small-latest",
+            #[cfg(feature = "bedrock")]
+            ProviderKind::Bedrock => "anthropic.claude-3-5-haiku-20241022-v1:0",
+        }
+    }
+}
+
+/// Everything a provider needs to generate commit message suggestions
+#[derive(Clone)]
+pub(crate) struct CompletionRequest {
+    pub(crate) model: String,
+    pub(crate) system: String,
+    pub(crate) user: String,
+    pub(crate) suggestions: u8,
+    pub(crate) max_tokens: u64,
+    /// The sampling temperature, `None` for the default of the provider
+    pub(crate) temperature: Option<f32>,
+    /// The nucleus sampling probability, `None` for the default of the provider
+    pub(crate) top_p: Option<f32>,
+    /// The penalty of tokens which already occur, only sent to providers which support it
+    pub(crate) presence_penalty: Option<f32>,
+    /// The penalty of tokens by how often they already occur, only sent to providers which
+    /// support it
+    pub(crate) frequency_penalty: Option<f32>,
+    /// The seed of the sampling, so the same request gives the same suggestions on providers
+    /// which support it
+    pub(crate) seed: Option<u32>,
+    /// The timeout of a whole response, or between two parts of a streamed response
+    pub(crate) timeout: Duration,
+    pub(crate) reasoning_effort: Option<ReasoningEffort>,
+    /// Whether the response should be structured as `CommitParts`, if the provider supports it
+    pub(crate) structured: bool,
+}
+
+/// A backend which turns a prompt into commit message suggestions
+#[async_trait]
+pub(crate) trait CompletionProvider: Send + Sync {
+    /// Generates `request.suggestions` commit messages for the given prompt
+    async fn complete(&self, request: &CompletionRequest) -> Result<Vec<String>, Error>;
+
+    /// Like `complete`, but streams the response by calling `on_delta` with the index of the
+    /// suggestion and the new text as soon as it arrives. Providers without streaming support
+    /// report every suggestion at once.
+    async fn complete_streaming(
+        &self,
+        request: &CompletionRequest,
+        on_delta: &OnDelta<'_>,
+    ) -> Result<Vec<String>, Error> {
+        let choices = self.complete(request).await?;
+        for (index, choice) in choices.iter().enumerate() {
+            on_delta(index, choice);
+        }
+        Ok(choices)
+    }
+}
+
+/// The callback of a streamed response, called with the index of the suggestion and the new text
+pub(crate) type OnDelta<'a> = dyn Fn(usize, &str) + Send + Sync + 'a;
+
+/// Creates the configured provider, wrapped with the configured fallback providers if there are
+/// any. The command line arguments take precedence over the config.
+pub(crate) async fn from_config(
+    kind: ProviderKind,
+    config: &Config,
+    args: &Args,
+) -> Result<Box<dyn CompletionProvider>, Error> {
+    // A streamed response takes longer than a single request, so the timeout of the response is
+    // applied by the providers, see `CompletionRequest::timeout`.
+    let client = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(config.timeout))
+        .build()?;
+    let provider = create(
+        kind,
+        client.clone(),
+        config,
+        args,
+        config.api_key.clone(),
+        args.base_url.clone().or(config.base_url.clone()),
+    )
+    .await?;
+    if config.fallback.is_empty() {
+        return Ok(provider);
+    }
+
+    let mut providers = vec![(provider, None)];
+    for (index, fallback) in config.fallback.iter().enumerate() {
+        let kind = fallback.provider.unwrap_or(kind);
+        let model = resolve_model(
+            fallback.model.as_deref().unwrap_or(kind.default_model()),
+            &config.aliases,
+        );
+        // The credentials of the configured provider would be sent to another endpoint, so
+        // every fallback has its own.
+        let provider = create(
+            kind,
+            client.clone(),
+            config,
+            args,
+            fallback.api_key(),
+            fallback.base_url.clone(),
+        )
+        .await
+        .map_err(|err| match err {
+            Error::MissingApiKey | Error::MissingBaseUrl => {
+                Error::MissingFallbackCredentials(index + 1)
+            }
+            err => err,
+        })?;
+        providers.push((provider, Some(model)));
+    }
+    Ok(Box::new(Fallback::new(providers)))
+}
+
+/// Creates the provider of the given kind with the given credentials
+async fn create(
+    kind: ProviderKind,
+    client: reqwest::Client,
+    config: &Config,
+    args: &Args,
+    api_key: Option<String>,
+    base_url: Option<String>,
+) -> Result<Box<dyn CompletionProvider>, Error> {
+    Ok(match kind {
+        ProviderKind::OpenAi => Box::new(
+            OpenAi::new(client, api_key, base_url)?
+                .with_attribution(config.organization.clone(), config.project.clone())
+                .with_api(config.api),
+        ),
+        ProviderKind::Ollama => Box::new(Ollama::new(client, base_url)),
+        ProviderKind::Anthropic => Box::new(Anthropic::new(
+            client,
+            api_key.ok_or(Error::MissingApiKey)?,
+            base_url,
+        )),
+        ProviderKind::Azure => Box::new(
+            Azure::new(
+                client,
+                api_key.ok_or(Error::MissingApiKey)?,
+                base_url.ok_or(Error::MissingBaseUrl)?,
+                args.api_version
+                    .clone()
+                    .unwrap_or(config.api_version.clone()),
+                args.deployment.clone(),
+            )
+            .with_deployments(config.deployments.clone(), config.deployment.clone()),
+        ),
+        ProviderKind::Gemini => Box::new(Gemini::new(
+            client,
+            api_key.ok_or(Error::MissingApiKey)?,
+            base_url,
+        )),
+        ProviderKind::Mistral => Box::new(Mistral::new(
+            client,
+            api_key.ok_or(Error::MissingApiKey)?,
+            base_url,
+        )),
+        #[cfg(feature = "bedrock")]
+        ProviderKind::Bedrock => {
+            Box::new(Bedrock::new(config.region.clone(), config.aws_profile.clone()).await)
+        }
+    })
+}
+
+/// Converts a non successful response into an error, using the error message of the body if
+/// there is one.
+pub(crate) async fn response_error(response: reqwest::Response) -> Error {
+    let status = response.status();
+    let body = match response.text().await {
+        Ok(body) => body,
+        Err(error) => return Error::Http(error),
+    };
+    let message = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|json| error_message(&json))
+        .unwrap_or(body);
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+        return Error::Unavailable(format!("{status}: {message}"));
+    }
+    Error::FetchData(format!("{status}: {message}"))
+}
+
+/// Extracts the error message of the different error formats of the providers:
+///
+/// - `{"error": {"message": "..."}}` (OpenAI, Azure, Anthropic, Gemini)
+/// - `{"error": "..."}` (Ollama)
+/// - `{"message": "..."}` (Mistral)
+/// - `{"detail": [{"msg": "..."}]}` (Mistral's validation errors)
+fn error_message(json: &serde_json::Value) -> Option<String> {
+    if let Some(error) = json.get("error") {
+        return error
+            .get("message")
+            .unwrap_or(error)
+            .as_str()
+            .map(str::to_owned);
+    }
+    if let Some(message) = json.get("message").and_then(serde_json::Value::as_str) {
+        return Some(message.to_owned());
+    }
+    match json.get("detail")? {
+        serde_json::Value::String(detail) => Some(detail.clone()),
+        serde_json::Value::Array(details) => Some(
+            details
+                .iter()
+                .filter_map(|detail| detail.get("msg").and_then(serde_json::Value::as_str))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        _ => None,
+    }
+}
@@ -0,0 +1,112 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::{response_error, CompletionProvider, CompletionRequest};
+use crate::{message::CommitParts, Error};
+
+const DEFAULT_BASE_URL: &str = "http://localhost:11434";
+
+/// A local Ollama server, see https://github.com/ollama/ollama/blob/main/docs/api.md
+pub(crate) struct Ollama {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: [Message<'a>; 2],
+    stream: bool,
+    options: Options,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+struct Message<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Serialize)]
+struct Options {
+    num_predict: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    message: ResponseMessage,
+}
+
+#[derive(Deserialize)]
+struct ResponseMessage {
+    content: String,
+}
+
+impl Ollama {
+    pub(crate) fn new(client: reqwest::Client, base_url: Option<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url
+                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for Ollama {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Vec<String>, Error> {
+        let body = ChatRequest {
+            model: &request.model,
+            messages: [
+                Message {
+                    role: "system",
+                    content: &request.system,
+                },
+                Message {
+                    role: "user",
+                    content: &request.user,
+                },
+            ],
+            stream: false,
+            options: Options {
+                num_predict: request.max_tokens,
+                temperature: request.temperature,
+                top_p: request.top_p,
+                presence_penalty: request.presence_penalty,
+                frequency_penalty: request.frequency_penalty,
+                seed: request.seed,
+            },
+            format: request.structured.then(CommitParts::schema),
+        };
+
+        // Ollama has no equivalent of `n`, so every suggestion is a request on its own.
+        let mut choices = Vec::with_capacity(request.suggestions.into());
+        for _ in 0..request.suggestions {
+            let response = self
+                .client
+                .post(format!("{}/api/chat", self.base_url))
+                .json(&body)
+                .timeout(request.timeout)
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                return Err(response_error(response).await);
+            }
+            choices.push(response.json::<ChatResponse>().await?.message.content);
+        }
+        Ok(choices)
+    }
+}
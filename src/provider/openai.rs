@@ -0,0 +1,135 @@
+use async_trait::async_trait;
+
+use serde::Deserialize;
+
+use super::{
+    chat_completions::{self, ChatCompletionRequest},
+    responses::{self, ResponseRequest},
+    CompletionProvider, CompletionRequest, OnDelta,
+};
+use crate::Error;
+
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// The API of OpenAI which should be used
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum OpenAiApi {
+    #[default]
+    ChatCompletions,
+    Responses,
+}
+
+/// OpenAI or any server which speaks its chat completions API, e.g. LM Studio, vLLM or the
+/// llama.cpp server.
+pub(crate) struct OpenAi {
+    client: reqwest::Client,
+    api_key: Option<String>,
+    base_url: String,
+    organization: Option<String>,
+    project: Option<String>,
+    api: OpenAiApi,
+}
+
+impl OpenAi {
+    pub(crate) fn new(
+        client: reqwest::Client,
+        api_key: Option<String>,
+        base_url: Option<String>,
+    ) -> Result<Self, Error> {
+        // Self-hosted servers usually don't require authentication, OpenAI itself always does.
+        if api_key.is_none() && base_url.is_none() {
+            return Err(Error::MissingApiKey);
+        }
+        Ok(Self {
+            client,
+            api_key,
+            base_url: base_url
+                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            organization: None,
+            project: None,
+            api: OpenAiApi::default(),
+        })
+    }
+
+    /// Attributes the usage to the given organization and project
+    pub(crate) fn with_attribution(
+        mut self,
+        organization: Option<String>,
+        project: Option<String>,
+    ) -> Self {
+        self.organization = organization;
+        self.project = project;
+        self
+    }
+
+    /// Uses the given API instead of the chat completions API
+    pub(crate) fn with_api(mut self, api: OpenAiApi) -> Self {
+        self.api = api;
+        self
+    }
+
+    fn post(&self, path: &str) -> reqwest::RequestBuilder {
+        let mut builder = self.client.post(format!("{}/{path}", self.base_url));
+        if let Some(api_key) = &self.api_key {
+            builder = builder.bearer_auth(api_key);
+        }
+        if let Some(organization) = &self.organization {
+            builder = builder.header("OpenAI-Organization", organization);
+        }
+        if let Some(project) = &self.project {
+            builder = builder.header("OpenAI-Project", project);
+        }
+        builder
+    }
+}
+
+#[async_trait]
+impl CompletionProvider for OpenAi {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Vec<String>, Error> {
+        match self.api {
+            OpenAiApi::ChatCompletions => {
+                chat_completions::create(
+                    self.post("chat/completions"),
+                    &ChatCompletionRequest::new(request),
+                )
+                .await
+            }
+            OpenAiApi::Responses => {
+                responses::create(
+                    || self.post("responses"),
+                    &ResponseRequest::new(request),
+                    request.suggestions,
+                )
+                .await
+            }
+        }
+    }
+
+    async fn complete_streaming(
+        &self,
+        request: &CompletionRequest,
+        on_delta: &OnDelta<'_>,
+    ) -> Result<Vec<String>, Error> {
+        match self.api {
+            OpenAiApi::ChatCompletions => {
+                chat_completions::create_streaming(
+                    self.post("chat/completions"),
+                    &ChatCompletionRequest::new(request).streaming(),
+                    on_delta,
+                )
+                .await
+            }
+            // The responses API streams a different set of events, so it isn't streamed (yet).
+            OpenAiApi::Responses => {
+                let choices = self.complete(request).await?;
+                for (index, choice) in choices.iter().enumerate() {
+                    on_delta(index, choice);
+                }
+                Ok(choices)
+            }
+        }
+    }
+}
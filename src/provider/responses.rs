@@ -0,0 +1,125 @@
+//! The wire format of OpenAI's responses API, the successor of the chat completions API.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::{response_error, CompletionRequest};
+use crate::{
+    message::CommitParts,
+    model::{is_reasoning, ReasoningEffort},
+    Error,
+};
+
+#[derive(Serialize)]
+pub(super) struct ResponseRequest<'a> {
+    model: &'a str,
+    instructions: &'a str,
+    input: &'a str,
+    max_output_tokens: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning: Option<Reasoning>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<serde_json::Value>,
+    /// The timeout of the response
+    #[serde(skip)]
+    timeout: Duration,
+}
+
+#[derive(Serialize)]
+struct Reasoning {
+    effort: ReasoningEffort,
+}
+
+#[derive(Deserialize)]
+struct Response {
+    output: Vec<OutputItem>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum OutputItem {
+    Message {
+        content: Vec<OutputContent>,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum OutputContent {
+    OutputText {
+        text: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+impl<'a> ResponseRequest<'a> {
+    pub(super) fn new(request: &'a CompletionRequest) -> Self {
+        Self {
+            model: &request.model,
+            instructions: &request.system,
+            input: &request.user,
+            max_output_tokens: request.max_tokens,
+            // Reasoning models reject the sampling parameters, the responses API has no
+            // penalties.
+            temperature: request
+                .temperature
+                .filter(|_| !is_reasoning(&request.model)),
+            top_p: request.top_p.filter(|_| !is_reasoning(&request.model)),
+            reasoning: request
+                .reasoning_effort
+                .filter(|_| is_reasoning(&request.model))
+                .map(|effort| Reasoning { effort }),
+            text: request.structured.then(|| {
+                serde_json::json!({
+                    "format": {
+                        "type": "json_schema",
+                        "name": "commit_message",
+                        "strict": true,
+                        "schema": CommitParts::schema(),
+                    },
+                })
+            }),
+            timeout: request.timeout,
+        }
+    }
+}
+
+/// Sends the request once per suggestion, as the responses API has no equivalent of `n`, and
+/// returns the text of every response
+pub(super) async fn create(
+    request: impl Fn() -> reqwest::RequestBuilder + Send + Sync,
+    body: &ResponseRequest<'_>,
+    suggestions: u8,
+) -> Result<Vec<String>, Error> {
+    let mut choices = Vec::with_capacity(suggestions.into());
+    for _ in 0..suggestions {
+        let response = request().json(body).timeout(body.timeout).send().await?;
+        if !response.status().is_success() {
+            return Err(response_error(response).await);
+        }
+        let text = response
+            .json::<Response>()
+            .await?
+            .output
+            .into_iter()
+            .flat_map(|item| match item {
+                OutputItem::Message { content } => content,
+                OutputItem::Other => Vec::new(),
+            })
+            .filter_map(|content| match content {
+                OutputContent::OutputText { text } => Some(text),
+                OutputContent::Other => None,
+            })
+            .collect::<String>();
+        choices.push(text);
+    }
+    Ok(choices)
+}
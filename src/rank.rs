@@ -0,0 +1,156 @@
+use std::collections::HashSet;
+
+use crate::{
+    conventions::Convention,
+    diff::file_diffs,
+    gitmoji::{normalize_gitmoji, GitmojiFormat},
+    is_trailer, Cli, Commitlint, Header,
+};
+
+/// Words which describe a change without saying anything about it
+const VAGUE_WORDS: &[&str] = &[
+    "update", "updates", "updated", "change", "changes", "changed", "stuff", "misc", "various",
+    "some", "things", "minor", "tweak", "tweaks", "wip",
+];
+
+/// What a suggestion is scored against
+pub(crate) struct RankContext {
+    convention: Convention,
+    commitlint: Option<Commitlint>,
+    max_subject_length: usize,
+    /// The names of the changed files and the identifiers of the changed lines, in lowercase
+    identifiers: HashSet<String>,
+}
+
+/// Whether the word looks like an identifier of code, e.g. `parse_config` or `parseConfig`
+fn is_identifier(word: &str) -> bool {
+    let mut chars = word.chars();
+    word.len() >= 4
+        && chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && word.chars().all(|c| c.is_alphanumeric() || c == '_')
+        && (word.contains('_') || chars.any(char::is_uppercase))
+}
+
+/// The file names without extension and the identifiers of the changed lines, in lowercase
+fn diff_identifiers(diff: &str) -> HashSet<String> {
+    let mut identifiers = HashSet::new();
+    for file in file_diffs(diff) {
+        let name = file.path.rsplit('/').next().unwrap_or_default();
+        let stem = name.split('.').next().unwrap_or_default();
+        if stem.len() >= 3 {
+            identifiers.insert(stem.to_lowercase());
+        }
+        for line in file
+            .changed_lines('+')
+            .into_iter()
+            .chain(file.changed_lines('-'))
+        {
+            identifiers.extend(
+                line.split(|c: char| !c.is_alphanumeric() && c != '_')
+                    .filter(|word| is_identifier(word))
+                    .map(str::to_lowercase),
+            );
+        }
+    }
+    identifiers
+}
+
+/// Whether the description starts with a verb in imperative mood, i.e. not like `added`,
+/// `adding` or `adds`
+fn is_imperative(description: &str) -> bool {
+    let Some(verb) = description.split_whitespace().next() else {
+        return false;
+    };
+    let verb = verb.to_lowercase();
+    !(verb.ends_with("ed")
+        || verb.ends_with("ing")
+        || (verb.ends_with('s') && !verb.ends_with("ss") && verb.len() > 3))
+}
+
+impl RankContext {
+    /// The score of the suggestion from 0 to 100, by its compliance with the convention and
+    /// commitlint, the length of its subject, the mood, its specificity and whether it has a body
+    pub(crate) fn score(&self, message: &str) -> u8 {
+        let mut paragraphs = message.trim_end().split("\n\n").collect::<Vec<_>>();
+        // The trailers are the same for every suggestion.
+        if paragraphs.len() > 1
+            && paragraphs
+                .last()
+                .is_some_and(|last| last.lines().all(is_trailer))
+        {
+            paragraphs.pop();
+        }
+        let subject = paragraphs
+            .first()
+            .and_then(|first| first.lines().next())
+            .unwrap_or_default();
+        let description = match self.convention {
+            Convention::Conventional => Header::parse(subject).map(|header| header.subject),
+            Convention::Gitmoji => normalize_gitmoji(subject, GitmojiFormat::Code)
+                .is_some()
+                .then(|| subject.split_once(' ').map_or("", |(_, rest)| rest)),
+        };
+        let mut score = 0u32;
+        // The convention and the rules of commitlint
+        if description.is_some() {
+            let violations = self
+                .commitlint
+                .as_ref()
+                .map_or(0, |commitlint| commitlint.violations(message).len() as u32);
+            score += 30u32.saturating_sub(10 * violations);
+        }
+        let description = description.unwrap_or(subject).trim();
+        // The length of the subject
+        if subject.chars().count() <= self.max_subject_length {
+            score += 10;
+        }
+        if description.chars().count() >= 10 {
+            score += 10;
+        }
+        if is_imperative(description) {
+            score += 15;
+        }
+        // The specificity of the subject
+        let words = description
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>();
+        if words.iter().any(|word| self.identifiers.contains(word)) {
+            score += 15;
+        }
+        if !words
+            .iter()
+            .any(|word| VAGUE_WORDS.contains(&word.as_str()))
+        {
+            score += 10;
+        }
+        if paragraphs.len() > 1 {
+            score += 10;
+        }
+        score.min(100) as u8
+    }
+}
+
+impl Cli {
+    /// Whether the suggestions are ordered best-first by their score
+    pub(crate) fn rank_suggestions(&self) -> bool {
+        self.args.rank || self.config.rank_suggestions
+    }
+
+    /// The context to score the suggestions for the diff with
+    pub(crate) fn rank_context(&self, diff: &str) -> RankContext {
+        RankContext {
+            convention: self.convention(),
+            commitlint: self.commitlint(),
+            max_subject_length: self.max_subject_length().unwrap_or(72),
+            identifiers: diff_identifiers(diff),
+        }
+    }
+}
+
+/// Orders the suggestions best-first by their score, equal ones stay in the order of the model
+pub(crate) fn rank(context: &RankContext, mut choices: Vec<String>) -> Vec<String> {
+    choices.sort_by_cached_key(|choice| std::cmp::Reverse(context.score(choice)));
+    choices
+}
@@ -0,0 +1,66 @@
+use crate::{render_response, Cli, CompletionRequest, Error};
+
+/// The line which separates the critique from the improved message
+const SEPARATOR: &str = "---";
+
+/// The improved message of the response, i.e. everything after the last separator line. `None`
+/// if there is no separator, as the response can't be told apart from the critique then.
+fn improved_message(response: &str) -> Option<&str> {
+    let mut start = None;
+    let mut offset = 0;
+    for line in response.split_inclusive('\n') {
+        offset += line.len();
+        if line.trim() == SEPARATOR {
+            start = Some(offset);
+        }
+    }
+    start.map(|start| &response[start..])
+}
+
+impl Cli {
+    /// Sends the drafts back to the model to critique and improve them, with `--refine`. The
+    /// drafts are kept as they are otherwise.
+    pub(crate) async fn refine(
+        &self,
+        request: &CompletionRequest,
+        drafts: Vec<String>,
+    ) -> Result<Vec<String>, Error> {
+        if !(self.args.refine || self.config.refine) || drafts.is_empty() {
+            return Ok(drafts);
+        }
+        let mut rendered = Vec::new();
+        for draft in &drafts {
+            let draft = render_response(draft, request.structured);
+            if !rendered.contains(&draft) {
+                rendered.push(draft);
+            }
+        }
+        let mut request = request.clone();
+        // The critique comes before the message, which a structured response has no room for.
+        request.structured = false;
+        request.user.push_str(&format!(
+            r#"
+
+These are drafts of the commit message:
+{}
+
+Critique the drafts in a few short bullet points: are they accurate according to the diff, written in imperative mood and specific about what changed and why? Then write a line with `{SEPARATOR}` followed by the improved commit message only, in the format which is requested above.
+"#,
+            rendered
+                .iter()
+                .enumerate()
+                .map(|(index, draft)| format!("Draft {}:\n{draft}", index + 1))
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        ));
+        let refined = self
+            .complete(&request)
+            .await?
+            .iter()
+            .filter_map(|response| improved_message(response))
+            .filter(|message| !message.trim().is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        Ok(if refined.is_empty() { drafts } else { refined })
+    }
+}
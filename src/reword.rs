@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use dialoguer::{theme::ColorfulTheme, Confirm, Editor, Select};
+
+use crate::{append_trailers, commit_trailers, Cli, DiffOptions, Error};
+
+impl Cli {
+    /// Offers regenerated messages for every commit since the base and rewrites the commits
+    /// with the accepted ones
+    pub(crate) async fn reword_range(&self, base: &str) -> Result<(), Error> {
+        let range = format!("{base}..HEAD");
+        let commits = self.vcs.commits(&range)?;
+        let mut messages = HashMap::new();
+
+        for (index, commit) in commits.iter().enumerate() {
+            println!(
+                "\n[{}/{}] {}\n{}\n",
+                index + 1,
+                commits.len(),
+                &commit.id[..commit.id.len().min(10)],
+                commit.message
+            );
+            let diff = self.vcs.diff(&DiffOptions {
+                commit: Some(&commit.id),
+                ..self.diff_options()
+            })?;
+            if diff.is_empty() {
+                println!("The commit has no changes, skipping it.");
+                continue;
+            }
+
+            let trailers = commit_trailers(&commit.message);
+            let response = self
+                .get_response(diff, Some(&commit.message))
+                .await?
+                .iter()
+                .map(|message| append_trailers(message, &trailers))
+                .collect::<Vec<_>>();
+            if let Some(message) = pick_message(&response)? {
+                messages.insert(commit.id.clone(), message);
+            }
+        }
+
+        if messages.is_empty() {
+            println!("No commit has been reworded.");
+            return Ok(());
+        }
+        self.vcs.reword(&range, &messages)?;
+        println!("Reworded {} of {} commits.", messages.len(), commits.len());
+        Ok(())
+    }
+
+    /// Regenerates the messages of all commits in the range, previews them next to the old
+    /// messages and rewrites the commits after a confirmation, unless it's a dry run
+    pub(crate) async fn rewrite(&self, range: &str, dry_run: bool) -> Result<(), Error> {
+        let range = if range.contains("..") {
+            range.to_string()
+        } else {
+            format!("{range}..HEAD")
+        };
+        // The rewritten commits replace the current branch, so they have to be its commits.
+        let tip = match range.rsplit("..").next() {
+            Some("") | None => "HEAD",
+            Some(tip) => tip,
+        };
+        let head = self.vcs.resolve("HEAD")?;
+        if head.is_none() || self.vcs.resolve(tip)? != head {
+            return Err(Error::RangeNotAtHead(range));
+        }
+        let commits = self.vcs.commits(&range)?;
+        let mut messages = HashMap::new();
+
+        for (index, commit) in commits.iter().enumerate() {
+            let diff = self.vcs.diff(&DiffOptions {
+                commit: Some(&commit.id),
+                ..self.diff_options()
+            })?;
+            println!(
+                "\n[{}/{}] {}",
+                index + 1,
+                commits.len(),
+                &commit.id[..commit.id.len().min(10)]
+            );
+            if diff.is_empty() {
+                println!("The commit has no changes, keeping its message.");
+                continue;
+            }
+
+            let response = self.get_response(diff, Some(&commit.message)).await?;
+            let message = append_trailers(
+                response.first().ok_or(Error::EmptySelection)?,
+                &commit_trailers(&commit.message),
+            );
+            let message = message.trim();
+            if message == commit.message {
+                println!("  {}", commit.message.replace('\n', "\n  "));
+                continue;
+            }
+            println!("- {}", commit.message.replace('\n', "\n- "));
+            println!("+ {}", message.replace('\n', "\n+ "));
+            messages.insert(commit.id.clone(), message.to_string());
+        }
+
+        if messages.is_empty() {
+            println!("\nNo message has changed.");
+            return Ok(());
+        }
+        if dry_run {
+            println!("\nDry run, {} commits would be rewritten.", messages.len());
+            return Ok(());
+        }
+        let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Rewrite {} commits?", messages.len()))
+            .default(false)
+            .interact()
+            .unwrap_or(false);
+        if !confirmed {
+            return Ok(());
+        }
+        self.vcs.reword(&range, &messages)?;
+        println!("Rewrote {} of {} commits.", messages.len(), commits.len());
+        Ok(())
+    }
+}
+
+/// Lets the user pick, edit or skip a suggestion. `None` keeps the original message.
+fn pick_message(response: &[String]) -> Result<Option<String>, Error> {
+    let mut items = response
+        .iter()
+        .map(|message| message.lines().next().unwrap_or_default())
+        .collect::<Vec<_>>();
+    items.push("Skip, keep the original message");
+
+    let Ok(index) = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Pick commit message")
+        .default(0)
+        .items(&items)
+        .interact()
+    else {
+        return Ok(None);
+    };
+    let Some(message) = response.get(index) else {
+        return Ok(None);
+    };
+
+    let action = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(message.as_str())
+        .default(0)
+        .items(&["Accept", "Edit", "Skip"])
+        .interact();
+    match action {
+        Ok(0) => Ok(Some(message.clone())),
+        Ok(1) => Ok(Editor::new()
+            .edit(message)?
+            .map(|message| message.trim().to_string())
+            .filter(|message| !message.is_empty())),
+        _ => Ok(None),
+    }
+}
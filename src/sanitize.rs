@@ -0,0 +1,126 @@
+use crate::CommitParts;
+
+/// The labels which models put in front of the message, compared in lowercase
+const LABELS: &[&str] = &["commit message:", "commit:", "message:", "subject:"];
+
+/// The quotes which models wrap the whole message in
+const QUOTES: &[&str] = &["\"\"\"", "'''", "\"", "'", "`"];
+
+/// Whether the line introduces the message instead of being part of it, e.g. `Here's a commit
+/// message:` or `**Commit message:**`
+fn is_preamble(line: &str) -> bool {
+    let line = line.trim().trim_matches('*').trim().to_lowercase();
+    line.ends_with(':')
+        && (line.starts_with("here")
+            || line.starts_with("sure")
+            || line.starts_with("certainly")
+            || line.contains("commit message"))
+}
+
+/// The message without a preamble line and a label in front of its subject
+fn strip_preamble(message: &str) -> &str {
+    let message = message.trim_start();
+    let message = match message.split_once('\n') {
+        Some((first, rest)) if is_preamble(first) => rest.trim_start(),
+        _ => message,
+    };
+    LABELS
+        .iter()
+        .find_map(|label| {
+            let prefix = message.get(..label.len())?;
+            prefix
+                .eq_ignore_ascii_case(label)
+                .then(|| message[label.len()..].trim_start())
+        })
+        .unwrap_or(message)
+}
+
+/// The content of the code block the message is wrapped in, anything after the block, e.g. an
+/// explanation, is dropped
+fn strip_fence(message: &str) -> &str {
+    let Some(fenced) = message.strip_prefix("```") else {
+        return message;
+    };
+    // The fence might name a language, e.g. ```text.
+    let Some((_, content)) = fenced.split_once('\n') else {
+        return message;
+    };
+    match content.find("```") {
+        Some(end) => &content[..end],
+        None => content,
+    }
+}
+
+/// The message without quotes which wrap it as a whole, quotes within it are kept
+fn strip_quotes(message: &str) -> &str {
+    QUOTES
+        .iter()
+        .find_map(|quote| {
+            let inner = message.strip_prefix(quote)?.strip_suffix(quote)?;
+            (quote.len() == 3 || !inner.contains(quote)).then_some(inner)
+        })
+        .unwrap_or(message)
+}
+
+/// Cleans up the response of the model, i.e. strips preambles like `Here's a commit message:`,
+/// labels like `Commit message:`, code fences, surrounding quotes and trailing whitespace
+pub(crate) fn sanitize(response: &str) -> String {
+    let message = strip_preamble(response.trim());
+    let message = strip_fence(message).trim();
+    let message = strip_preamble(message);
+    let message = strip_quotes(message.trim()).trim();
+    message
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The message of the response, rendered if it's a structured response and sanitized otherwise
+pub(crate) fn render_response(response: &str, structured: bool) -> String {
+    match CommitParts::parse(response).filter(|_| structured) {
+        Some(parts) => parts.render(),
+        None => sanitize(response),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_preambles_and_labels() {
+        assert_eq!(
+            sanitize("Here's a commit message:\n\nCommit message: fix: handle x"),
+            "fix: handle x"
+        );
+        assert_eq!(
+            sanitize("**Commit message:**\nfix: handle x"),
+            "fix: handle x"
+        );
+    }
+
+    #[test]
+    fn strips_code_fences_with_the_explanation_after_them() {
+        assert_eq!(
+            sanitize("```text\nfix: handle x\n\nThe body.\n```\nThis message explains the fix."),
+            "fix: handle x\n\nThe body."
+        );
+    }
+
+    #[test]
+    fn strips_surrounding_quotes_only() {
+        assert_eq!(sanitize("\"fix: handle x\""), "fix: handle x");
+        assert_eq!(sanitize("\"fix\": handle \"x\""), "\"fix\": handle \"x\"");
+    }
+
+    #[test]
+    fn keeps_subjects_which_end_with_a_colon() {
+        assert_eq!(sanitize("fix: handle x:"), "fix: handle x:");
+    }
+
+    #[test]
+    fn strips_trailing_whitespace() {
+        assert_eq!(sanitize("fix: x  \n\nbody \n"), "fix: x\n\nbody");
+    }
+}
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use crate::{diff::file_diffs, package_of, Cli, Header, Package};
+
+/// The scope of a changed file, from the mapping of path prefixes to scopes, the package of a
+/// monorepo or the top-level directory, in this order. `None` for files at the root.
+fn file_scope(
+    path: &str,
+    scopes: &HashMap<String, String>,
+    packages: &[Package],
+) -> Option<String> {
+    let mapped = scopes
+        .iter()
+        .map(|(prefix, scope)| (prefix.trim_end_matches('/'), scope))
+        .filter(|(prefix, _)| {
+            path.strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+        .max_by_key(|(prefix, _)| prefix.len());
+    if let Some((_, scope)) = mapped {
+        return Some(scope.clone());
+    }
+    if let Some(package) = package_of(packages, path) {
+        return Some(package.name.clone());
+    }
+    path.split_once('/')
+        .map(|(directory, _)| directory.to_string())
+}
+
+/// The message with the scope as scope of its header, replacing the scope of the model. Other
+/// messages than conventional commits are kept as they are.
+pub(crate) fn force_scope(message: &str, scope: &str) -> String {
+    let (header, body) = match message.split_once('\n') {
+        Some((header, body)) => (header, Some(body)),
+        None => (message, None),
+    };
+    let Some(parsed) = Header::parse(header) else {
+        return message.to_string();
+    };
+    let mut scoped = Header {
+        scope: Some(scope),
+        ..parsed
+    }
+    .to_string();
+    if let Some(body) = body {
+        scoped.push('\n');
+        scoped.push_str(body);
+    }
+    scoped
+}
+
+impl Cli {
+    /// The scope which all changed files share, `None` if scope inference is disabled or the
+    /// files have different or no scopes
+    pub(crate) fn inferred_scope(&self, diff: &str) -> Option<String> {
+        if !self.config.infer_scope {
+            return None;
+        }
+        let packages = if self.config.package_scopes {
+            self.packages()
+        } else {
+            Vec::new()
+        };
+        let mut scopes = file_diffs(diff)
+            .into_iter()
+            .map(|file| file_scope(&file.path, &self.config.scopes, &packages));
+        let scope = scopes.next()??;
+        scopes
+            .all(|other| other.as_ref() == Some(&scope))
+            .then_some(scope)
+    }
+}
@@ -0,0 +1,348 @@
+use std::fs;
+
+use tree_sitter::{Language, Node, Parser};
+
+use crate::{diff::FileDiff, Cli};
+
+/// The definitions of a language which are compared between the old and the new file
+struct Grammar {
+    language: Language,
+    /// The kinds of the nodes which define something and what they define, e.g. a function
+    definitions: &'static [(&'static str, &'static str)],
+    /// The kinds of the nodes which contain further definitions, e.g. classes
+    containers: &'static [&'static str],
+    /// The kinds of the containers whose functions are methods
+    types: &'static [&'static str],
+    /// The separator between the name of a container and its definitions, e.g. `::`
+    separator: &'static str,
+}
+
+const RUST_DEFINITIONS: &[(&str, &str)] = &[
+    ("function_item", "function"),
+    ("function_signature_item", "function"),
+    ("struct_item", "struct"),
+    ("enum_item", "enum"),
+    ("union_item", "union"),
+    ("trait_item", "trait"),
+    ("type_item", "type"),
+    ("const_item", "const"),
+    ("static_item", "static"),
+    ("mod_item", "module"),
+    ("macro_definition", "macro"),
+];
+
+const PYTHON_DEFINITIONS: &[(&str, &str)] = &[
+    ("function_definition", "function"),
+    ("class_definition", "class"),
+];
+
+const JAVASCRIPT_DEFINITIONS: &[(&str, &str)] = &[
+    ("function_declaration", "function"),
+    ("generator_function_declaration", "function"),
+    ("variable_declarator", "function"),
+    ("class_declaration", "class"),
+    ("method_definition", "method"),
+];
+
+const TYPESCRIPT_DEFINITIONS: &[(&str, &str)] = &[
+    ("function_declaration", "function"),
+    ("generator_function_declaration", "function"),
+    ("variable_declarator", "function"),
+    ("class_declaration", "class"),
+    ("abstract_class_declaration", "class"),
+    ("method_definition", "method"),
+    ("interface_declaration", "interface"),
+    ("type_alias_declaration", "type"),
+    ("enum_declaration", "enum"),
+];
+
+const GO_DEFINITIONS: &[(&str, &str)] = &[
+    ("function_declaration", "function"),
+    ("method_declaration", "method"),
+    ("type_spec", "type"),
+];
+
+const CLASSES: &[&str] = &["class_declaration", "abstract_class_declaration"];
+
+/// The grammar of the file by its extension, `None` if the language isn't supported
+fn grammar(path: &str) -> Option<Grammar> {
+    let extension = path.rsplit_once('.')?.1;
+    Some(match extension {
+        "rs" => Grammar {
+            language: tree_sitter_rust::LANGUAGE.into(),
+            definitions: RUST_DEFINITIONS,
+            containers: &["impl_item", "trait_item", "mod_item"],
+            types: &["impl_item", "trait_item"],
+            separator: "::",
+        },
+        "py" | "pyi" => Grammar {
+            language: tree_sitter_python::LANGUAGE.into(),
+            definitions: PYTHON_DEFINITIONS,
+            containers: &["class_definition"],
+            types: &["class_definition"],
+            separator: ".",
+        },
+        "js" | "jsx" | "mjs" | "cjs" => Grammar {
+            language: tree_sitter_javascript::LANGUAGE.into(),
+            definitions: JAVASCRIPT_DEFINITIONS,
+            containers: CLASSES,
+            types: CLASSES,
+            separator: ".",
+        },
+        "ts" | "mts" | "cts" => Grammar {
+            language: tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            definitions: TYPESCRIPT_DEFINITIONS,
+            containers: CLASSES,
+            types: CLASSES,
+            separator: ".",
+        },
+        "tsx" => Grammar {
+            language: tree_sitter_typescript::LANGUAGE_TSX.into(),
+            definitions: TYPESCRIPT_DEFINITIONS,
+            containers: CLASSES,
+            types: CLASSES,
+            separator: ".",
+        },
+        "go" => Grammar {
+            language: tree_sitter_go::LANGUAGE.into(),
+            definitions: GO_DEFINITIONS,
+            containers: &[],
+            types: &[],
+            separator: ".",
+        },
+        _ => return None,
+    })
+}
+
+/// A function, type or method of a file
+struct Definition {
+    /// What is defined, e.g. `function`
+    kind: &'static str,
+    /// The name qualified by its containers, e.g. `Config::load`
+    name: String,
+    /// The code which is compared, without the definitions of a container
+    code: String,
+}
+
+/// The definitions of a file and its code outside of them
+struct Definitions {
+    definitions: Vec<Definition>,
+    /// The code outside of the definitions, e.g. imports
+    rest: String,
+}
+
+/// The text with every run of whitespace replaced by a single space, so reformatting isn't a
+/// change
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The text of the node
+fn text<'a>(node: Node, source: &'a [u8]) -> &'a str {
+    node.utf8_text(source).unwrap_or_default()
+}
+
+impl Grammar {
+    /// Parses the source into its definitions, `None` if it has syntax errors
+    fn definitions(&self, source: &[u8]) -> Option<Definitions> {
+        let mut parser = Parser::new();
+        parser.set_language(&self.language).ok()?;
+        let tree = parser.parse(source, None)?;
+        if tree.root_node().has_error() {
+            return None;
+        }
+        let mut definitions = Vec::new();
+        let mut covered = vec![false; source.len()];
+        self.collect(
+            tree.root_node(),
+            source,
+            &[],
+            &mut definitions,
+            &mut covered,
+        );
+        let rest = source
+            .iter()
+            .zip(&covered)
+            .filter(|(_, covered)| !**covered)
+            .map(|(byte, _)| *byte)
+            .collect::<Vec<_>>();
+        Some(Definitions {
+            definitions,
+            rest: normalize(&String::from_utf8_lossy(&rest)),
+        })
+    }
+
+    /// Collects the definitions below the node, whose containers are named `containers`, and
+    /// marks the code they cover
+    fn collect(
+        &self,
+        node: Node,
+        source: &[u8],
+        containers: &[(&str, String)],
+        definitions: &mut Vec<Definition>,
+        covered: &mut [bool],
+    ) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            let definition = self
+                .definitions
+                .iter()
+                .find(|(kind, _)| *kind == child.kind())
+                .and_then(|(_, kind)| Some((*kind, self.name(child, source)?)));
+            let is_container = self.containers.contains(&child.kind());
+            if definition.is_none() && !is_container {
+                self.collect(child, source, containers, definitions, covered);
+                continue;
+            }
+            covered[child.byte_range()].fill(true);
+
+            let container_name = match &definition {
+                Some((_, name)) => name.clone(),
+                // An `impl` block isn't a definition, but its type names its methods.
+                None => match child.child_by_field_name("type") {
+                    Some(name) => type_name(text(name, source)),
+                    None => continue,
+                },
+            };
+            if let Some((mut kind, name)) = definition {
+                if kind == "function"
+                    && containers
+                        .last()
+                        .is_some_and(|(container, _)| self.types.contains(container))
+                {
+                    kind = "method";
+                }
+                // The definitions of a container are compared on their own.
+                let code = match child.child_by_field_name("body") {
+                    Some(body) if is_container => &source[child.start_byte()..body.start_byte()],
+                    _ => &source[child.byte_range()],
+                };
+                definitions.push(Definition {
+                    kind,
+                    name: self.qualified_name(containers, &name),
+                    code: normalize(&String::from_utf8_lossy(code)),
+                });
+            }
+            if is_container {
+                let mut containers = containers.to_vec();
+                containers.push((child.kind(), container_name));
+                self.collect(child, source, &containers, definitions, covered);
+            }
+        }
+    }
+
+    /// The name of the definition, `None` if the node defines no function, e.g. a variable with
+    /// another value
+    fn name(&self, node: Node, source: &[u8]) -> Option<String> {
+        let name = text(node.child_by_field_name("name")?, source).to_string();
+        match node.kind() {
+            "variable_declarator" => {
+                let value = node.child_by_field_name("value")?;
+                matches!(value.kind(), "arrow_function" | "function_expression").then_some(name)
+            }
+            // A method of Go is named by the type of its receiver, e.g. `(s *Server)`.
+            "method_declaration" => {
+                let receiver = node.child_by_field_name("receiver")?;
+                let receiver = text(receiver, source).trim_matches(['(', ')']);
+                let receiver = receiver.split_whitespace().last().unwrap_or_default();
+                Some(format!(
+                    "{}{}{name}",
+                    type_name(receiver.trim_start_matches('*')),
+                    self.separator
+                ))
+            }
+            _ => Some(name),
+        }
+    }
+
+    /// The name qualified by the names of its containers, e.g. `Config::load`
+    fn qualified_name(&self, containers: &[(&str, String)], name: &str) -> String {
+        containers
+            .iter()
+            .map(|(_, container)| container.as_str())
+            .chain([name])
+            .collect::<Vec<_>>()
+            .join(self.separator)
+    }
+}
+
+/// The name of the type without its generic parameters, e.g. `Config` for `Config<'a>`
+fn type_name(text: &str) -> String {
+    let name = text.split(['<', '[']).next().unwrap_or(text);
+    normalize(name)
+}
+
+/// Lists the definitions as e.g. ``function `parse`, method `Config::load` ``
+fn list(definitions: &[&Definition]) -> String {
+    definitions
+        .iter()
+        .map(|definition| format!("{} `{}`", definition.kind, definition.name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Cli {
+    /// Describes the changes of a modified file by the functions, types and methods which were
+    /// added, removed or modified, if semantic diffs are enabled and the language is supported.
+    /// `None` if the file can't be parsed or no definition changed.
+    pub(crate) fn semantic_summary(&self, file: &FileDiff) -> Option<String> {
+        if !(self.args.semantic || self.config.semantic_diff) || file.is_new() || file.is_deleted()
+        {
+            return None;
+        }
+        let grammar = grammar(&file.path)?;
+        let (old_id, new_id) = file.blob_ids();
+        if old_id.is_empty() || new_id.is_empty() {
+            return None;
+        }
+        let old = self.vcs.blob(old_id).ok().flatten()?;
+        let new = match self.vcs.blob(new_id).ok().flatten() {
+            Some(new) => new,
+            // Unstaged changes aren't stored as blobs yet.
+            None => fs::read(self.vcs.workdir().ok()??.join(&file.path)).ok()?,
+        };
+        let old = grammar.definitions(&old)?;
+        let new = grammar.definitions(&new)?;
+
+        let mut old_definitions = old.definitions.iter().map(Some).collect::<Vec<_>>();
+        let mut added = Vec::new();
+        let mut modified = Vec::new();
+        for definition in &new.definitions {
+            let old_definition = old_definitions
+                .iter_mut()
+                .find(|old_definition| {
+                    old_definition.is_some_and(|old_definition| {
+                        old_definition.kind == definition.kind
+                            && old_definition.name == definition.name
+                    })
+                })
+                .and_then(Option::take);
+            match old_definition {
+                Some(old_definition) if old_definition.code != definition.code => {
+                    modified.push(definition)
+                }
+                Some(_) => {}
+                None => added.push(definition),
+            }
+        }
+        let removed = old_definitions.into_iter().flatten().collect::<Vec<_>>();
+        if added.is_empty() && removed.is_empty() && modified.is_empty() {
+            return None;
+        }
+
+        let mut summary = "(changed definitions, the hunks are omitted)\n".to_string();
+        for (label, definitions) in [
+            ("Added", added),
+            ("Removed", removed),
+            ("Modified", modified),
+        ] {
+            if !definitions.is_empty() {
+                summary.push_str(&format!("{label}: {}\n", list(&definitions)));
+            }
+        }
+        if old.rest != new.rest {
+            summary.push_str("Also changed outside of the definitions, e.g. imports\n");
+        }
+        Some(summary.trim_end().to_string())
+    }
+}
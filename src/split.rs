@@ -0,0 +1,347 @@
+use dialoguer::{theme::ColorfulTheme, Confirm};
+use serde::Deserialize;
+
+use crate::{
+    append_trailers,
+    diff::{file_diffs, split_before, FileDiff},
+    Cli, CompletionRequest, DiffOptions, Error, Privacy,
+};
+
+/// The most lines of a hunk which are sent to the model to group it
+const MAX_HUNK_LINES: usize = 40;
+
+const SPLIT_PROMPT: &str = "You are a helpful assistant which splits staged changes into \
+logically related commits, e.g. a rename, a feature and formatting. Group the numbered hunks and \
+write a commit message for every group. Every hunk belongs to exactly one group. Respond with a \
+JSON object like {\"groups\": [{\"hunks\": [1, 3], \"message\": \"<commit message>\"}]} and \
+nothing else.";
+
+/// A hunk of the staged changes, or a whole file without hunks, e.g. a binary file
+struct Hunk {
+    path: String,
+    /// The header of the file, starting with its `diff --git` line
+    header: String,
+    /// The hunk starting with its `@@` line, empty for files without hunks
+    text: String,
+    /// Whether the file is binary, so it can't be applied as patch
+    binary: bool,
+    /// The note which is shown to the model instead of the changes, e.g. for lockfiles
+    note: Option<&'static str>,
+}
+
+impl Hunk {
+    /// The hunk as it's shown to the model, with long hunks shortened. Only the header of the
+    /// hunk is shown with stats only, and a note for changes which are omitted from the prompt.
+    fn preview(&self, privacy: Privacy, ignore_space: bool) -> String {
+        if let Some(note) = self.note {
+            return format!("({note})");
+        }
+        if privacy == Privacy::Stats {
+            return match self.text.lines().next() {
+                Some(range) => range.to_string(),
+                None => self.header.trim_end().to_string(),
+            };
+        }
+        if ignore_space && self.changes_only_whitespace() {
+            return "(only whitespace changes)".to_string();
+        }
+        if self.text.is_empty() {
+            return self.header.trim_end().to_string();
+        }
+        let lines = self.text.lines().collect::<Vec<_>>();
+        let mut preview = lines[..lines.len().min(MAX_HUNK_LINES)].join("\n");
+        if lines.len() > MAX_HUNK_LINES {
+            preview.push_str(&format!("\n({} more lines)", lines.len() - MAX_HUNK_LINES));
+        }
+        preview
+    }
+
+    /// Whether the hunk only changes whitespace, which `--ignore-space-change` leaves out
+    fn changes_only_whitespace(&self) -> bool {
+        let changed = |marker: char| {
+            self.text
+                .lines()
+                .skip(1)
+                .filter_map(|line| line.strip_prefix(marker))
+                .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+                .collect::<Vec<_>>()
+        };
+        !self.text.is_empty() && changed('-') == changed('+')
+    }
+
+    /// The path with the header of the hunk, e.g. `src/main.rs @@ -1,3 +1,4 @@`
+    fn describe(&self) -> String {
+        match self.text.lines().next() {
+            Some(range) => format!("{} {range}", self.path),
+            None => self.path.clone(),
+        }
+    }
+}
+
+/// The grouping of the hunks the model proposed
+#[derive(Deserialize)]
+struct Split {
+    groups: Vec<Group>,
+}
+
+/// Related hunks which are committed together, by their numbers starting at 1
+#[derive(Deserialize)]
+struct Group {
+    hunks: Vec<usize>,
+    message: String,
+}
+
+/// Splits the diff into its hunks, with the note of their file
+fn hunks(diff: &str, note: impl Fn(&FileDiff) -> Option<&'static str>) -> Vec<Hunk> {
+    let mut hunks = Vec::new();
+    for file in file_diffs(diff) {
+        let binary = file.is_binary();
+        let note = note(&file);
+        let mut parts = split_before(file.text, "@@ ").into_iter();
+        let header = parts.next().unwrap_or_default();
+        let texts = parts.collect::<Vec<_>>();
+        if texts.is_empty() {
+            hunks.push(Hunk {
+                path: file.path.clone(),
+                header,
+                text: String::new(),
+                binary,
+                note,
+            });
+            continue;
+        }
+        for text in texts {
+            hunks.push(Hunk {
+                path: file.path.clone(),
+                header: header.clone(),
+                text,
+                binary,
+                note,
+            });
+        }
+    }
+    hunks
+}
+
+/// The patch of the hunks, with the header of every file once
+fn patch(hunks: &[&Hunk]) -> String {
+    let mut patch = String::new();
+    let mut last_header = None;
+    for hunk in hunks {
+        if last_header != Some(&hunk.header) {
+            patch.push_str(&hunk.header);
+            last_header = Some(&hunk.header);
+        }
+        patch.push_str(&hunk.text);
+    }
+    patch
+}
+
+impl Cli {
+    /// Lets the model group the staged hunks into logically related commits with a message
+    /// each, then commits the groups one after another after a confirmation. Hunks without a
+    /// group stay staged.
+    pub(crate) async fn split(&self) -> Result<(), Error> {
+        // The hunks are applied as patches later, so they have to contain every change. Hunks
+        // which only change whitespace are left out of the prompt instead.
+        let options = self.diff_options();
+        let ignore_space = options.ignore_space;
+        let diff = self.vcs.diff(&DiffOptions {
+            ignore_space: false,
+            ..options
+        })?;
+        if diff.is_empty() {
+            return Err(Error::EmptyDiff);
+        }
+        let ignore_file = self.ignore_file()?;
+        let hunks = hunks(&diff, |file| self.omission_note(file, ignore_file.as_ref()));
+
+        let anonymizer = self.anonymizer(&diff);
+        let privacy = self.privacy();
+        let user = hunks
+            .iter()
+            .enumerate()
+            .map(|(index, hunk)| {
+                format!(
+                    "Hunk {}: {}\n{}",
+                    index + 1,
+                    hunk.path,
+                    hunk.preview(privacy, ignore_space)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let system = match self.language_instruction() {
+            Some(instruction) => format!("{SPLIT_PROMPT} {instruction}"),
+            None => SPLIT_PROMPT.to_string(),
+        };
+        let request = CompletionRequest {
+            suggestions: 1,
+            ..self.completion_request(system, anonymizer.anonymize(&user), false)
+        };
+        let response = self.complete(&request).await?;
+        let response = anonymizer.restore(response.first().ok_or(Error::EmptySelection)?);
+        // The JSON object might be wrapped in a code block.
+        let json = match (response.find('{'), response.rfind('}')) {
+            (Some(start), Some(end)) if start < end => &response[start..=end],
+            _ => response.as_str(),
+        };
+        let split = serde_json::from_str::<Split>(json)?;
+
+        // Every hunk is committed once, with the first group it's assigned to.
+        let mut grouped = vec![false; hunks.len()];
+        let mut groups = Vec::new();
+        for group in split.groups {
+            let members = group
+                .hunks
+                .into_iter()
+                .filter(|&number| {
+                    let index = number.wrapping_sub(1);
+                    index < hunks.len() && !std::mem::replace(&mut grouped[index], true)
+                })
+                .map(|number| &hunks[number - 1])
+                .collect::<Vec<_>>();
+            if !members.is_empty() && !group.message.trim().is_empty() {
+                groups.push((group.message.trim().to_string(), members));
+            }
+        }
+        if groups.is_empty() {
+            return Err(Error::EmptySelection);
+        }
+
+        for (index, (message, members)) in groups.iter().enumerate() {
+            println!(
+                "\n{}. {}",
+                index + 1,
+                message.lines().next().unwrap_or_default()
+            );
+            for hunk in members {
+                println!("   {}", hunk.describe());
+            }
+        }
+        let ungrouped = hunks
+            .iter()
+            .zip(&grouped)
+            .filter(|(_, grouped)| !**grouped)
+            .map(|(hunk, _)| hunk.describe())
+            .collect::<Vec<_>>();
+        if !ungrouped.is_empty() {
+            println!("\nNot grouped, these hunks stay staged:");
+            for hunk in ungrouped {
+                println!("   {hunk}");
+            }
+        }
+        let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Commit {} groups?", groups.len()))
+            .default(true)
+            .interact()
+            .unwrap_or(false);
+        if !confirmed {
+            return Ok(());
+        }
+
+        let tree = self.vcs.write_index_tree()?;
+        let trailers = self.trailers()?;
+        for (message, members) in &groups {
+            // Binary files can't be applied as patch, they're staged as a whole instead.
+            let binary_paths = members
+                .iter()
+                .filter(|hunk| hunk.binary)
+                .map(|hunk| hunk.path.as_str())
+                .collect::<Vec<_>>();
+            let patch = patch(
+                &members
+                    .iter()
+                    .copied()
+                    .filter(|hunk| !hunk.binary)
+                    .collect::<Vec<_>>(),
+            );
+            let committed = self
+                .vcs
+                .stage_only(&tree, &binary_paths)
+                .and_then(|()| {
+                    if patch.is_empty() {
+                        Ok(())
+                    } else {
+                        self.vcs.apply_to_index(&patch)
+                    }
+                })
+                .and_then(|()| self.commit(&append_trailers(message, &trailers)));
+            self.vcs.restore_index(&tree)?;
+            committed?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/a.rs b/a.rs
+--- a/a.rs
++++ b/a.rs
+@@ -1,2 +1,2 @@
+-let a = 1;
++let a = 2;
+ b
+@@ -10,2 +10,2 @@
+-    c
++  c
+ d
+diff --git a/logo.png b/logo.png
+Binary files a/logo.png and b/logo.png differ
+";
+
+    #[test]
+    fn splits_the_diff_into_hunks() {
+        let hunks = hunks(DIFF, |_| None);
+        assert_eq!(
+            hunks.iter().map(Hunk::describe).collect::<Vec<_>>(),
+            ["a.rs @@ -1,2 +1,2 @@", "a.rs @@ -10,2 +10,2 @@", "logo.png"]
+        );
+        assert!(!hunks[0].binary);
+        assert!(hunks[2].binary);
+        assert!(hunks[2].text.is_empty());
+    }
+
+    #[test]
+    fn rebuilds_the_patch_with_every_header_once() {
+        let hunks = hunks(DIFF, |_| None);
+        assert_eq!(patch(&hunks.iter().collect::<Vec<_>>()), DIFF);
+        let second = patch(&[&hunks[1]]);
+        assert!(second.starts_with("diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -10,2"));
+    }
+
+    #[test]
+    fn previews_what_may_be_sent() {
+        let hunks = hunks(DIFF, |file| {
+            (file.path == "logo.png").then_some("binary file updated")
+        });
+        assert_eq!(
+            hunks[0].preview(Privacy::Full, true),
+            "@@ -1,2 +1,2 @@\n-let a = 1;\n+let a = 2;\n b"
+        );
+        assert_eq!(hunks[0].preview(Privacy::Stats, false), "@@ -1,2 +1,2 @@");
+        assert_eq!(
+            hunks[1].preview(Privacy::Full, true),
+            "(only whitespace changes)"
+        );
+        assert_eq!(hunks[1].preview(Privacy::Full, false).lines().count(), 4);
+        assert_eq!(
+            hunks[2].preview(Privacy::Full, false),
+            "(binary file updated)"
+        );
+    }
+
+    #[test]
+    fn shortens_long_hunks() {
+        let lines = (0..MAX_HUNK_LINES + 5)
+            .map(|index| format!("+{index}\n"))
+            .collect::<String>();
+        let diff = format!("diff --git a/x b/x\n@@ -0,0 +1,45 @@\n{lines}");
+        let preview = hunks(&diff, |_| None)[0].preview(Privacy::Full, false);
+        assert_eq!(preview.lines().count(), MAX_HUNK_LINES + 1);
+        assert!(preview.ends_with("(6 more lines)"));
+    }
+}
@@ -0,0 +1,40 @@
+use crate::{is_reasoning, provider::CompletionProvider, Cli, CompletionRequest, Error};
+
+/// The temperatures of the suggestions, evenly spread from the lowest to the highest
+fn spread(range: [f32; 2], suggestions: u8) -> Vec<f32> {
+    let [lowest, highest] = range;
+    let steps = f32::from(suggestions.saturating_sub(1).max(1));
+    (0..suggestions)
+        .map(|index| lowest + (highest - lowest) * f32::from(index) / steps)
+        .collect()
+}
+
+impl Cli {
+    /// The temperature of every suggestion if `temperature_spread` is configured, `None` for a
+    /// single request. Reasoning models ignore the temperature, so they aren't spread.
+    pub(crate) fn temperature_spread(&self, request: &CompletionRequest) -> Option<Vec<f32>> {
+        let range = self.config.temperature_spread?;
+        (request.suggestions > 1 && !is_reasoning(&request.model))
+            .then(|| spread(range, request.suggestions))
+    }
+
+    /// Requests every suggestion on its own with its temperature, so the suggestions frame the
+    /// change differently instead of rephrasing the same sentence
+    pub(crate) async fn complete_spread(
+        &self,
+        provider: &dyn CompletionProvider,
+        request: &CompletionRequest,
+        temperatures: &[f32],
+    ) -> Result<Vec<String>, Error> {
+        let mut choices = Vec::with_capacity(temperatures.len());
+        for &temperature in temperatures {
+            let request = CompletionRequest {
+                suggestions: 1,
+                temperature: Some(temperature),
+                ..request.clone()
+            };
+            choices.extend(provider.complete(&request).await?);
+        }
+        Ok(choices)
+    }
+}
@@ -0,0 +1,326 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use config_reader::{Config, File, FileFormat};
+
+use crate::{diff::file_diffs, Cli};
+
+/// The least share of the changed lines a language needs to be mentioned
+const MIN_LANGUAGE_SHARE: f64 = 0.2;
+
+/// The most languages which are mentioned
+const MAX_LANGUAGES: usize = 3;
+
+/// A language, its extensions and the manifest files of its projects
+struct Language {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    manifests: &'static [&'static str],
+}
+
+const LANGUAGES: &[Language] = &[
+    Language {
+        name: "Rust",
+        extensions: &["rs"],
+        manifests: &["Cargo.toml"],
+    },
+    Language {
+        name: "Python",
+        extensions: &["py", "pyi"],
+        manifests: &["pyproject.toml", "requirements.txt"],
+    },
+    Language {
+        name: "JavaScript",
+        extensions: &["js", "jsx", "mjs", "cjs"],
+        manifests: &["package.json"],
+    },
+    Language {
+        name: "TypeScript",
+        extensions: &["ts", "tsx", "mts", "cts"],
+        manifests: &["package.json"],
+    },
+    Language {
+        name: "Go",
+        extensions: &["go"],
+        manifests: &["go.mod"],
+    },
+    Language {
+        name: "Java",
+        extensions: &["java"],
+        manifests: &[],
+    },
+    Language {
+        name: "Kotlin",
+        extensions: &["kt", "kts"],
+        manifests: &[],
+    },
+    Language {
+        name: "Swift",
+        extensions: &["swift"],
+        manifests: &[],
+    },
+    Language {
+        name: "C",
+        extensions: &["c", "h"],
+        manifests: &[],
+    },
+    Language {
+        name: "C++",
+        extensions: &["cc", "cpp", "cxx", "hpp", "hh"],
+        manifests: &[],
+    },
+    Language {
+        name: "C#",
+        extensions: &["cs"],
+        manifests: &[],
+    },
+    Language {
+        name: "Ruby",
+        extensions: &["rb"],
+        manifests: &[],
+    },
+    Language {
+        name: "PHP",
+        extensions: &["php"],
+        manifests: &[],
+    },
+    Language {
+        name: "Elixir",
+        extensions: &["ex", "exs"],
+        manifests: &[],
+    },
+    Language {
+        name: "Haskell",
+        extensions: &["hs"],
+        manifests: &[],
+    },
+    Language {
+        name: "Zig",
+        extensions: &["zig"],
+        manifests: &[],
+    },
+    Language {
+        name: "Shell",
+        extensions: &["sh", "bash", "zsh"],
+        manifests: &[],
+    },
+];
+
+/// The dependencies which are worth mentioning with their names, e.g. frameworks and runtimes
+const FRAMEWORKS: &[(&str, &str)] = &[
+    ("tokio", "tokio"),
+    ("async-std", "async-std"),
+    ("axum", "axum"),
+    ("actix-web", "actix-web"),
+    ("rocket", "Rocket"),
+    ("warp", "warp"),
+    ("bevy", "Bevy"),
+    ("tauri", "Tauri"),
+    ("leptos", "Leptos"),
+    ("yew", "Yew"),
+    ("diesel", "Diesel"),
+    ("sqlx", "SQLx"),
+    ("embassy-executor", "Embassy"),
+    ("react", "React"),
+    ("vue", "Vue"),
+    ("svelte", "Svelte"),
+    ("@angular/core", "Angular"),
+    ("next", "Next.js"),
+    ("nuxt", "Nuxt"),
+    ("express", "Express"),
+    ("@nestjs/core", "NestJS"),
+    ("electron", "Electron"),
+    ("react-native", "React Native"),
+    ("django", "Django"),
+    ("flask", "Flask"),
+    ("fastapi", "FastAPI"),
+    ("torch", "PyTorch"),
+    ("tensorflow", "TensorFlow"),
+    ("pandas", "pandas"),
+    ("sqlalchemy", "SQLAlchemy"),
+    ("github.com/gin-gonic/gin", "Gin"),
+    ("github.com/labstack/echo/v4", "Echo"),
+    ("github.com/gofiber/fiber/v2", "Fiber"),
+    ("github.com/spf13/cobra", "Cobra"),
+    ("k8s.io/client-go", "client-go of Kubernetes"),
+];
+
+/// The name of a Python requirement like `django>=4.2`, in lowercase
+fn requirement_name(requirement: &str) -> String {
+    requirement
+        .trim()
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// The names of the dependencies in the manifest, empty if it can't be read
+fn dependencies(manifest: &Path) -> Vec<String> {
+    let name = manifest
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let toml = || {
+        Config::builder()
+            .add_source(File::from(manifest).format(FileFormat::Toml))
+            .build()
+            .ok()
+    };
+    let table_keys = |config: &Config, key: &str| {
+        config
+            .get_table(key)
+            .map(|table| table.into_keys().collect::<Vec<_>>())
+            .unwrap_or_default()
+    };
+    match name {
+        "Cargo.toml" => toml()
+            .map(|cargo| {
+                ["dependencies", "workspace.dependencies"]
+                    .iter()
+                    .flat_map(|key| table_keys(&cargo, key))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        "pyproject.toml" => toml()
+            .map(|pyproject| {
+                let mut dependencies = pyproject
+                    .get::<Vec<String>>("project.dependencies")
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|requirement| requirement_name(requirement))
+                    .collect::<Vec<_>>();
+                dependencies.extend(table_keys(&pyproject, "tool.poetry.dependencies"));
+                dependencies
+            })
+            .unwrap_or_default(),
+        "requirements.txt" => fs::read_to_string(manifest)
+            .unwrap_or_default()
+            .lines()
+            .filter(|line| !line.trim_start().starts_with(['#', '-']))
+            .map(requirement_name)
+            .collect(),
+        "package.json" => {
+            let package = fs::read_to_string(manifest)
+                .ok()
+                .and_then(|package| serde_json::from_str::<serde_json::Value>(&package).ok())
+                .unwrap_or_default();
+            ["dependencies", "devDependencies", "peerDependencies"]
+                .iter()
+                .filter_map(|key| package[key].as_object())
+                .flat_map(|dependencies| dependencies.keys().cloned())
+                .collect()
+        }
+        "go.mod" => {
+            let go_mod = fs::read_to_string(manifest).unwrap_or_default();
+            let mut dependencies = Vec::new();
+            let mut in_block = false;
+            for line in go_mod.lines().map(str::trim) {
+                let module = if in_block {
+                    in_block = line != ")";
+                    line
+                } else if line == "require (" {
+                    in_block = true;
+                    continue;
+                } else if let Some(module) = line.strip_prefix("require ") {
+                    module
+                } else {
+                    continue;
+                };
+                if let Some(module) = module.split_whitespace().next() {
+                    dependencies.push(module.to_string());
+                }
+            }
+            dependencies
+        }
+        _ => Vec::new(),
+    }
+}
+
+impl Cli {
+    /// Describes the dominant languages of the changed files and the notable frameworks their
+    /// projects use, so the message uses their terminology. Empty if no known language changed.
+    pub(crate) fn stack_hint(&self, diff: &str) -> String {
+        let files = file_diffs(diff);
+        let mut changed_lines = vec![0; LANGUAGES.len()];
+        for file in &files {
+            let extension = file.path.rsplit_once('.').map(|(_, extension)| extension);
+            if let Some(language) = LANGUAGES
+                .iter()
+                .position(|language| extension.is_some_and(|e| language.extensions.contains(&e)))
+            {
+                changed_lines[language] +=
+                    file.changed_lines('+').len() + file.changed_lines('-').len();
+            }
+        }
+        let total = changed_lines.iter().sum::<usize>();
+        if total == 0 {
+            return String::new();
+        }
+        let mut languages = (0..LANGUAGES.len())
+            .filter(|&language| changed_lines[language] as f64 / total as f64 >= MIN_LANGUAGE_SHARE)
+            .collect::<Vec<_>>();
+        languages.sort_by_key(|&language| std::cmp::Reverse(changed_lines[language]));
+        languages.truncate(MAX_LANGUAGES);
+        let languages = languages
+            .into_iter()
+            .map(|language| &LANGUAGES[language])
+            .collect::<Vec<_>>();
+
+        // The manifests of the dominant languages from the directories of the changed files up to
+        // the root, so every package of a monorepo is covered.
+        let mut frameworks = Vec::new();
+        if let Ok(Some(root)) = self.vcs.workdir() {
+            let mut manifests = HashMap::new();
+            for file in &files {
+                let mut directory = Path::new(&file.path).parent();
+                while let Some(current) = directory {
+                    for language in &languages {
+                        for manifest in language.manifests {
+                            let path = root.join(current).join(manifest);
+                            manifests
+                                .entry(path.clone())
+                                .or_insert_with(|| path.is_file());
+                        }
+                    }
+                    directory = current.parent();
+                }
+            }
+            let mut manifests = manifests
+                .into_iter()
+                .filter_map(|(path, exists)| exists.then_some(path))
+                .collect::<Vec<_>>();
+            manifests.sort();
+            let dependencies = manifests
+                .iter()
+                .flat_map(|manifest| dependencies(manifest))
+                .collect::<Vec<_>>();
+            frameworks = FRAMEWORKS
+                .iter()
+                .filter(|(dependency, _)| dependencies.iter().any(|name| name == dependency))
+                .map(|(_, framework)| *framework)
+                .collect();
+        }
+
+        let names = languages
+            .iter()
+            .map(|language| language.name)
+            .collect::<Vec<_>>();
+        let mut hint = format!("The changes are mostly written in {}.", list(&names));
+        if !frameworks.is_empty() {
+            hint.push_str(&format!(
+                " The project uses {}, use their terminology where it fits.",
+                list(&frameworks)
+            ));
+        }
+        hint
+    }
+}
+
+/// Joins the names like `Rust, Python and Go`
+fn list(names: &[&str]) -> String {
+    match names {
+        [] => String::new(),
+        [name] => name.to_string(),
+        [names @ .., last] => format!("{} and {last}", names.join(", ")),
+    }
+}
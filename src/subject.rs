@@ -0,0 +1,82 @@
+use crate::{render_response, Cli, CompletionRequest, Error, Header};
+
+/// The subject line of the suggestion, rendered or sanitized first
+fn subject(choice: &str, structured: bool) -> String {
+    let message = render_response(choice, structured);
+    message.lines().next().unwrap_or_default().to_string()
+}
+
+/// The message with the given subject line and the body of the model. The subject of the model
+/// is replaced if it repeats or rephrases the given one, otherwise the subject is prepended.
+pub(crate) fn with_subject(message: &str, subject: &str) -> String {
+    let message = message.trim();
+    let (first, rest) = message.split_once("\n\n").unwrap_or((message, ""));
+    let normalize = |line: &str| line.trim().trim_end_matches('.').to_lowercase();
+    let repeats_subject = !first.contains('\n')
+        && (normalize(first) == normalize(subject) || Header::parse(first).is_some());
+    let body = if repeats_subject {
+        rest.trim()
+    } else {
+        message
+    };
+    if body.is_empty() {
+        subject.trim().to_string()
+    } else {
+        format!("{}\n\n{body}", subject.trim())
+    }
+}
+
+impl Cli {
+    /// The most characters of the subject line, `None` if the length isn't limited
+    pub(crate) fn max_subject_length(&self) -> Option<usize> {
+        Some(self.config.max_subject_length).filter(|length| *length > 0)
+    }
+
+    /// Asks the model once more for shorter subject lines if the subject of every suggestion is
+    /// longer than `max_subject_length`, instead of presenting only unusable suggestions
+    pub(crate) async fn shorten_subjects(
+        &self,
+        request: &CompletionRequest,
+        choices: Vec<String>,
+    ) -> Result<Vec<String>, Error> {
+        // A subject of `--subject` is kept as it is.
+        let Some(max_length) = self
+            .max_subject_length()
+            .filter(|_| self.args.subject.is_none())
+        else {
+            return Ok(choices);
+        };
+        let subjects = choices
+            .iter()
+            .map(|choice| subject(choice, request.structured))
+            .collect::<Vec<_>>();
+        if subjects.is_empty()
+            || subjects
+                .iter()
+                .any(|subject| subject.chars().count() <= max_length)
+        {
+            return Ok(choices);
+        }
+        let mut distinct = Vec::new();
+        for subject in subjects {
+            if !distinct.contains(&subject) {
+                distinct.push(subject);
+            }
+        }
+        let mut request = request.clone();
+        request.user.push_str(&format!(
+            r#"
+
+The subject lines of these suggestions are longer than {max_length} characters, write the messages again with shorter subject lines:
+{}
+"#,
+            distinct.join("\n")
+        ));
+        let shortened = self.complete(&request).await?;
+        Ok(if shortened.is_empty() {
+            choices
+        } else {
+            shortened
+        })
+    }
+}
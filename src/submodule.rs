@@ -0,0 +1,117 @@
+use crate::Cli;
+
+/// The most commits which are listed per submodule
+const MAX_COMMITS: usize = 20;
+
+/// A submodule whose commit changed, was added or removed
+struct SubmoduleChange {
+    path: String,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+/// Finds the submodule changes in the diff, which only consist of the old and new commit ID
+fn submodule_changes(diff: &str) -> Vec<SubmoduleChange> {
+    let mut changes = Vec::<SubmoduleChange>::new();
+    let mut path = None;
+    for line in diff.lines() {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            path = paths.split_once(" b/").map(|(_, path)| path.to_string());
+            continue;
+        }
+        let (removed, id) = match (
+            line.strip_prefix("-Subproject commit "),
+            line.strip_prefix("+Subproject commit "),
+        ) {
+            (Some(id), _) => (true, id),
+            (_, Some(id)) => (false, id),
+            _ => continue,
+        };
+        let Some(path) = &path else {
+            continue;
+        };
+        // A modified working tree of the submodule is marked as `-dirty`.
+        let id = id.trim().trim_end_matches("-dirty").to_string();
+        let change = match changes.last_mut() {
+            Some(change) if &change.path == path => change,
+            _ => {
+                changes.push(SubmoduleChange {
+                    path: path.clone(),
+                    from: None,
+                    to: None,
+                });
+                changes.last_mut().expect("expect the pushed change")
+            }
+        };
+        if removed {
+            change.from = Some(id);
+        } else {
+            change.to = Some(id);
+        }
+    }
+    changes
+}
+
+fn short(id: &str) -> &str {
+    &id[..id.len().min(10)]
+}
+
+impl Cli {
+    /// Summarizes the submodule changes of the diff with the commits between the old and new
+    /// commit of every submodule, as the diff itself only contains the commit IDs
+    pub(crate) fn submodule_summary(&self, diff: &str) -> String {
+        let mut summary = String::new();
+        for change in submodule_changes(diff) {
+            let (from, to) = match (&change.from, &change.to) {
+                (Some(from), Some(to)) => (from, to),
+                (None, Some(to)) => {
+                    summary.push_str(&format!(
+                        "Added submodule `{}` at {}\n",
+                        change.path,
+                        short(to)
+                    ));
+                    continue;
+                }
+                (Some(_), None) => {
+                    summary.push_str(&format!("Removed submodule `{}`\n", change.path));
+                    continue;
+                }
+                (None, None) => continue,
+            };
+
+            // The commits might not be fetched, then only the IDs are known.
+            let forward = self
+                .vcs
+                .submodule_commits(&change.path, &format!("{from}..{to}"))
+                .unwrap_or_default();
+            let (action, commits) = if forward.is_empty() {
+                let backward = self
+                    .vcs
+                    .submodule_commits(&change.path, &format!("{to}..{from}"))
+                    .unwrap_or_default();
+                ("Reverted", backward)
+            } else {
+                ("Updated", forward)
+            };
+            summary.push_str(&format!(
+                "{action} submodule `{}` from {} to {}",
+                change.path,
+                short(from),
+                short(to)
+            ));
+            if commits.is_empty() {
+                summary.push('\n');
+                continue;
+            }
+            summary.push_str(&format!(", {} commits:\n", commits.len()));
+            for commit in commits.iter().rev().take(MAX_COMMITS) {
+                let subject = commit.message.lines().next().unwrap_or_default();
+                summary.push_str(&format!("- {subject}\n"));
+            }
+            if commits.len() > MAX_COMMITS {
+                summary.push_str(&format!("- and {} more\n", commits.len() - MAX_COMMITS));
+            }
+        }
+        summary
+    }
+}
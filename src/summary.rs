@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+use crate::{diff::split_before, from_config, resolve_model, Cli, CompletionRequest, Error};
+
+/// Roughly the amount of characters of a token, to estimate the size of a diff without a
+/// tokenizer
+pub(crate) const CHARS_PER_TOKEN: usize = 4;
+
+const SUMMARY_PROMPT: &str = "You are a helpful assistant which summarizes a part of a large git \
+diff. Describe what has been changed in every file in a few short bullet points, without \
+repeating the code. The summaries of all parts are used to write the commit message.";
+
+impl Cli {
+    /// The size of the diff from which it's too large to be sent as a whole, estimated from
+    /// `max_diff_tokens`
+    pub(crate) fn max_diff_chars(&self) -> usize {
+        usize::try_from(self.config.max_diff_tokens)
+            .unwrap_or(usize::MAX)
+            .saturating_mul(CHARS_PER_TOKEN)
+    }
+
+    /// Summarizes the parts of a diff which exceeds `max_diff_tokens`, so the message describes
+    /// every change instead of a truncated diff. `None` if the diff fits as it is.
+    pub(crate) async fn summarize_diff(&self, diff: &str) -> Result<Option<String>, Error> {
+        let max_chars = self.max_diff_chars();
+        if diff.len() <= max_chars {
+            return Ok(None);
+        }
+
+        let chunks = split_diff(diff, max_chars);
+        let provider = from_config(self.provider(), &self.config, &self.args).await?;
+        let model = match &self.config.summary_model {
+            Some(model) => resolve_model(model, &self.config.aliases),
+            None => self.model(),
+        };
+        let progress_bar = ProgressBar::new(chunks.len() as u64)
+            .with_style(
+                ProgressStyle::with_template("{spinner} {msg} {pos}/{len}")
+                    .expect("expect a valid progress bar template"),
+            )
+            .with_message("🤖 Summarizing the parts of the large diff.");
+        let mut summaries = Vec::with_capacity(chunks.len());
+        for (index, chunk) in chunks.iter().enumerate() {
+            let request = CompletionRequest {
+                model: model.clone(),
+                system: SUMMARY_PROMPT.to_string(),
+                user: format!("Diff: ```diff\n{chunk}\n```"),
+                suggestions: 1,
+                max_tokens: self.max_tokens(),
+                temperature: self.temperature(),
+                top_p: self.top_p(),
+                presence_penalty: self.presence_penalty(),
+                frequency_penalty: self.frequency_penalty(),
+                seed: self.seed(),
+                timeout: Duration::from_secs(self.config.timeout),
+                reasoning_effort: None,
+                structured: false,
+            };
+            // The parts share the prompt, confirming the first one sends all of them.
+            if index == 0 {
+                self.preview_prompt(&request)?;
+            }
+            let summary = provider.complete(&request).await?;
+            summaries.push(
+                summary
+                    .first()
+                    .ok_or(Error::EmptySelection)?
+                    .trim()
+                    .to_string(),
+            );
+            progress_bar.inc(1);
+        }
+        progress_bar.finish_and_clear();
+
+        Ok(Some(
+            summaries
+                .iter()
+                .enumerate()
+                .map(|(index, summary)| format!("Part {}:\n{summary}", index + 1))
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        ))
+    }
+}
+
+/// Splits the diff into chunks of at most `max_chars`, at the boundaries of files. Larger files
+/// are split at the boundaries of their hunks, each chunk repeating the header of the file, and
+/// larger hunks at the boundaries of lines.
+fn split_diff(diff: &str, max_chars: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    for file in split_before(diff, "diff --git ") {
+        if file.len() <= max_chars {
+            parts.push(file);
+            continue;
+        }
+        let mut hunks = split_before(&file, "@@ ").into_iter();
+        let header = hunks.next().unwrap_or_default();
+        let max_hunk_chars = max_chars.saturating_sub(header.len()).max(1);
+        for hunk in hunks {
+            for lines in split_lines(&hunk, max_hunk_chars) {
+                parts.push(format!("{header}{lines}"));
+            }
+        }
+    }
+
+    let mut chunks = Vec::<String>::new();
+    for part in parts {
+        match chunks.last_mut() {
+            Some(chunk) if chunk.len() + part.len() <= max_chars => chunk.push_str(&part),
+            _ => chunks.push(part),
+        }
+    }
+    chunks
+}
+
+/// Splits the text into parts of at most `max_chars` at the boundaries of lines, only longer
+/// lines become a part on their own
+fn split_lines(text: &str, max_chars: usize) -> Vec<String> {
+    let mut parts = Vec::<String>::new();
+    for line in text.split_inclusive('\n') {
+        match parts.last_mut() {
+            Some(part) if part.len() + line.len() <= max_chars => part.push_str(line),
+            _ => parts.push(line.to_string()),
+        }
+    }
+    parts
+}
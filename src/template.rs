@@ -0,0 +1,24 @@
+use std::fs;
+
+use crate::{Cli, Error};
+
+impl Cli {
+    /// The content of the commit template configured with `commit.template`, unless the message
+    /// is requested as structured output. Like git, a relative path is relative to the root of
+    /// the working tree.
+    pub(crate) fn commit_template(&self) -> Result<Option<String>, Error> {
+        if self.args.structured || self.config.structured {
+            return Ok(None);
+        }
+        let Some(path) = self.vcs.config_path("commit.template")? else {
+            return Ok(None);
+        };
+        let path = match self.vcs.workdir()? {
+            Some(workdir) if path.is_relative() => workdir.join(path),
+            _ => path,
+        };
+        Ok(fs::read_to_string(path)
+            .ok()
+            .filter(|template| !template.trim().is_empty()))
+    }
+}
@@ -0,0 +1,95 @@
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{Cli, Error};
+
+/// Where the ticket of the branch is added to the messages
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum TicketPlacement {
+    /// As `Refs:` footer
+    #[default]
+    Footer,
+    /// At the end of the subject, e.g. `feat: add the cache (PROJ-123)`
+    Subject,
+    /// Nowhere
+    Off,
+}
+
+/// The ticket in the name of the branch, i.e. the first capture group of the pattern or its
+/// whole match, `None` if the branch doesn't reference a ticket
+fn ticket_of_branch(branch: &str, pattern: &Regex) -> Option<String> {
+    let captures = pattern.captures(branch)?;
+    let ticket = captures.get(1).or_else(|| captures.get(0))?;
+    Some(ticket.as_str().to_string())
+}
+
+/// The message with the ticket at the end of its subject, unless the subject mentions it
+pub(crate) fn add_ticket_to_subject(message: &str, ticket: &str) -> String {
+    let (subject, body) = match message.split_once('\n') {
+        Some((subject, body)) => (subject, Some(body)),
+        None => (message, None),
+    };
+    if subject.contains(ticket) {
+        return message.to_string();
+    }
+    let mut referenced = format!("{} ({ticket})", subject.trim_end());
+    if let Some(body) = body {
+        referenced.push('\n');
+        referenced.push_str(body);
+    }
+    referenced
+}
+
+impl Cli {
+    /// The ticket which the current branch references, e.g. `PROJ-123` of
+    /// `feature/PROJ-123-add-cache`. `None` if the branch references none or tickets are off.
+    pub(crate) fn ticket(&self) -> Result<Option<String>, Error> {
+        if self.config.ticket_placement == TicketPlacement::Off {
+            return Ok(None);
+        }
+        let Some(branch) = self.vcs.branch()? else {
+            return Ok(None);
+        };
+        let pattern = Regex::new(&self.config.ticket_pattern)?;
+        Ok(ticket_of_branch(&branch, &pattern))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_ticket_of_the_branch() {
+        let pattern = Regex::new(r"[A-Z]+-\d+").unwrap();
+        assert_eq!(
+            ticket_of_branch("feature/PROJ-123-cache", &pattern).as_deref(),
+            Some("PROJ-123")
+        );
+        assert_eq!(ticket_of_branch("main", &pattern), None);
+    }
+
+    #[test]
+    fn prefers_the_first_capture_group() {
+        let pattern = Regex::new(r"issue-(\d+)").unwrap();
+        assert_eq!(
+            ticket_of_branch("issue-42-fix", &pattern).as_deref(),
+            Some("42")
+        );
+    }
+
+    #[test]
+    fn adds_the_ticket_to_the_subject() {
+        assert_eq!(
+            add_ticket_to_subject("feat: add the cache \n\nThe body.", "PROJ-123"),
+            "feat: add the cache (PROJ-123)\n\nThe body."
+        );
+    }
+
+    #[test]
+    fn keeps_a_subject_which_mentions_the_ticket() {
+        let message = "feat(PROJ-123): add the cache";
+        assert_eq!(add_ticket_to_subject(message, "PROJ-123"), message);
+    }
+}
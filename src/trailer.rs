@@ -0,0 +1,135 @@
+use std::env;
+
+use crate::{prompt_template::render_with, ticket::TicketPlacement, Cli, Commands, Error};
+
+/// The variables which can be used in the configured trailers, besides `git.<key>` and
+/// `env.<NAME>`
+pub(crate) const TRAILER_VARIABLES: &[&str] = &["ticket", "branch", "author"];
+
+impl Cli {
+    /// The trailers which are appended to every message, the co-authors, the ticket of the
+    /// branch, the configured trailers and the sign-off
+    pub(crate) fn trailers(&self) -> Result<Vec<(String, String)>, Error> {
+        // The commits of others aren't co-authored or signed off by the user who rewords them.
+        let rewording = self.rewords_history();
+        let mut trailers = if rewording {
+            Vec::new()
+        } else {
+            self.co_authors()?
+                .into_iter()
+                .map(|co_author| ("Co-authored-by".to_string(), co_author))
+                .collect::<Vec<_>>()
+        };
+        if self.config.ticket_placement == TicketPlacement::Footer {
+            if let Some(ticket) = self.ticket()? {
+                trailers.push(("Refs".to_string(), ticket));
+            }
+        }
+        for trailer in &self.config.trailers {
+            if let Some(trailer) = self.render_trailer(trailer)? {
+                trailers.push(trailer);
+            }
+        }
+        if !rewording && (self.args.signoff || self.config.signoff) {
+            trailers.push(("Signed-off-by".to_string(), self.identity()?));
+        }
+        Ok(trailers)
+    }
+
+    /// Whether the messages of existing commits are regenerated, which keep their own trailers
+    fn rewords_history(&self) -> bool {
+        matches!(
+            self.args.command,
+            Some(Commands::RewordRange { .. } | Commands::Rewrite { .. })
+        )
+    }
+
+    /// The configured trailer like `Ticket: {{ticket}}` with its variables filled in. `None` if
+    /// a variable has no value, e.g. on a branch without a ticket, or the value is empty.
+    fn render_trailer(&self, trailer: &str) -> Result<Option<(String, String)>, Error> {
+        let (key, template) = trailer
+            .split_once(':')
+            .filter(|(key, _)| is_trailer(&format!("{}: ", key.trim())))
+            .ok_or_else(|| Error::InvalidTrailer(trailer.to_string()))?;
+        let mut missing = false;
+        let value = render_with(template.trim(), |name| {
+            let value = match name {
+                "ticket" => self.ticket()?,
+                "branch" => self.vcs.branch()?,
+                "author" => self.identity().ok(),
+                _ => match (name.strip_prefix("git."), name.strip_prefix("env.")) {
+                    (Some(key), _) => self.vcs.config(key)?,
+                    (_, Some(name)) => env::var(name).ok(),
+                    _ => return Err(Error::UnknownTrailerVariable(name.to_string())),
+                },
+            };
+            missing |= value.as_deref().is_none_or(str::is_empty);
+            Ok(value.unwrap_or_default())
+        })?;
+        Ok((!missing && !value.trim().is_empty())
+            .then(|| (key.trim().to_string(), value.trim().to_string())))
+    }
+
+    /// The identity of the committer as `Name <email>`, like `git commit --signoff` uses it
+    fn identity(&self) -> Result<String, Error> {
+        let name = match env::var("GIT_COMMITTER_NAME") {
+            Ok(name) => Some(name),
+            Err(_) => self.vcs.config("user.name")?,
+        };
+        let email = match env::var("GIT_COMMITTER_EMAIL") {
+            Ok(email) => Some(email),
+            Err(_) => self.vcs.config("user.email")?,
+        };
+        match (name, email) {
+            (Some(name), Some(email)) => Ok(format!("{name} <{email}>")),
+            _ => Err(Error::MissingIdentity),
+        }
+    }
+}
+
+/// Whether the line is a trailer like `Signed-off-by: Jane Doe <jane@example.com>`
+pub(crate) fn is_trailer(line: &str) -> bool {
+    line.split_once(": ").is_some_and(|(key, _)| {
+        !key.is_empty()
+            && key
+                .chars()
+                .all(|char| char.is_ascii_alphanumeric() || char == '-')
+    })
+}
+
+/// The trailers at the end of the message of a commit, e.g. its `Signed-off-by`
+pub(crate) fn commit_trailers(message: &str) -> Vec<(String, String)> {
+    // The subject is never a trailer, even if it looks like one, e.g. `fix: the typo`.
+    let Some((_, paragraph)) = message.trim_end().rsplit_once("\n\n") else {
+        return Vec::new();
+    };
+    if !paragraph.lines().all(is_trailer) {
+        return Vec::new();
+    }
+    paragraph
+        .lines()
+        .filter_map(|line| line.split_once(": "))
+        .map(|(key, value)| (key.to_string(), value.trim().to_string()))
+        .collect()
+}
+
+/// Appends the trailers to the message, e.g. `("Co-authored-by", "Jane <jane@example.com>")`.
+/// An existing block of trailers at the end of the message is extended and trailers which
+/// already exist are skipped.
+pub(crate) fn append_trailers(message: &str, trailers: &[(String, String)]) -> String {
+    let mut message = message.trim_end().to_string();
+    let has_trailers = message
+        .rsplit_once("\n\n")
+        .is_some_and(|(_, paragraph)| paragraph.lines().all(is_trailer));
+    let mut separated = has_trailers;
+    for (key, value) in trailers {
+        let trailer = format!("{key}: {value}");
+        if message.lines().any(|line| line.trim_end() == trailer) {
+            continue;
+        }
+        message.push_str(if separated { "\n" } else { "\n\n" });
+        message.push_str(&trailer);
+        separated = true;
+    }
+    message
+}
@@ -0,0 +1,69 @@
+use std::{
+    fs::{self, File},
+    io::Read,
+};
+
+use crate::{is_ignored, Cli, Error, Privacy};
+
+/// The most untracked files which are listed
+const MAX_FILES: usize = 20;
+
+/// The most bytes of every untracked file which are previewed
+const PREVIEW_BYTES: u64 = 512;
+
+/// The most lines of every untracked file which are previewed
+const PREVIEW_LINES: usize = 10;
+
+impl Cli {
+    /// Lists the untracked files with a short preview of each, so the model knows about new files
+    /// which aren't staged (yet)
+    pub(crate) fn untracked_summary(&self) -> Result<String, Error> {
+        if !(self.args.include_untracked || self.config.include_untracked)
+            || self.args.stdin
+            || self.args.patch_file.is_some()
+            || self.range().is_some()
+        {
+            return Ok(String::new());
+        }
+        let Some(workdir) = self.vcs.workdir()? else {
+            return Ok(String::new());
+        };
+
+        let untracked = self.vcs.untracked()?;
+        let ignore_file = self.ignore_file()?;
+        let mut summary = String::new();
+        for path in untracked.iter().take(MAX_FILES) {
+            summary.push_str(&format!("- {path}\n"));
+            if self.privacy() == Privacy::Stats
+                || ignore_file
+                    .as_ref()
+                    .is_some_and(|ignore_file| is_ignored(ignore_file, path))
+            {
+                continue;
+            }
+            // Only regular files are previewed, reading a FIFO could block forever.
+            let path = workdir.join(path);
+            if !fs::metadata(&path).is_ok_and(|metadata| metadata.is_file()) {
+                continue;
+            }
+            let mut preview = Vec::new();
+            let read = File::open(&path)
+                .and_then(|file| file.take(PREVIEW_BYTES).read_to_end(&mut preview));
+            // Unreadable and binary files aren't previewed.
+            if read.is_err() || preview.is_empty() || preview.contains(&0) {
+                continue;
+            }
+            let preview = String::from_utf8_lossy(&preview);
+            let preview = preview
+                .lines()
+                .take(PREVIEW_LINES)
+                .collect::<Vec<_>>()
+                .join("\n");
+            summary.push_str(&format!("```\n{preview}\n```\n"));
+        }
+        if untracked.len() > MAX_FILES {
+            summary.push_str(&format!("- and {} more\n", untracked.len() - MAX_FILES));
+        }
+        Ok(summary)
+    }
+}
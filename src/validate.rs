@@ -0,0 +1,306 @@
+use serde::Deserialize;
+
+use crate::{
+    commit_type::COMMIT_TYPES, conventions::Convention, is_case, wrap::is_footer, Cli, Error,
+    Header,
+};
+
+/// Common misspellings of conventional commit types and the types they mean
+const TYPE_ALIASES: &[(&str, &str)] = &[
+    ("feature", "feat"),
+    ("features", "feat"),
+    ("bugfix", "fix"),
+    ("fixes", "fix"),
+    ("hotfix", "fix"),
+    ("doc", "docs"),
+    ("documentation", "docs"),
+    ("tests", "test"),
+    ("testing", "test"),
+    ("refactoring", "refactor"),
+    ("performance", "perf"),
+    ("chores", "chore"),
+];
+
+/// What happens with suggestions which don't follow the convention
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Validation {
+    /// Show them as they are
+    #[default]
+    Off,
+    /// Fix what can be fixed, e.g. the case of the type and scope, and drop the others
+    Fix,
+    /// Drop them
+    Drop,
+}
+
+/// The rules a conventional commit message has to follow
+struct Rules {
+    types: Vec<String>,
+    scope_cases: Vec<String>,
+    max_header_length: Option<usize>,
+}
+
+/// The scope in the case, as far as it can be converted, e.g. `Api Client` to `api-client` in
+/// `kebab-case`
+fn convert_case(scope: &str, case: &str) -> Option<String> {
+    let words = scope
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    match case {
+        "lower-case" | "lowercase" => Some(scope.to_lowercase()),
+        "upper-case" | "uppercase" => Some(scope.to_uppercase()),
+        "kebab-case" | "kebabcase" => Some(words.join("-")),
+        "snake-case" | "snakecase" => Some(words.join("_")),
+        _ => None,
+    }
+}
+
+/// The footer with a valid token, e.g. `Breaking change: x` becomes `BREAKING CHANGE: x` and
+/// `Reviewed by: Jane` becomes `Reviewed-by: Jane`. `None` if the line isn't a footer.
+fn fix_footer(line: &str) -> Option<String> {
+    let (token, value) = line.split_once(": ")?;
+    if token.eq_ignore_ascii_case("breaking change")
+        || token.eq_ignore_ascii_case("breaking-change")
+    {
+        return Some(format!("BREAKING CHANGE: {value}"));
+    }
+    let words = token.split(' ').collect::<Vec<_>>();
+    let is_token = words.len() <= 3
+        && words.iter().all(|word| {
+            !word.is_empty() && word.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    is_token.then(|| format!("{}: {value}", words.join("-")))
+}
+
+impl Rules {
+    /// The message with its type, scope and footers fixed, `None` if it can't be fixed, e.g.
+    /// because its header doesn't follow the format or is too long
+    fn fix(&self, message: &str) -> Option<String> {
+        let (header, body) = match message.split_once('\n') {
+            Some((header, body)) => (header, Some(body)),
+            None => (message, None),
+        };
+        let parsed = Header::parse(header)?;
+        let lowercase = parsed.kind.to_lowercase();
+        let alias = TYPE_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == lowercase)
+            .map(|(_, kind)| kind.to_string());
+        let kind = [lowercase]
+            .into_iter()
+            .chain(alias)
+            .find(|kind| self.types.contains(kind))?;
+        let scope = match parsed.scope {
+            Some(scope) if !self.scope_cases.is_empty() => Some(
+                scope
+                    .split(',')
+                    .map(str::trim)
+                    .map(|scope| {
+                        self.scope_cases
+                            .iter()
+                            .find_map(|case| convert_case(scope, case))
+                            .filter(|_| !self.scope_cases.iter().any(|case| is_case(scope, case)))
+                            .unwrap_or_else(|| scope.to_string())
+                    })
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            scope => scope.map(str::to_string),
+        };
+        let mut fixed = Header {
+            kind: &kind,
+            scope: scope.as_deref(),
+            ..parsed
+        }
+        .to_string();
+        if let Some(body) = body {
+            fixed.push('\n');
+            fixed.push_str(&self.fix_footers(body));
+        }
+        self.violations(&fixed).is_empty().then_some(fixed)
+    }
+
+    /// The body with the tokens of the footers in its last paragraph fixed
+    fn fix_footers(&self, body: &str) -> String {
+        let (rest, last) = body.rsplit_once("\n\n").unwrap_or(("", body));
+        let footers = last.lines().map(fix_footer).collect::<Option<Vec<_>>>();
+        match footers {
+            Some(footers) if !rest.is_empty() => format!("{rest}\n\n{}", footers.join("\n")),
+            Some(footers) if body.starts_with('\n') => format!("\n{}", footers.join("\n")),
+            _ => body.to_string(),
+        }
+    }
+
+    /// The rules the message breaks, empty if it follows them
+    fn violations(&self, message: &str) -> Vec<String> {
+        let (header, body) = match message.split_once('\n') {
+            Some((header, body)) => (header, body),
+            None => (message, ""),
+        };
+        let Some(parsed) = Header::parse(header) else {
+            return vec!["the header isn't `type(scope): subject`".to_string()];
+        };
+        let mut violations = Vec::new();
+        if !self.types.iter().any(|kind| kind == parsed.kind) {
+            violations.push(format!("the type `{}` isn't allowed", parsed.kind));
+        }
+        if let Some(scope) = parsed.scope {
+            if !self.scope_cases.is_empty()
+                && !scope
+                    .split(',')
+                    .map(str::trim)
+                    .all(|scope| self.scope_cases.iter().any(|case| is_case(scope, case)))
+            {
+                violations.push(format!(
+                    "the scope isn't in {}",
+                    self.scope_cases.join(" or ")
+                ));
+            }
+        }
+        if let Some(length) = self.max_header_length {
+            if header.chars().count() > length {
+                violations.push(format!("the header is longer than {length} characters"));
+            }
+        }
+        if !body.is_empty() && !body.starts_with('\n') {
+            violations.push("the body isn't separated by a blank line".to_string());
+        }
+        let last = body.rsplit_once("\n\n").map_or(body, |(_, last)| last);
+        let lines = last.lines().filter(|line| !line.starts_with(' '));
+        if last.lines().any(is_footer) && !lines.clone().all(is_footer) {
+            violations.push("a footer isn't `Token: value` or `Token #value`".to_string());
+        }
+        violations
+    }
+}
+
+impl Cli {
+    /// The rules of the configuration, completed by the rules of commitlint
+    fn validation_rules(&self) -> Rules {
+        let commitlint = self.commitlint();
+        let mut types = self.config.types.clone();
+        if types.is_empty() {
+            types = commitlint
+                .as_ref()
+                .map(|commitlint| commitlint.types().to_vec())
+                .unwrap_or_default();
+        }
+        if types.is_empty() {
+            types = COMMIT_TYPES
+                .iter()
+                .map(|(kind, _)| kind.to_string())
+                .collect();
+        }
+        let mut scope_cases = self.config.scope_case.iter().cloned().collect::<Vec<_>>();
+        if scope_cases.is_empty() {
+            scope_cases = commitlint
+                .as_ref()
+                .map(|commitlint| commitlint.scope_cases().to_vec())
+                .unwrap_or_default();
+        }
+        Rules {
+            types,
+            scope_cases,
+            max_header_length: match &commitlint {
+                Some(commitlint) => commitlint.header_max_length(),
+                None => self.max_subject_length(),
+            },
+        }
+    }
+
+    /// The suggestions which follow conventional commits, fixed as far as possible with
+    /// `validation = "fix"`. The others are dropped with a warning. Fails if none is left.
+    pub(crate) fn validate(&self, choices: Vec<String>) -> Result<Vec<String>, Error> {
+        let validation = self.args.validation.unwrap_or(self.config.validation);
+        if validation == Validation::Off || self.convention() != Convention::Conventional {
+            return Ok(choices);
+        }
+        let rules = self.validation_rules();
+        let mut valid = Vec::new();
+        for choice in &choices {
+            let violations = rules.violations(choice);
+            let fixed = match validation {
+                _ if violations.is_empty() => Some(choice.clone()),
+                Validation::Fix => rules.fix(choice),
+                _ => None,
+            };
+            match fixed {
+                Some(fixed) => valid.push(fixed),
+                None => eprintln!(
+                    "Dropping the suggestion \"{}\", {}",
+                    choice.lines().next().unwrap_or_default(),
+                    violations.join(", ")
+                ),
+            }
+        }
+        if valid.is_empty() {
+            return Err(Error::NoValidSuggestion);
+        }
+        Ok(valid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> Rules {
+        Rules {
+            types: ["feat", "fix", "docs"].map(str::to_string).to_vec(),
+            scope_cases: vec!["kebab-case".to_string()],
+            max_header_length: Some(30),
+        }
+    }
+
+    #[test]
+    fn accepts_valid_messages() {
+        assert!(rules()
+            .violations("feat(api-client): add x\n\nThe body.\n\nRefs: #1")
+            .is_empty());
+    }
+
+    #[test]
+    fn reports_violations() {
+        let rules = rules();
+        assert_eq!(rules.violations("add x").len(), 1);
+        assert_eq!(
+            rules.violations("chore(Api Client): add something which is too long\nbody"),
+            [
+                "the type `chore` isn't allowed",
+                "the scope isn't in kebab-case",
+                "the header is longer than 30 characters",
+                "the body isn't separated by a blank line",
+            ]
+        );
+    }
+
+    #[test]
+    fn fixes_the_type_scope_and_footers() {
+        assert_eq!(
+            rules()
+                .fix("Feature(Api Client): add x\n\nThe body.\n\nBreaking change: y\nReviewed by: Jane")
+                .as_deref(),
+            Some("feat(api-client): add x\n\nThe body.\n\nBREAKING CHANGE: y\nReviewed-by: Jane")
+        );
+    }
+
+    #[test]
+    fn leaves_unfixable_messages() {
+        let rules = rules();
+        assert_eq!(rules.fix("add x"), None);
+        assert_eq!(rules.fix("chore: add x"), None);
+        assert_eq!(rules.fix("fix: add something which is too long"), None);
+    }
+
+    #[test]
+    fn converts_the_case_of_scopes() {
+        assert_eq!(
+            convert_case("Api Client", "snake-case").as_deref(),
+            Some("api_client")
+        );
+        assert_eq!(convert_case("api", "pascal-case"), None);
+    }
+}
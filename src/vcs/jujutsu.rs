@@ -0,0 +1,270 @@
+use std::{
+    collections::HashMap,
+    env,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+use super::{subprocess::GitCommand, Commit, CommitOptions, DiffOptions, PreparedMessage, Vcs};
+use crate::Error;
+
+/// Runs the `jj` command in a Jujutsu repository which is colocated with a git repository.
+/// There is no staging area, the changes of the working-copy commit `@` are described instead.
+/// The history is read with `git`, as it's shared by both.
+pub(crate) struct Jujutsu {
+    git: GitCommand,
+}
+
+impl Jujutsu {
+    /// Checks if the current directory is inside a Jujutsu repository, i.e. some ancestor has a
+    /// `.jj` directory, and if `jj` is installed
+    pub(crate) fn open() -> Result<Self, Error> {
+        let current_dir = env::current_dir()?;
+        if !current_dir
+            .ancestors()
+            .any(|directory| directory.join(".jj").is_dir())
+        {
+            return Err(Error::NotARepository);
+        }
+        let root = Command::new("jj").args(["root"]).output()?;
+        if !root.status.success() {
+            return Err(Error::NotARepository);
+        }
+        Ok(Self {
+            git: GitCommand::open()?,
+        })
+    }
+
+    /// Runs `jj` with the arguments and returns its output, or the error if it fails
+    fn output(&self, arguments: &[&str], command: &'static str) -> Result<String, Error> {
+        let output = Command::new("jj")
+            .args(["--color=never", "--no-pager"])
+            .args(arguments)
+            .output()?;
+        if !output.status.success() {
+            return Err(Error::Jujutsu(command));
+        }
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    /// The ID of the git commit of the revision
+    fn commit_id(&self, revision: &str) -> Result<String, Error> {
+        let output = self.output(
+            &["log", "--no-graph", "-r", revision, "-T", "commit_id"],
+            "log",
+        )?;
+        Ok(output.trim().to_string())
+    }
+}
+
+/// The text as string literal of a fileset expression, with `\` and `"` escaped
+fn fileset_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl Vcs for Jujutsu {
+    fn diff(&self, options: &DiffOptions) -> Result<String, Error> {
+        // The revisions are git's, e.g. `main..HEAD`, and the commits are shared.
+        if options.commit.is_some() || options.range.is_some() {
+            return self.git.diff(options);
+        }
+        // The working-copy commit already contains every change, staged or not.
+        let context = options.context_lines.to_string();
+        let mut arguments = vec!["diff", "--git", "--context", &context];
+        if options.ignore_space {
+            arguments.push("--ignore-space-change");
+        }
+        arguments.push("--");
+        // The excluded globs are subtracted from the paths in a single fileset expression.
+        let fileset;
+        if options.excludes.is_empty() {
+            for path in options.paths {
+                arguments.push(path.as_str());
+            }
+        } else {
+            let paths = if options.paths.is_empty() {
+                "all()".to_string()
+            } else {
+                options
+                    .paths
+                    .iter()
+                    .map(|path| fileset_string(path))
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            };
+            let excludes = options
+                .excludes
+                .iter()
+                .map(|exclude| format!("glob:{}", fileset_string(exclude)))
+                .collect::<Vec<_>>()
+                .join(" | ");
+            fileset = format!("({paths}) ~ ({excludes})");
+            arguments.push(&fileset);
+        }
+        self.output(&arguments, "diff")
+    }
+
+    fn commit(&self, message: &str, options: &CommitOptions) -> Result<(), Error> {
+        // The arguments are meant for `git commit`, most of which `jj describe` doesn't know.
+        if !options.arguments.is_empty() {
+            return Err(Error::JujutsuArguments);
+        }
+        let mut command = Command::new("jj");
+        command.args(["describe", "--message", message]);
+        if options.edit {
+            command.arg("--edit");
+        }
+        let status = command.status()?;
+        if !status.success() {
+            return Err(Error::Jujutsu("describe"));
+        }
+        Ok(())
+    }
+
+    /// The description of the working-copy commit, which is the one being described
+    fn last_message(&self) -> Result<String, Error> {
+        let output = self.output(
+            &["log", "--no-graph", "-r", "@", "-T", "description"],
+            "log",
+        )?;
+        Ok(output.trim_end().to_string())
+    }
+
+    fn config(&self, key: &str) -> Result<Option<String>, Error> {
+        self.git.config(key)
+    }
+
+    fn attribute(&self, path: &str, name: &str) -> Result<bool, Error> {
+        self.git.attribute(path, name)
+    }
+
+    fn config_path(&self, key: &str) -> Result<Option<PathBuf>, Error> {
+        self.git.config_path(key)
+    }
+
+    fn workdir(&self) -> Result<Option<PathBuf>, Error> {
+        let root = self.output(&["root"], "root")?;
+        Ok(Some(PathBuf::from(root.trim_end())))
+    }
+
+    /// The closest bookmark of the working-copy commit or its ancestors, as Jujutsu has no
+    /// current branch
+    fn branch(&self) -> Result<Option<String>, Error> {
+        let output = self.output(
+            &[
+                "log",
+                "--no-graph",
+                "-r",
+                "latest(::@ & bookmarks())",
+                "-T",
+                r#"local_bookmarks.map(|bookmark| bookmark.name()).join("\n")"#,
+            ],
+            "log",
+        )?;
+        Ok(output.lines().next().map(str::to_string))
+    }
+
+    /// Jujutsu tracks new files automatically, so they're part of the diff
+    fn untracked(&self) -> Result<Vec<String>, Error> {
+        Ok(Vec::new())
+    }
+
+    fn contributors(&self) -> Result<Vec<String>, Error> {
+        self.git.contributors()
+    }
+
+    fn blob_size(&self, id: &str) -> Result<Option<u64>, Error> {
+        self.git.blob_size(id)
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    fn blob(&self, id: &str) -> Result<Option<Vec<u8>>, Error> {
+        self.git.blob(id)
+    }
+
+    fn recent_commits(&self, count: usize) -> Result<Vec<Commit>, Error> {
+        self.git.recent_commits(count)
+    }
+
+    fn resolve(&self, revision: &str) -> Result<Option<String>, Error> {
+        self.git.resolve(revision)
+    }
+
+    fn commits(&self, range: &str) -> Result<Vec<Commit>, Error> {
+        self.git.commits(range)
+    }
+
+    fn submodule_commits(&self, path: &str, range: &str) -> Result<Vec<Commit>, Error> {
+        self.git.submodule_commits(path, range)
+    }
+
+    fn reword(&self, range: &str, messages: &HashMap<String, String>) -> Result<(), Error> {
+        // Describing a commit rewrites its descendants, so the newest commits are described
+        // first, while the IDs of their ancestors are still valid.
+        for commit in self.git.commits(range)?.iter().rev() {
+            if let Some(message) = messages.get(&commit.id) {
+                self.output(
+                    &["describe", "-r", &commit.id, "--message", message],
+                    "describe",
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Jujutsu has no operations in progress, conflicts are recorded in the commits instead
+    fn prepared_message(&self) -> Result<Option<PreparedMessage>, Error> {
+        Ok(None)
+    }
+
+    /// Creates a bookmark at the working-copy commit, which Jujutsu has instead of branches
+    fn create_branch(&self, name: &str) -> Result<(), Error> {
+        self.output(&["bookmark", "create", name, "-r", "@"], "bookmark")?;
+        Ok(())
+    }
+
+    /// Adds the note to the git commit of the working-copy commit, which was just described
+    fn add_note(&self, notes_ref: &str, note: &str) -> Result<(), Error> {
+        let commit_id = self.commit_id("@")?;
+        let status = Command::new("git")
+            .args(["notes", &format!("--ref={notes_ref}"), "add", "--force"])
+            .args(["--message", note, &commit_id])
+            .status()?;
+        if !status.success() {
+            return Err(Error::GitNotes);
+        }
+        Ok(())
+    }
+
+    /// Applies the patch to the working copy, which Jujutsu snapshots on its next command
+    fn apply(&self, patch: &str) -> Result<(), Error> {
+        let mut child = Command::new("git")
+            .args(["apply", "-"])
+            .stdin(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(patch.as_bytes())?;
+        }
+        if !child.wait()?.success() {
+            return Err(Error::GitApply);
+        }
+        Ok(())
+    }
+
+    fn write_index_tree(&self) -> Result<String, Error> {
+        Err(Error::NoStagingArea)
+    }
+
+    fn stage_only(&self, _tree: &str, _paths: &[&str]) -> Result<(), Error> {
+        Err(Error::NoStagingArea)
+    }
+
+    fn restore_index(&self, _tree: &str) -> Result<(), Error> {
+        Err(Error::NoStagingArea)
+    }
+
+    fn apply_to_index(&self, _patch: &str) -> Result<(), Error> {
+        Err(Error::NoStagingArea)
+    }
+}
@@ -0,0 +1,129 @@
+use std::{cell::OnceCell, collections::HashMap, path::PathBuf};
+
+use super::{open, Commit, CommitOptions, DiffOptions, PreparedMessage, Vcs};
+use crate::Error;
+
+/// Opens the repository on first use, so commands which don't need one, like a diff of stdin,
+/// also work outside of a repository. Outside of a repository, the context like the git config
+/// or the recent commits is empty and everything else fails.
+#[derive(Default)]
+pub(crate) struct Lazy {
+    vcs: OnceCell<Option<Box<dyn Vcs>>>,
+}
+
+impl Lazy {
+    /// The repository of the current directory, `None` outside of a repository
+    fn get(&self) -> Option<&dyn Vcs> {
+        self.vcs.get_or_init(|| open().ok()).as_deref()
+    }
+
+    /// The repository of the current directory, for what fails outside of a repository
+    fn repository(&self) -> Result<&dyn Vcs, Error> {
+        self.get().ok_or(Error::NotARepository)
+    }
+}
+
+impl Vcs for Lazy {
+    fn diff(&self, options: &DiffOptions) -> Result<String, Error> {
+        self.repository()?.diff(options)
+    }
+
+    fn commit(&self, message: &str, options: &CommitOptions) -> Result<(), Error> {
+        self.repository()?.commit(message, options)
+    }
+
+    fn last_message(&self) -> Result<String, Error> {
+        self.repository()?.last_message()
+    }
+
+    fn config(&self, key: &str) -> Result<Option<String>, Error> {
+        self.get().map_or(Ok(None), |vcs| vcs.config(key))
+    }
+
+    fn attribute(&self, path: &str, name: &str) -> Result<bool, Error> {
+        self.get()
+            .map_or(Ok(false), |vcs| vcs.attribute(path, name))
+    }
+
+    fn config_path(&self, key: &str) -> Result<Option<PathBuf>, Error> {
+        self.get().map_or(Ok(None), |vcs| vcs.config_path(key))
+    }
+
+    fn workdir(&self) -> Result<Option<PathBuf>, Error> {
+        self.get().map_or(Ok(None), |vcs| vcs.workdir())
+    }
+
+    fn branch(&self) -> Result<Option<String>, Error> {
+        self.get().map_or(Ok(None), |vcs| vcs.branch())
+    }
+
+    fn untracked(&self) -> Result<Vec<String>, Error> {
+        self.get().map_or(Ok(Vec::new()), |vcs| vcs.untracked())
+    }
+
+    fn contributors(&self) -> Result<Vec<String>, Error> {
+        self.get().map_or(Ok(Vec::new()), |vcs| vcs.contributors())
+    }
+
+    fn blob_size(&self, id: &str) -> Result<Option<u64>, Error> {
+        self.get().map_or(Ok(None), |vcs| vcs.blob_size(id))
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    fn blob(&self, id: &str) -> Result<Option<Vec<u8>>, Error> {
+        self.get().map_or(Ok(None), |vcs| vcs.blob(id))
+    }
+
+    fn recent_commits(&self, count: usize) -> Result<Vec<Commit>, Error> {
+        self.get()
+            .map_or(Ok(Vec::new()), |vcs| vcs.recent_commits(count))
+    }
+
+    fn resolve(&self, revision: &str) -> Result<Option<String>, Error> {
+        self.repository()?.resolve(revision)
+    }
+
+    fn commits(&self, range: &str) -> Result<Vec<Commit>, Error> {
+        self.repository()?.commits(range)
+    }
+
+    fn submodule_commits(&self, path: &str, range: &str) -> Result<Vec<Commit>, Error> {
+        self.repository()?.submodule_commits(path, range)
+    }
+
+    fn reword(&self, range: &str, messages: &HashMap<String, String>) -> Result<(), Error> {
+        self.repository()?.reword(range, messages)
+    }
+
+    fn prepared_message(&self) -> Result<Option<PreparedMessage>, Error> {
+        self.get().map_or(Ok(None), |vcs| vcs.prepared_message())
+    }
+
+    fn create_branch(&self, name: &str) -> Result<(), Error> {
+        self.repository()?.create_branch(name)
+    }
+
+    fn add_note(&self, notes_ref: &str, note: &str) -> Result<(), Error> {
+        self.repository()?.add_note(notes_ref, note)
+    }
+
+    fn apply(&self, patch: &str) -> Result<(), Error> {
+        self.repository()?.apply(patch)
+    }
+
+    fn write_index_tree(&self) -> Result<String, Error> {
+        self.repository()?.write_index_tree()
+    }
+
+    fn stage_only(&self, tree: &str, paths: &[&str]) -> Result<(), Error> {
+        self.repository()?.stage_only(tree, paths)
+    }
+
+    fn restore_index(&self, tree: &str) -> Result<(), Error> {
+        self.repository()?.restore_index(tree)
+    }
+
+    fn apply_to_index(&self, patch: &str) -> Result<(), Error> {
+        self.repository()?.apply_to_index(patch)
+    }
+}
@@ -0,0 +1,575 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use dialoguer::Editor;
+use git2::{DiffFormat, Repository};
+
+use super::{
+    subprocess::GitCommand, Commit, CommitOptions, DiffOptions, Operation, PreparedMessage, Vcs,
+    MAX_CONTRIBUTOR_COMMITS,
+};
+use crate::Error;
+
+/// Uses libgit2, which neither depends on the `git` command nor on its (localized) output
+pub(crate) struct Libgit2 {
+    repository: Repository,
+    /// The current directory relative to the working directory, as pathspecs are relative to it
+    prefix: PathBuf,
+    /// Commits are created by the `git` command if it's available, so hooks, signing and the
+    /// configured editor keep working
+    git: Option<GitCommand>,
+}
+
+impl Libgit2 {
+    pub(crate) fn open() -> Result<Self, Error> {
+        let repository = Repository::open_from_env()?;
+        let prefix = match (repository.workdir(), std::env::current_dir()) {
+            (Some(workdir), Ok(current_dir)) => {
+                match (workdir.canonicalize(), current_dir.canonicalize()) {
+                    (Ok(workdir), Ok(current_dir)) => current_dir
+                        .strip_prefix(workdir)
+                        .map(PathBuf::from)
+                        .unwrap_or_default(),
+                    _ => PathBuf::new(),
+                }
+            }
+            _ => PathBuf::new(),
+        };
+        Ok(Self {
+            repository,
+            prefix,
+            git: GitCommand::open().ok(),
+        })
+    }
+
+    fn head(&self) -> Result<Option<git2::Commit<'_>>, Error> {
+        match self.repository.head() {
+            Ok(head) => Ok(Some(head.peel_to_commit()?)),
+            // There is no commit yet
+            Err(error) if error.code() == git2::ErrorCode::UnbornBranch => Ok(None),
+            Err(error) if error.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// The tree the staged changes are compared to, the parent of the last commit when amending
+    fn base_tree(&self, amend: bool) -> Result<Option<git2::Tree<'_>>, Error> {
+        let Some(head) = self.head()? else {
+            return Ok(None);
+        };
+        if !amend {
+            return Ok(Some(head.tree()?));
+        }
+        match head.parents().next() {
+            Some(parent) => Ok(Some(parent.tree()?)),
+            None => Ok(None),
+        }
+    }
+
+    /// The trees of both ends of the revision range, `A...B` starts at the merge base of both
+    fn range_trees(&self, range: &str) -> Result<(git2::Tree<'_>, git2::Tree<'_>), Error> {
+        let revspec = self.repository.revparse(range)?;
+        let from = revspec.from().ok_or(Error::GitRevParse)?.peel_to_commit()?;
+        let to = match revspec.to() {
+            Some(to) => to.peel_to_commit()?,
+            None => self.head()?.ok_or(Error::GitRevParse)?,
+        };
+        let from = if revspec.mode().contains(git2::RevparseMode::MERGE_BASE) {
+            self.repository
+                .find_commit(self.repository.merge_base(from.id(), to.id())?)?
+        } else {
+            from
+        };
+        Ok((from.tree()?, to.tree()?))
+    }
+
+    /// The commits which are merged into `HEAD`, if a merge is in progress
+    fn merge_heads(&self) -> Result<Vec<git2::Commit<'_>>, Error> {
+        let Ok(merge_heads) = fs::read_to_string(self.repository.path().join("MERGE_HEAD")) else {
+            return Ok(Vec::new());
+        };
+        merge_heads
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                Ok(self
+                    .repository
+                    .find_commit(git2::Oid::from_str(line.trim())?)?)
+            })
+            .collect()
+    }
+}
+
+/// The commits of the repository in the revision range, oldest first
+fn commits(repository: &Repository, range: &str) -> Result<Vec<Commit>, Error> {
+    let mut revwalk = repository.revwalk()?;
+    revwalk.push_range(range)?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+    revwalk
+        .map(|oid| {
+            let commit = repository.find_commit(oid?)?;
+            Ok(Commit {
+                id: commit.id().to_string(),
+                message: String::from_utf8_lossy(commit.message_bytes())
+                    .trim()
+                    .to_string(),
+            })
+        })
+        .collect()
+}
+
+impl Vcs for Libgit2 {
+    fn diff(&self, options: &DiffOptions) -> Result<String, Error> {
+        if self.repository.is_bare() && options.commit.is_none() && options.range.is_none() {
+            return Err(Error::BareRepository);
+        }
+        let mut diff_options = git2::DiffOptions::new();
+        diff_options
+            .context_lines(options.context_lines)
+            .ignore_whitespace_change(options.ignore_space)
+            .ignore_blank_lines(options.ignore_space);
+        // The first matching pathspec wins, so the negative ones come first. Without a positive
+        // pathspec, they wouldn't match anything.
+        for exclude in options.excludes {
+            diff_options.pathspec(format!("!{}", self.prefix.join(exclude).display()));
+        }
+        for path in options.paths {
+            diff_options.pathspec(self.prefix.join(path));
+        }
+        if !options.excludes.is_empty() && options.paths.is_empty() {
+            diff_options.pathspec("*");
+        }
+        let base_tree = self.base_tree(options.amend)?;
+        let mut diff = if let Some(commit) = options.commit {
+            let commit = self.repository.revparse_single(commit)?.peel_to_commit()?;
+            let parent_tree = match commit.parents().next() {
+                Some(parent) => Some(parent.tree()?),
+                None => None,
+            };
+            self.repository.diff_tree_to_tree(
+                parent_tree.as_ref(),
+                Some(&commit.tree()?),
+                Some(&mut diff_options),
+            )?
+        } else if let Some(range) = options.range {
+            let (from, to) = self.range_trees(range)?;
+            self.repository
+                .diff_tree_to_tree(Some(&from), Some(&to), Some(&mut diff_options))?
+        } else if options.all {
+            self.repository
+                .diff_tree_to_workdir_with_index(base_tree.as_ref(), Some(&mut diff_options))?
+        } else {
+            self.repository
+                .diff_tree_to_index(base_tree.as_ref(), None, Some(&mut diff_options))?
+        };
+        let mut find_options = git2::DiffFindOptions::new();
+        find_options
+            .renames(options.rename_threshold > 0)
+            .rename_threshold(options.rename_threshold.into())
+            .copies(options.copy_threshold > 0)
+            .copy_threshold(options.copy_threshold.into());
+        diff.find_similar(Some(&mut find_options))?;
+
+        let mut patch = String::new();
+        diff.print(DiffFormat::Patch, |_, _, line| {
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                patch.push(line.origin());
+            }
+            patch.push_str(&String::from_utf8_lossy(line.content()));
+            true
+        })?;
+        Ok(patch)
+    }
+
+    fn commit(&self, message: &str, options: &CommitOptions) -> Result<(), Error> {
+        if let Some(git) = &self.git {
+            return git.commit(message, options);
+        }
+        if !options.arguments.is_empty() {
+            return Err(Error::GitArguments);
+        }
+
+        // Like `git commit`, comments like the list of conflicts of a merge are stripped from an
+        // edited message, otherwise the message is committed verbatim.
+        let message = if options.edit {
+            let Some(message) = Editor::new().edit(message)? else {
+                return Err(Error::GitCommit);
+            };
+            let comment_string = match self.config("core.commentString")? {
+                Some(comment_string) => Some(comment_string),
+                None => self.config("core.commentChar")?,
+            };
+            // libgit2 only strips comments of a single character, so longer comment strings
+            // are stripped beforehand.
+            match comment_string.as_deref() {
+                Some("auto") => git2::message_prettify(message, None)?,
+                Some(comment_string) if !comment_string.is_empty() => {
+                    let message = message
+                        .lines()
+                        .filter(|line| !line.starts_with(comment_string))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    git2::message_prettify(message, None)?
+                }
+                _ => git2::message_prettify(message, git2::DEFAULT_COMMENT_CHAR)?,
+            }
+        } else {
+            message.to_string()
+        };
+        let signature = self.repository.signature()?;
+        let mut index = self.repository.index()?;
+        if options.all {
+            index.update_all(["*"], None)?;
+            index.write()?;
+        }
+        let tree = self.repository.find_tree(index.write_tree()?)?;
+        let head = self.head()?;
+        if options.amend {
+            let head = head.ok_or(Error::GitCommit)?;
+            head.amend(Some("HEAD"), None, None, None, Some(&message), Some(&tree))?;
+            return Ok(());
+        }
+        let merge_heads = self.merge_heads()?;
+        let parents = head.iter().chain(&merge_heads).collect::<Vec<_>>();
+        self.repository.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            &message,
+            &tree,
+            &parents,
+        )?;
+        // The state of a sequence of cherry-picks or reverts is kept for `--continue`.
+        if !merge_heads.is_empty()
+            || matches!(
+                self.repository.state(),
+                git2::RepositoryState::CherryPick | git2::RepositoryState::Revert
+            )
+        {
+            self.repository.cleanup_state()?;
+        }
+        Ok(())
+    }
+
+    fn last_message(&self) -> Result<String, Error> {
+        let head = self.head()?.ok_or(Error::GitLog)?;
+        Ok(head.message().unwrap_or_default().trim_end().to_string())
+    }
+
+    fn config(&self, key: &str) -> Result<Option<String>, Error> {
+        match self.repository.config()?.get_string(key) {
+            Ok(value) => Ok(Some(value)),
+            Err(error) if error.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn attribute(&self, path: &str, name: &str) -> Result<bool, Error> {
+        let value = self.repository.get_attr(
+            Path::new(path),
+            name,
+            git2::AttrCheckFlags::FILE_THEN_INDEX,
+        )?;
+        Ok(
+            matches!(git2::AttrValue::from_string(value), git2::AttrValue::True)
+                || value == Some("true"),
+        )
+    }
+
+    fn config_path(&self, key: &str) -> Result<Option<PathBuf>, Error> {
+        match self.repository.config()?.get_path(key) {
+            Ok(value) => Ok(Some(value)),
+            Err(error) if error.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn workdir(&self) -> Result<Option<PathBuf>, Error> {
+        Ok(self.repository.workdir().map(PathBuf::from))
+    }
+
+    fn branch(&self) -> Result<Option<String>, Error> {
+        // The symbolic target also names the branch before its first commit.
+        let head = self.repository.find_reference("HEAD")?;
+        Ok(head
+            .symbolic_target()
+            .and_then(|target| target.strip_prefix("refs/heads/"))
+            .map(str::to_string))
+    }
+
+    fn untracked(&self) -> Result<Vec<String>, Error> {
+        if self.repository.is_bare() {
+            return Ok(Vec::new());
+        }
+        let mut options = git2::StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(false);
+        let statuses = self.repository.statuses(Some(&mut options))?;
+        Ok(statuses
+            .iter()
+            .filter(|entry| entry.status().is_wt_new())
+            .filter_map(|entry| entry.path().map(str::to_string))
+            .collect())
+    }
+
+    fn contributors(&self) -> Result<Vec<String>, Error> {
+        if self.head()?.is_none() {
+            return Ok(Vec::new());
+        }
+        let mut revwalk = self.repository.revwalk()?;
+        revwalk.push_head()?;
+        let mut contributors = Vec::<String>::new();
+        for oid in revwalk.take(MAX_CONTRIBUTOR_COMMITS) {
+            let commit = self.repository.find_commit(oid?)?;
+            let author = commit.author();
+            let mut candidates = vec![format!(
+                "{} <{}>",
+                author.name().unwrap_or_default(),
+                author.email().unwrap_or_default()
+            )];
+            if let Ok(trailers) = git2::message_trailers_strs(commit.message().unwrap_or_default())
+            {
+                candidates.extend(
+                    trailers
+                        .iter()
+                        .filter(|(key, _)| key.eq_ignore_ascii_case("Co-authored-by"))
+                        .map(|(_, value)| value.trim().to_string()),
+                );
+            }
+            for candidate in candidates {
+                if !contributors.contains(&candidate) {
+                    contributors.push(candidate);
+                }
+            }
+        }
+        Ok(contributors)
+    }
+
+    fn blob_size(&self, id: &str) -> Result<Option<u64>, Error> {
+        match self.repository.revparse_single(&format!("{id}^{{blob}}")) {
+            Ok(object) => Ok(object
+                .as_blob()
+                .and_then(|blob| u64::try_from(blob.size()).ok())),
+            Err(error) if error.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(error) if error.code() == git2::ErrorCode::Ambiguous => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    fn blob(&self, id: &str) -> Result<Option<Vec<u8>>, Error> {
+        match self.repository.revparse_single(&format!("{id}^{{blob}}")) {
+            Ok(object) => Ok(object.as_blob().map(|blob| blob.content().to_vec())),
+            Err(error) if error.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(error) if error.code() == git2::ErrorCode::Ambiguous => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn recent_commits(&self, count: usize) -> Result<Vec<Commit>, Error> {
+        if self.head()?.is_none() {
+            return Ok(Vec::new());
+        }
+        let mut revwalk = self.repository.revwalk()?;
+        revwalk.push_head()?;
+        let mut commits = Vec::new();
+        for oid in revwalk {
+            if commits.len() >= count {
+                break;
+            }
+            let commit = self.repository.find_commit(oid?)?;
+            if commit.parent_count() > 1 {
+                continue;
+            }
+            commits.push(Commit {
+                id: commit.id().to_string(),
+                message: String::from_utf8_lossy(commit.message_bytes())
+                    .trim()
+                    .to_string(),
+            });
+        }
+        Ok(commits)
+    }
+
+    fn resolve(&self, revision: &str) -> Result<Option<String>, Error> {
+        match self.repository.revparse_single(revision) {
+            Ok(object) => Ok(Some(object.peel_to_commit()?.id().to_string())),
+            Err(error) if error.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn commits(&self, range: &str) -> Result<Vec<Commit>, Error> {
+        commits(&self.repository, range)
+    }
+
+    fn submodule_commits(&self, path: &str, range: &str) -> Result<Vec<Commit>, Error> {
+        let submodule = self.repository.find_submodule(path)?.open()?;
+        commits(&submodule, range)
+    }
+
+    fn reword(&self, range: &str, messages: &HashMap<String, String>) -> Result<(), Error> {
+        let mut revwalk = self.repository.revwalk()?;
+        revwalk.push_range(range)?;
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+        let committer = self.repository.signature()?;
+        let mut rewritten = HashMap::new();
+        let mut head = None;
+        for oid in revwalk {
+            let commit = self.repository.find_commit(oid?)?;
+            let new_parents = commit
+                .parent_ids()
+                .map(|parent| rewritten.get(&parent).copied().unwrap_or(parent))
+                .collect::<Vec<_>>();
+            let message = messages.get(&commit.id().to_string());
+            // Commits before the first reworded commit are kept as they are.
+            let new_id = if message.is_none() && commit.parent_ids().eq(new_parents.iter().copied())
+            {
+                commit.id()
+            } else {
+                let parents = new_parents
+                    .iter()
+                    .map(|parent| self.repository.find_commit(*parent))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.repository.commit(
+                    None,
+                    &commit.author(),
+                    &committer,
+                    &message
+                        .cloned()
+                        .unwrap_or_else(|| String::from_utf8_lossy(commit.message_bytes()).into()),
+                    &commit.tree()?,
+                    &parents.iter().collect::<Vec<_>>(),
+                )?
+            };
+            head = Some((commit.id(), new_id));
+            rewritten.insert(commit.id(), new_id);
+        }
+        if let Some((old_head, new_head)) = head.filter(|(old, new)| old != new) {
+            // Like `git update-ref` with the old value, only `HEAD` itself is replaced.
+            let mut head = self.repository.head()?;
+            if head.target() != Some(old_head) {
+                return Err(Error::GitReword);
+            }
+            head.set_target(new_head, "commitgpt: reword")?;
+        }
+        Ok(())
+    }
+
+    fn prepared_message(&self) -> Result<Option<PreparedMessage>, Error> {
+        let operation = match self.repository.state() {
+            git2::RepositoryState::Merge => Operation::Merge,
+            git2::RepositoryState::CherryPick | git2::RepositoryState::CherryPickSequence => {
+                Operation::CherryPick
+            }
+            git2::RepositoryState::Revert | git2::RepositoryState::RevertSequence => {
+                Operation::Revert
+            }
+            _ => return Ok(None),
+        };
+        match self.repository.message() {
+            Ok(message) => Ok(Some(PreparedMessage { operation, message })),
+            Err(error) if error.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn create_branch(&self, name: &str) -> Result<(), Error> {
+        // Without a commit yet, the branch is created by the first commit.
+        if let Some(head) = self.head()? {
+            self.repository.branch(name, &head, false)?;
+        }
+        self.repository.set_head(&format!("refs/heads/{name}"))?;
+        Ok(())
+    }
+
+    fn add_note(&self, notes_ref: &str, note: &str) -> Result<(), Error> {
+        let head = self.head()?.ok_or(Error::GitNotes)?;
+        let signature = self.repository.signature()?;
+        self.repository.note(
+            &signature,
+            &signature,
+            Some(notes_ref),
+            head.id(),
+            note,
+            true,
+        )?;
+        Ok(())
+    }
+
+    fn apply(&self, patch: &str) -> Result<(), Error> {
+        // `git apply` also understands the mails of `git format-patch`.
+        if let Some(git) = &self.git {
+            return git.apply(patch);
+        }
+        let diff = git2::Diff::from_buffer(patch.as_bytes())?;
+        self.repository
+            .apply(&diff, git2::ApplyLocation::Both, None)?;
+        Ok(())
+    }
+
+    fn write_index_tree(&self) -> Result<String, Error> {
+        Ok(self.repository.index()?.write_tree()?.to_string())
+    }
+
+    fn stage_only(&self, tree: &str, paths: &[&str]) -> Result<(), Error> {
+        let tree = self.repository.find_tree(git2::Oid::from_str(tree)?)?;
+        let mut index = self.repository.index()?;
+        match self.head()? {
+            Some(head) => index.read_tree(&head.tree()?)?,
+            // Without a commit yet, nothing is staged at all.
+            None => index.clear()?,
+        }
+        for path in paths {
+            let path = Path::new(path);
+            match tree.get_path(path) {
+                Ok(entry) => index.add(&git2::IndexEntry {
+                    ctime: git2::IndexTime::new(0, 0),
+                    mtime: git2::IndexTime::new(0, 0),
+                    dev: 0,
+                    ino: 0,
+                    mode: u32::try_from(entry.filemode()).map_err(|_| Error::GitIndex)?,
+                    uid: 0,
+                    gid: 0,
+                    file_size: 0,
+                    id: entry.id(),
+                    flags: 0,
+                    flags_extended: 0,
+                    path: path.to_string_lossy().as_bytes().to_vec(),
+                })?,
+                // The path was deleted or renamed.
+                Err(error) if error.code() == git2::ErrorCode::NotFound => {
+                    match index.remove_path(path) {
+                        Err(error) if error.code() != git2::ErrorCode::NotFound => {
+                            return Err(error.into())
+                        }
+                        _ => {}
+                    }
+                }
+                Err(error) => return Err(error.into()),
+            }
+        }
+        index.write()?;
+        Ok(())
+    }
+
+    fn restore_index(&self, tree: &str) -> Result<(), Error> {
+        let tree = self.repository.find_tree(git2::Oid::from_str(tree)?)?;
+        let mut index = self.repository.index()?;
+        index.read_tree(&tree)?;
+        index.write()?;
+        Ok(())
+    }
+
+    fn apply_to_index(&self, patch: &str) -> Result<(), Error> {
+        let diff = git2::Diff::from_buffer(patch.as_bytes())?;
+        self.repository
+            .apply(&diff, git2::ApplyLocation::Index, None)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use crate::Error;
+
+mod jujutsu;
+mod lazy;
+#[cfg(feature = "libgit2")]
+mod libgit2;
+mod subprocess;
+
+use self::jujutsu::Jujutsu;
+pub(crate) use self::lazy::Lazy;
+#[cfg(feature = "libgit2")]
+use self::libgit2::Libgit2;
+use self::subprocess::GitCommand;
+
+/// Which changes should be diffed and how
+#[derive(Default)]
+pub(crate) struct DiffOptions<'a> {
+    /// Ignore space change and blank lines
+    pub(crate) ignore_space: bool,
+    /// The amount of context lines around every change
+    pub(crate) context_lines: u32,
+    /// Limit the diff to these paths, relative to the current directory
+    pub(crate) paths: &'a [String],
+    /// Leave the paths matching these globs out, relative to the current directory
+    pub(crate) excludes: &'a [String],
+    /// The similarity in percent from which a deleted and an added file are a rename, 0
+    /// disables the detection
+    pub(crate) rename_threshold: u8,
+    /// The similarity in percent from which an added file is a copy of a modified file, 0
+    /// disables the detection
+    pub(crate) copy_threshold: u8,
+    /// Include the changes of the last commit, as it's going to be amended
+    pub(crate) amend: bool,
+    /// Include the unstaged changes of tracked files, as they're going to be staged
+    pub(crate) all: bool,
+    /// Diff the given revision range instead of the staged changes, e.g. `main..feature`. A
+    /// single revision is compared to `HEAD`.
+    pub(crate) range: Option<&'a str>,
+    /// Diff the changes of the given commit instead of the staged changes
+    pub(crate) commit: Option<&'a str>,
+}
+
+/// How the commit should be created
+pub(crate) struct CommitOptions<'a> {
+    /// Replace the last commit instead of creating a new one
+    pub(crate) amend: bool,
+    /// Stage the changes of all tracked files before committing
+    pub(crate) all: bool,
+    /// Open the editor to review the message before committing
+    pub(crate) edit: bool,
+    /// Further arguments which are passed as they are to `git commit`, e.g. `--no-verify`
+    pub(crate) arguments: &'a [String],
+}
+
+/// An operation in progress whose message git has prepared
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Operation {
+    Merge,
+    CherryPick,
+    Revert,
+}
+
+/// The message git has prepared for an operation in progress, e.g. `Merge branch 'feature'`
+/// followed by the list of conflicts
+pub(crate) struct PreparedMessage {
+    pub(crate) operation: Operation,
+    pub(crate) message: String,
+}
+
+/// A commit of the history
+pub(crate) struct Commit {
+    /// The full ID of the commit
+    pub(crate) id: String,
+    pub(crate) message: String,
+}
+
+/// The version control system which provides the changes and creates the commits
+pub(crate) trait Vcs {
+    /// The staged changes as unified diff, see `DiffOptions` for the other changes
+    fn diff(&self, options: &DiffOptions) -> Result<String, Error>;
+
+    /// Commits the staged changes with the given message, after the user had the chance to edit
+    /// it
+    fn commit(&self, message: &str, options: &CommitOptions) -> Result<(), Error>;
+
+    /// The message of the last commit
+    fn last_message(&self) -> Result<String, Error>;
+
+    /// The value of the git config, e.g. `core.commentChar`
+    fn config(&self, key: &str) -> Result<Option<String>, Error>;
+
+    /// Whether the git attribute is set for the path, relative to the root of the working tree,
+    /// e.g. `linguist-vendored`
+    fn attribute(&self, path: &str, name: &str) -> Result<bool, Error>;
+
+    /// The value of the git config as path with `~` expanded, e.g. `commit.template`
+    fn config_path(&self, key: &str) -> Result<Option<PathBuf>, Error>;
+
+    /// The root of the working tree, `None` for bare repositories
+    fn workdir(&self) -> Result<Option<PathBuf>, Error>;
+
+    /// The name of the current branch, `None` if `HEAD` is detached
+    fn branch(&self) -> Result<Option<String>, Error>;
+
+    /// The files which are neither tracked nor ignored, relative to the root of the working tree
+    fn untracked(&self) -> Result<Vec<String>, Error>;
+
+    /// The authors and co-authors of the recent commits as `Name <email>`, the most recent
+    /// first and without duplicates
+    fn contributors(&self) -> Result<Vec<String>, Error>;
+
+    /// The size of the blob with the (abbreviated) ID in bytes, `None` if there is no such blob
+    fn blob_size(&self, id: &str) -> Result<Option<u64>, Error>;
+
+    /// The content of the blob with the (abbreviated) ID, `None` if there is no such blob
+    #[cfg(feature = "tree-sitter")]
+    fn blob(&self, id: &str) -> Result<Option<Vec<u8>>, Error>;
+
+    /// The last commits of `HEAD` without merges, the most recent first. Empty without a commit.
+    fn recent_commits(&self, count: usize) -> Result<Vec<Commit>, Error>;
+
+    /// The full ID of the commit the revision points to, e.g. of `HEAD` or `main`. `None` if
+    /// there is no such commit.
+    fn resolve(&self, revision: &str) -> Result<Option<String>, Error>;
+
+    /// The commits in the revision range like `main..HEAD`, oldest first
+    fn commits(&self, range: &str) -> Result<Vec<Commit>, Error>;
+
+    /// The commits in the revision range of the submodule at the path, oldest first
+    fn submodule_commits(&self, path: &str, range: &str) -> Result<Vec<Commit>, Error>;
+
+    /// Rewrites the commits of the revision range, which has to end at `HEAD`, with the new
+    /// messages by their ID. The changes and authors of the commits are kept.
+    fn reword(&self, range: &str, messages: &HashMap<String, String>) -> Result<(), Error>;
+
+    /// The prepared message of a merge, cherry-pick or revert in progress
+    fn prepared_message(&self) -> Result<Option<PreparedMessage>, Error>;
+
+    /// Creates a branch at `HEAD` and switches to it, keeping the staged and unstaged changes
+    fn create_branch(&self, name: &str) -> Result<(), Error>;
+
+    /// Adds the note to the commit which was just created in the notes ref, replacing an
+    /// existing note
+    fn add_note(&self, notes_ref: &str, note: &str) -> Result<(), Error>;
+
+    /// Applies the patch to the working tree and stages it
+    fn apply(&self, patch: &str) -> Result<(), Error>;
+
+    /// Writes the staged changes as tree and returns its ID, so they can be staged again later
+    fn write_index_tree(&self) -> Result<String, Error>;
+
+    /// Stages only the changes of the paths, relative to the root of the working tree, as they
+    /// are in the tree. The other changes are unstaged, but kept in the working tree.
+    fn stage_only(&self, tree: &str, paths: &[&str]) -> Result<(), Error>;
+
+    /// Stages the changes of the tree again, e.g. those which weren't committed
+    fn restore_index(&self, tree: &str) -> Result<(), Error>;
+
+    /// Applies the patch to the index only, leaving the working tree as it is
+    fn apply_to_index(&self, patch: &str) -> Result<(), Error>;
+}
+
+/// The most recent commits whose authors are contributors
+pub(crate) const MAX_CONTRIBUTOR_COMMITS: usize = 1000;
+
+/// Opens the repository of the current directory, using `jj` in Jujutsu repositories, libgit2
+/// if possible and the `git` command otherwise
+pub(crate) fn open() -> Result<Box<dyn Vcs>, Error> {
+    if let Ok(jujutsu) = Jujutsu::open() {
+        return Ok(Box::new(jujutsu));
+    }
+    #[cfg(feature = "libgit2")]
+    if let Ok(libgit2) = Libgit2::open() {
+        return Ok(Box::new(libgit2));
+    }
+    Ok(Box::new(GitCommand::open()?))
+}
@@ -0,0 +1,526 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+use super::{
+    Commit, CommitOptions, DiffOptions, Operation, PreparedMessage, Vcs, MAX_CONTRIBUTOR_COMMITS,
+};
+use crate::{git::git_path, Error};
+
+/// Runs the `git` command
+pub(crate) struct GitCommand {
+    /// The repository has no working tree, so there are no staged changes
+    bare: bool,
+}
+
+impl GitCommand {
+    /// Checks if there is a repository. Like every other command, this honors `GIT_DIR`,
+    /// `GIT_WORK_TREE` and linked worktrees.
+    pub(crate) fn open() -> Result<Self, Error> {
+        let output = Command::new("git")
+            .args(["rev-parse", "--is-bare-repository"])
+            .output()?;
+        if !output.status.success() {
+            return Err(Error::NotARepository);
+        }
+        Ok(Self {
+            bare: String::from_utf8(output.stdout)?.trim() == "true",
+        })
+    }
+
+    /// The commit the changes are compared to, or the empty tree if there is no such commit
+    fn base(&self, revision: &str) -> Result<String, Error> {
+        let commit = Command::new("git")
+            .args(["rev-parse", "--verify", "--quiet", revision])
+            .output()?;
+        if commit.status.success() {
+            return Ok(String::from_utf8(commit.stdout)?.trim().to_string());
+        }
+        let empty_tree = Command::new("git")
+            .args(["hash-object", "-t", "tree", "--stdin"])
+            .stdin(Stdio::null())
+            .output()?;
+        if !empty_tree.status.success() {
+            return Err(Error::GitDiff);
+        }
+        Ok(String::from_utf8(empty_tree.stdout)?.trim().to_string())
+    }
+
+    /// The value of `git config --get`, which fails if the key isn't set
+    fn config_value(&self, arguments: &[&str]) -> Result<Option<String>, Error> {
+        let output = Command::new("git")
+            .args(["config", "--get"])
+            .args(arguments)
+            .output()?;
+        match output.status.code() {
+            Some(0) => Ok(Some(
+                String::from_utf8(output.stdout)?.trim_end().to_string(),
+            )),
+            Some(1) => Ok(None),
+            _ => Err(Error::GitConfig),
+        }
+    }
+
+    /// Runs `git` with the arguments and returns its output, or the error if it fails
+    fn output(&self, arguments: &[&str], error: Error) -> Result<String, Error> {
+        let output = Command::new("git").args(arguments).output()?;
+        if !output.status.success() {
+            return Err(error);
+        }
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    /// The commits in the revision range, oldest first. The options are passed to `git` itself.
+    fn log(&self, options: &[&str], range: &str) -> Result<Vec<Commit>, Error> {
+        let mut arguments = options.to_vec();
+        arguments.extend([
+            "log",
+            "--reverse",
+            "--topo-order",
+            "--format=%H%n%B%x00",
+            range,
+        ]);
+        let output = self.output(&arguments, Error::GitLog)?;
+        Ok(parse_log(&output))
+    }
+
+    /// Creates a copy of the commit with other parents and optionally another message, keeping
+    /// its tree and author
+    fn copy_commit(
+        &self,
+        id: &str,
+        parents: &[String],
+        message: Option<&str>,
+    ) -> Result<String, Error> {
+        let message = match message {
+            Some(message) => message.to_string(),
+            None => self.output(&["log", "-1", "--format=%B", id], Error::GitLog)?,
+        };
+        let author = self.output(
+            &["log", "-1", "--format=%an%n%ae%n%ad", "--date=raw", id],
+            Error::GitLog,
+        )?;
+        let mut author = author.lines();
+        let tree = format!("{id}^{{tree}}");
+        let mut arguments = vec!["commit-tree", tree.as_str(), "-F", "-"];
+        for parent in parents {
+            arguments.push("-p");
+            arguments.push(parent.as_str());
+        }
+        let mut child = Command::new("git")
+            .args(&arguments)
+            .env("GIT_AUTHOR_NAME", author.next().unwrap_or_default())
+            .env("GIT_AUTHOR_EMAIL", author.next().unwrap_or_default())
+            .env("GIT_AUTHOR_DATE", author.next().unwrap_or_default())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(message.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(Error::GitReword);
+        }
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    }
+}
+
+/// Parses the output of `git log` with the format `%H%n%B%x00` into its commits
+fn parse_log(output: &str) -> Vec<Commit> {
+    output
+        .split('\0')
+        .filter_map(|commit| commit.trim_start().split_once('\n'))
+        .map(|(id, message)| Commit {
+            id: id.to_string(),
+            message: message.trim().to_string(),
+        })
+        .collect()
+}
+
+impl Vcs for GitCommand {
+    fn diff(&self, options: &DiffOptions) -> Result<String, Error> {
+        if self.bare && options.commit.is_none() && options.range.is_none() {
+            return Err(Error::BareRepository);
+        }
+        // Without `--staged` the working tree is compared, which includes the staged changes.
+        // An amended commit only gets the staged changes, unless every change is committed.
+        let staged = options.commit.is_none() && options.range.is_none() && !options.all;
+        let base = match (options.commit, options.range) {
+            (Some(commit), _) => Some(format!("{}..{commit}", self.base(&format!("{commit}^"))?)),
+            (None, Some(range)) if range.contains("..") => Some(range.to_string()),
+            (None, Some(revision)) => Some(format!("{revision}..HEAD")),
+            (None, None) if options.amend => Some(self.base("HEAD^")?),
+            (None, None) if options.all => Some(self.base("HEAD")?),
+            (None, None) => None,
+        };
+        // The submodules are summarized from the changed commit IDs, regardless of `diff.submodule`.
+        let mut arguments = vec!["--no-pager", "diff", "--submodule=short"];
+        if staged {
+            arguments.push("--staged");
+        }
+        if let Some(base) = &base {
+            arguments.push(base.as_str());
+        }
+        let unified = format!("--unified={}", options.context_lines);
+        arguments.push(&unified);
+        if options.ignore_space {
+            arguments.push("--ignore-space-change");
+            arguments.push("--ignore-blank-lines");
+        }
+        let find_renames = format!("--find-renames={}%", options.rename_threshold);
+        let find_copies = format!("--find-copies={}%", options.copy_threshold);
+        if options.rename_threshold > 0 {
+            arguments.push(&find_renames);
+        } else {
+            arguments.push("--no-renames");
+        }
+        if options.copy_threshold > 0 {
+            arguments.push(&find_copies);
+        }
+        let excludes = options
+            .excludes
+            .iter()
+            .map(|exclude| format!(":(exclude){exclude}"))
+            .collect::<Vec<_>>();
+        arguments.push("--");
+        for path in options.paths {
+            arguments.push(path.as_str());
+        }
+        for exclude in &excludes {
+            arguments.push(exclude.as_str());
+        }
+        let output = Command::new("git").args(&arguments).output()?;
+        if !output.status.success() {
+            return Err(Error::GitDiff);
+        }
+        let respone = String::from_utf8(output.stdout)?;
+        Ok(respone)
+    }
+
+    fn commit(&self, message: &str, options: &CommitOptions) -> Result<(), Error> {
+        let mut command = Command::new("git");
+        command.args(["commit", "--message", message]);
+        // Without the editor the message is committed as it is, including lines which start
+        // with the comment string.
+        if options.edit {
+            command.arg("--edit");
+        } else {
+            command.arg("--cleanup=verbatim");
+        }
+        if options.amend {
+            command.arg("--amend");
+        }
+        if options.all {
+            command.arg("--all");
+        }
+        command.args(options.arguments);
+        if !command.status()?.success() {
+            return Err(Error::GitCommit);
+        }
+        Ok(())
+    }
+
+    fn last_message(&self) -> Result<String, Error> {
+        let output = Command::new("git")
+            .args(["log", "-1", "--format=%B"])
+            .output()?;
+        if !output.status.success() {
+            return Err(Error::GitLog);
+        }
+        Ok(String::from_utf8(output.stdout)?.trim_end().to_string())
+    }
+
+    fn config(&self, key: &str) -> Result<Option<String>, Error> {
+        self.config_value(&[key])
+    }
+
+    fn attribute(&self, path: &str, name: &str) -> Result<bool, Error> {
+        // The paths of `git check-attr` are relative to the current directory.
+        let Some(workdir) = self.workdir()? else {
+            return Ok(false);
+        };
+        let output = self.output(
+            &[
+                "check-attr",
+                name,
+                "--",
+                &workdir.join(path).to_string_lossy(),
+            ],
+            Error::GitCheckAttr,
+        )?;
+        let value = output.trim_end().rsplit(": ").next().unwrap_or_default();
+        Ok(matches!(value, "set" | "true"))
+    }
+
+    fn config_path(&self, key: &str) -> Result<Option<PathBuf>, Error> {
+        Ok(self.config_value(&["--type=path", key])?.map(PathBuf::from))
+    }
+
+    fn workdir(&self) -> Result<Option<PathBuf>, Error> {
+        if self.bare {
+            return Ok(None);
+        }
+        let toplevel = self.output(&["rev-parse", "--show-toplevel"], Error::GitRevParse)?;
+        Ok(Some(PathBuf::from(toplevel.trim_end())))
+    }
+
+    fn branch(&self) -> Result<Option<String>, Error> {
+        let output = Command::new("git")
+            .args(["symbolic-ref", "--quiet", "--short", "HEAD"])
+            .output()?;
+        match output.status.code() {
+            Some(0) => Ok(Some(
+                String::from_utf8(output.stdout)?.trim_end().to_string(),
+            )),
+            // `HEAD` is detached
+            Some(1) => Ok(None),
+            _ => Err(Error::GitRevParse),
+        }
+    }
+
+    fn untracked(&self) -> Result<Vec<String>, Error> {
+        if self.bare {
+            return Ok(Vec::new());
+        }
+        let output = self.output(
+            &[
+                "ls-files",
+                "--others",
+                "--exclude-standard",
+                "--full-name",
+                "-z",
+                ":/",
+            ],
+            Error::GitStatus,
+        )?;
+        Ok(output
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    fn contributors(&self) -> Result<Vec<String>, Error> {
+        let max_count = format!("--max-count={MAX_CONTRIBUTOR_COMMITS}");
+        let output = self.output(
+            &[
+                "log",
+                &max_count,
+                "--format=%an <%ae>%n%(trailers:key=Co-authored-by,valueonly,unfold)",
+            ],
+            Error::GitLog,
+        )?;
+        let mut contributors = Vec::<String>::new();
+        for contributor in output.lines().map(str::trim) {
+            if !contributor.is_empty() && !contributors.iter().any(|known| known == contributor) {
+                contributors.push(contributor.to_string());
+            }
+        }
+        Ok(contributors)
+    }
+
+    fn blob_size(&self, id: &str) -> Result<Option<u64>, Error> {
+        let output = Command::new("git")
+            .args(["cat-file", "-s", &format!("{id}^{{blob}}")])
+            .output()?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        Ok(String::from_utf8(output.stdout)?.trim().parse().ok())
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    fn blob(&self, id: &str) -> Result<Option<Vec<u8>>, Error> {
+        let output = Command::new("git")
+            .args(["cat-file", "blob", &format!("{id}^{{blob}}")])
+            .output()?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        Ok(Some(output.stdout))
+    }
+
+    fn recent_commits(&self, count: usize) -> Result<Vec<Commit>, Error> {
+        let has_commit = Command::new("git")
+            .args(["rev-parse", "--verify", "--quiet", "HEAD"])
+            .output()?
+            .status
+            .success();
+        if !has_commit || count == 0 {
+            return Ok(Vec::new());
+        }
+        let max_count = format!("--max-count={count}");
+        let output = self.output(
+            &["log", "--no-merges", &max_count, "--format=%H%n%B%x00"],
+            Error::GitLog,
+        )?;
+        Ok(parse_log(&output))
+    }
+
+    fn resolve(&self, revision: &str) -> Result<Option<String>, Error> {
+        let output = Command::new("git")
+            .args(["rev-parse", "--verify", "--quiet"])
+            .arg(format!("{revision}^{{commit}}"))
+            .output()?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        Ok(Some(String::from_utf8(output.stdout)?.trim().to_string()))
+    }
+
+    fn commits(&self, range: &str) -> Result<Vec<Commit>, Error> {
+        self.log(&[], range)
+    }
+
+    fn submodule_commits(&self, path: &str, range: &str) -> Result<Vec<Commit>, Error> {
+        let submodule = self.workdir()?.ok_or(Error::BareRepository)?.join(path);
+        self.log(&["-C", &submodule.to_string_lossy()], range)
+    }
+
+    fn reword(&self, range: &str, messages: &HashMap<String, String>) -> Result<(), Error> {
+        let commits = self.output(
+            &["rev-list", "--reverse", "--topo-order", "--parents", range],
+            Error::GitLog,
+        )?;
+        let mut rewritten = HashMap::<String, String>::new();
+        let mut head = None;
+        for line in commits.lines() {
+            let mut ids = line.split_whitespace().map(str::to_string);
+            let Some(id) = ids.next() else {
+                continue;
+            };
+            let parents = ids.collect::<Vec<_>>();
+            let new_parents = parents
+                .iter()
+                .map(|parent| rewritten.get(parent).unwrap_or(parent).clone())
+                .collect::<Vec<_>>();
+            let message = messages.get(&id).map(String::as_str);
+            // Commits before the first reworded commit are kept as they are.
+            let new_id = if message.is_none() && new_parents == parents {
+                id.clone()
+            } else {
+                self.copy_commit(&id, &new_parents, message)?
+            };
+            head = Some((id.clone(), new_id.clone()));
+            rewritten.insert(id, new_id);
+        }
+        if let Some((old_head, new_head)) = head.filter(|(old, new)| old != new) {
+            self.output(
+                &[
+                    "update-ref",
+                    "-m",
+                    "commitgpt: reword",
+                    "HEAD",
+                    &new_head,
+                    &old_head,
+                ],
+                Error::GitReword,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn prepared_message(&self) -> Result<Option<PreparedMessage>, Error> {
+        let operation = if git_path("MERGE_HEAD")?.exists() {
+            Operation::Merge
+        } else if git_path("CHERRY_PICK_HEAD")?.exists() {
+            Operation::CherryPick
+        } else if git_path("REVERT_HEAD")?.exists() {
+            Operation::Revert
+        } else {
+            return Ok(None);
+        };
+        Ok(fs::read_to_string(git_path("MERGE_MSG")?)
+            .ok()
+            .map(|message| PreparedMessage { operation, message }))
+    }
+
+    fn create_branch(&self, name: &str) -> Result<(), Error> {
+        self.output(&["checkout", "-b", name], Error::GitBranch)?;
+        Ok(())
+    }
+
+    fn add_note(&self, notes_ref: &str, note: &str) -> Result<(), Error> {
+        self.output(
+            &[
+                "notes",
+                &format!("--ref={notes_ref}"),
+                "add",
+                "--force",
+                "--message",
+                note,
+                "HEAD",
+            ],
+            Error::GitNotes,
+        )?;
+        Ok(())
+    }
+
+    fn apply(&self, patch: &str) -> Result<(), Error> {
+        let mut child = Command::new("git")
+            .args(["apply", "--index", "-"])
+            .stdin(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(patch.as_bytes())?;
+        }
+        if !child.wait()?.success() {
+            return Err(Error::GitApply);
+        }
+        Ok(())
+    }
+
+    fn write_index_tree(&self) -> Result<String, Error> {
+        let output = self.output(&["write-tree"], Error::GitIndex)?;
+        Ok(output.trim().to_string())
+    }
+
+    fn stage_only(&self, tree: &str, paths: &[&str]) -> Result<(), Error> {
+        let head = Command::new("git")
+            .args(["rev-parse", "--verify", "--quiet", "HEAD"])
+            .output()?;
+        // Without a commit yet, nothing is staged at all.
+        let base = if head.status.success() {
+            "HEAD"
+        } else {
+            "--empty"
+        };
+        self.output(&["read-tree", base], Error::GitIndex)?;
+        // `git reset` only accepts a tree instead of a commit together with paths.
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let pathspecs = paths
+            .iter()
+            .map(|path| format!(":(top,literal){path}"))
+            .collect::<Vec<_>>();
+        let mut arguments = vec!["reset", "--quiet", tree, "--"];
+        arguments.extend(pathspecs.iter().map(String::as_str));
+        self.output(&arguments, Error::GitIndex)?;
+        Ok(())
+    }
+
+    fn restore_index(&self, tree: &str) -> Result<(), Error> {
+        self.output(&["read-tree", tree], Error::GitIndex)?;
+        Ok(())
+    }
+
+    fn apply_to_index(&self, patch: &str) -> Result<(), Error> {
+        let mut child = Command::new("git")
+            .args(["apply", "--cached", "-"])
+            .stdin(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(patch.as_bytes())?;
+        }
+        if !child.wait()?.success() {
+            return Err(Error::GitApply);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+/// The directories of vendored code and installed packages, which aren't written in the
+/// repository
+const VENDORED_DIRECTORIES: &[&str] = &[
+    "vendor",
+    "vendors",
+    "third_party",
+    "third-party",
+    "node_modules",
+    "bower_components",
+];
+
+/// The suffixes of minified assets and sourcemaps
+const MINIFIED_SUFFIXES: &[&str] = &[".min.js", ".min.mjs", ".min.css", ".bundle.js", ".map"];
+
+/// The length from which a changed line is assumed to be written by a minifier
+const MINIFIED_LINE_LENGTH: usize = 500;
+
+/// Whether the file is inside a vendored directory, e.g. `vendor/` or `node_modules/`
+pub(crate) fn is_vendored(path: &str) -> bool {
+    path.split('/')
+        .rev()
+        .skip(1)
+        .any(|directory| VENDORED_DIRECTORIES.contains(&directory))
+}
+
+/// Whether the file is a minified asset or sourcemap, by its name or by the length of its
+/// changed lines
+pub(crate) fn is_minified(path: &str, file_diff: &str) -> bool {
+    MINIFIED_SUFFIXES
+        .iter()
+        .any(|suffix| path.ends_with(suffix))
+        || file_diff
+            .lines()
+            .skip_while(|line| !line.starts_with("@@ "))
+            .any(|line| line.starts_with(['+', '-']) && line.chars().count() > MINIFIED_LINE_LENGTH)
+}
@@ -0,0 +1,223 @@
+use std::{fs, path::Path};
+
+use config_reader::{Config, File, FileFormat};
+
+use crate::{diff::file_diffs, Cli};
+
+/// A package of a monorepo, e.g. a member of a Cargo workspace
+pub(crate) struct Package {
+    /// The name of the package without the scope of npm, e.g. `core` for `@acme/core`
+    pub(crate) name: String,
+    /// The directory of the package, relative to the root of the working tree
+    pub(crate) directory: String,
+}
+
+/// Reads the file in the format, `None` if it doesn't exist or is invalid
+fn read(path: &Path, format: FileFormat) -> Option<Config> {
+    if !path.is_file() {
+        return None;
+    }
+    Config::builder()
+        .add_source(File::from(path).format(format))
+        .build()
+        .ok()
+}
+
+/// Whether the name matches the pattern, in which `*` matches any text
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.collect::<Vec<_>>();
+    let Some(last) = parts.pop() else {
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// The directories matching the pattern of a workspace like `crates/*`, relative to the root
+fn expand(root: &Path, pattern: &str) -> Vec<String> {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    let mut directories = vec![String::new()];
+    for component in pattern.split('/') {
+        directories = directories
+            .into_iter()
+            .flat_map(|directory| {
+                let join = |name: &str| {
+                    if directory.is_empty() {
+                        name.to_string()
+                    } else {
+                        format!("{directory}/{name}")
+                    }
+                };
+                if !component.contains('*') {
+                    return vec![join(component)];
+                }
+                fs::read_dir(root.join(&directory))
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .filter(|entry| entry.path().is_dir())
+                    .filter_map(|entry| entry.file_name().into_string().ok())
+                    .filter(|name| !name.starts_with('.') && matches_wildcard(component, name))
+                    .map(|name| join(&name))
+                    .collect()
+            })
+            .collect();
+    }
+    directories
+}
+
+/// The patterns of the packages of the workspace at the root and the patterns which are
+/// excluded, from `Cargo.toml`, `pnpm-workspace.yaml` or `package.json`
+fn workspace_patterns(root: &Path) -> (Vec<String>, Vec<String>) {
+    if let Some(cargo) = read(&root.join("Cargo.toml"), FileFormat::Toml) {
+        if let Ok(members) = cargo.get::<Vec<String>>("workspace.members") {
+            let excludes = cargo
+                .get::<Vec<String>>("workspace.exclude")
+                .unwrap_or_default();
+            return (members, excludes);
+        }
+    }
+    let patterns = if let Some(pnpm) = read(&root.join("pnpm-workspace.yaml"), FileFormat::Yaml) {
+        pnpm.get::<Vec<String>>("packages").unwrap_or_default()
+    } else {
+        let package = fs::read_to_string(root.join("package.json"))
+            .ok()
+            .and_then(|package| serde_json::from_str::<serde_json::Value>(&package).ok())
+            .unwrap_or_default();
+        // The workspaces are either a list or an object with a list of packages, like of Yarn.
+        let workspaces = &package["workspaces"];
+        workspaces
+            .as_array()
+            .or_else(|| workspaces["packages"].as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|pattern| pattern.as_str().map(str::to_string))
+            .collect()
+    };
+    let (excludes, patterns) = patterns
+        .into_iter()
+        .partition::<Vec<_>, _>(|pattern| pattern.starts_with('!'));
+    let excludes = excludes
+        .into_iter()
+        .map(|pattern| pattern[1..].to_string())
+        .collect();
+    (patterns, excludes)
+}
+
+/// The name of the package in the directory from its manifest, defaults to the name of the
+/// directory. `None` if the directory has no manifest.
+fn package_name(root: &Path, directory: &str) -> Option<String> {
+    let directory_path = root.join(directory);
+    let name = if let Some(cargo) = read(&directory_path.join("Cargo.toml"), FileFormat::Toml) {
+        cargo.get::<String>("package.name").ok()
+    } else {
+        let package = fs::read_to_string(directory_path.join("package.json")).ok()?;
+        serde_json::from_str::<serde_json::Value>(&package).ok()?["name"]
+            .as_str()
+            .map(str::to_string)
+    };
+    let name = name.unwrap_or_else(|| {
+        directory
+            .rsplit('/')
+            .next()
+            .unwrap_or(directory)
+            .to_string()
+    });
+    Some(match name.split_once('/') {
+        Some((scope, name)) if scope.starts_with('@') => name.to_string(),
+        _ => name,
+    })
+}
+
+/// The package which contains the path, i.e. the one with the longest matching directory
+pub(crate) fn package_of<'a>(packages: &'a [Package], path: &str) -> Option<&'a Package> {
+    packages
+        .iter()
+        .filter(|package| {
+            path.strip_prefix(&package.directory)
+                .is_some_and(|rest| rest.starts_with('/'))
+        })
+        .max_by_key(|package| package.directory.len())
+}
+
+/// Adds the scope to the header of a conventional commit without a scope, e.g. `feat: add x`
+/// becomes `feat(cli): add x`. Other messages are kept as they are.
+pub(crate) fn inject_scope(message: &str, scope: &str) -> String {
+    let (header, body) = match message.split_once('\n') {
+        Some((header, body)) => (header, Some(body)),
+        None => (message, None),
+    };
+    let Some((kind, subject)) = header.split_once(": ") else {
+        return message.to_string();
+    };
+    let (kind, breaking) = match kind.strip_suffix('!') {
+        Some(kind) => (kind, "!"),
+        None => (kind, ""),
+    };
+    if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_alphabetic()) {
+        return message.to_string();
+    }
+    let mut scoped = format!("{kind}({scope}){breaking}: {subject}");
+    if let Some(body) = body {
+        scoped.push('\n');
+        scoped.push_str(body);
+    }
+    scoped
+}
+
+impl Cli {
+    /// The packages of the monorepo, from the members of a Cargo workspace, a pnpm workspace or
+    /// the workspaces of `package.json`. Empty if the repository isn't a monorepo.
+    pub(crate) fn packages(&self) -> Vec<Package> {
+        let Ok(Some(root)) = self.vcs.workdir() else {
+            return Vec::new();
+        };
+        let (patterns, excludes) = workspace_patterns(&root);
+        let excluded = excludes
+            .iter()
+            .flat_map(|pattern| expand(&root, pattern))
+            .collect::<Vec<_>>();
+        let mut packages = Vec::<Package>::new();
+        for directory in patterns.iter().flat_map(|pattern| expand(&root, pattern)) {
+            if excluded.contains(&directory)
+                || packages
+                    .iter()
+                    .any(|package| package.directory == directory)
+            {
+                continue;
+            }
+            if let Some(name) = package_name(&root, &directory) {
+                packages.push(Package { name, directory });
+            }
+        }
+        packages
+    }
+
+    /// The names of the packages whose files are changed by the diff, in the order of the diff.
+    /// Empty if package scopes are disabled.
+    pub(crate) fn touched_packages(&self, diff: &str) -> Vec<String> {
+        if !self.config.package_scopes {
+            return Vec::new();
+        }
+        let packages = self.packages();
+        let mut touched = Vec::new();
+        for file in file_diffs(diff) {
+            if let Some(package) = package_of(&packages, &file.path) {
+                if !touched.contains(&package.name) {
+                    touched.push(package.name.clone());
+                }
+            }
+        }
+        touched
+    }
+}
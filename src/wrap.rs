@@ -0,0 +1,158 @@
+use crate::is_trailer;
+
+/// The markers which start an item of a list, besides numbers like `1.`
+const LIST_MARKERS: &[&str] = &["- ", "* ", "+ "];
+
+/// The marker of the list item the line starts with, including its indentation and the space
+/// after it, e.g. `- ` or `1. `. `None` if the line doesn't start a list item.
+fn list_marker(line: &str) -> Option<&str> {
+    let indentation = line.len() - line.trim_start().len();
+    let rest = &line[indentation..];
+    let length = match LIST_MARKERS.iter().find(|marker| rest.starts_with(*marker)) {
+        Some(marker) => marker.len(),
+        None => {
+            let digits = rest.chars().take_while(char::is_ascii_digit).count();
+            let after = &rest[digits..];
+            if digits == 0 || !(after.starts_with(". ") || after.starts_with(") ")) {
+                return None;
+            }
+            digits + 2
+        }
+    };
+    Some(&line[..indentation + length])
+}
+
+/// Whether the line is a footer, i.e. a trailer, a `BREAKING CHANGE:` or a reference like
+/// `Closes #123`
+pub(crate) fn is_footer(line: &str) -> bool {
+    is_trailer(line)
+        || line.starts_with("BREAKING CHANGE: ")
+        || line.split_once(" #").is_some_and(|(token, _)| {
+            !token.is_empty() && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// Wraps the words of the text at the width, the first line starts with `first` and the
+/// others with `rest`. Words longer than the width, e.g. URLs, are kept on their own line.
+fn fill(text: &str, width: usize, first: &str, rest: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = first.to_string();
+    let mut empty = true;
+    for word in text.split_whitespace() {
+        if !empty && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(line);
+            line = rest.to_string();
+            empty = true;
+        }
+        if !empty {
+            line.push(' ');
+        }
+        line.push_str(word);
+        empty = false;
+    }
+    lines.push(line);
+    lines
+}
+
+/// Wraps the paragraph, which is either a list item or plain text, unless it consists of
+/// footers only. An empty paragraph has no lines.
+fn wrap_paragraph(paragraph: &[&str], width: usize) -> Vec<String> {
+    if paragraph.iter().all(|line| is_footer(line)) {
+        return paragraph.iter().map(|line| line.to_string()).collect();
+    }
+    let text = paragraph.join(" ");
+    match paragraph.first().and_then(|line| list_marker(line)) {
+        Some(marker) => fill(
+            &text[marker.len()..],
+            width,
+            marker,
+            &" ".repeat(marker.chars().count()),
+        ),
+        None => {
+            let indentation = &text[..text.len() - text.trim_start().len()];
+            fill(&text, width, indentation, indentation)
+        }
+    }
+}
+
+/// Hard-wraps the paragraphs and list items of the body at the width, like git expects it. The
+/// subject, footers, code blocks, comments and indented lines are kept as they are.
+pub(crate) fn wrap_body(message: &str, width: usize) -> String {
+    let Some((subject, body)) = message.split_once('\n') else {
+        return message.to_string();
+    };
+    let mut lines = vec![subject.to_string()];
+    let mut paragraph = Vec::<&str>::new();
+    let mut in_code_block = false;
+    for line in body.lines() {
+        let fence = line.trim_start().starts_with("```");
+        let verbatim = in_code_block
+            || fence
+            || line.trim().is_empty()
+            || line.trim_start().starts_with('#')
+            || line.starts_with('|')
+            || (line.starts_with("    ") && paragraph.is_empty());
+        if verbatim || list_marker(line).is_some() {
+            lines.extend(wrap_paragraph(&paragraph, width));
+            paragraph.clear();
+        }
+        if fence {
+            in_code_block = !in_code_block;
+        }
+        if verbatim {
+            lines.push(line.to_string());
+        } else {
+            paragraph.push(line);
+        }
+    }
+    lines.extend(wrap_paragraph(&paragraph, width));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_paragraphs_of_the_body() {
+        assert_eq!(
+            wrap_body("feat: add x\n\none two three four five", 10),
+            "feat: add x\n\none two\nthree four\nfive"
+        );
+    }
+
+    #[test]
+    fn keeps_a_long_subject() {
+        let message = "feat: add a subject which is longer than the width";
+        assert_eq!(wrap_body(message, 10), message);
+    }
+
+    #[test]
+    fn indents_the_continuation_of_list_items() {
+        assert_eq!(
+            wrap_body("fix: x\n\n- one two three\n1. four five six", 10),
+            "fix: x\n\n- one two\n  three\n1. four\n   five\n   six"
+        );
+    }
+
+    #[test]
+    fn keeps_long_words_on_their_own_line() {
+        assert_eq!(
+            wrap_body("fix: x\n\nsee https://example.com/a/long/path here", 10),
+            "fix: x\n\nsee\nhttps://example.com/a/long/path\nhere"
+        );
+    }
+
+    #[test]
+    fn keeps_code_blocks_comments_and_footers() {
+        let message = "fix: x\n\n```\nlet a = b + c + d;\n```\n# a comment which is long\n\nReviewed-by: Jane Doe <jane@example.com>\nCloses #123";
+        assert_eq!(wrap_body(message, 10), message);
+    }
+
+    #[test]
+    fn detects_footers() {
+        assert!(is_footer("Closes #123"));
+        assert!(is_footer("BREAKING CHANGE: the API changed"));
+        assert!(!is_footer("See the issue #123 for details"));
+    }
+}